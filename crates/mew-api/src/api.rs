@@ -1,16 +1,73 @@
-use mew_bundle::Bundler;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::{
+    OutputSourceMap, Reflection, Target, TargetOutput,
+    validation::{self, OutputMap},
+};
+use mew_bundle::{Bundler, LoadedModule, ModuleLoader};
 use mew_parse::{
-    span::{Span, Spanned},
+    diagnostic::Diagnostic,
+    span::{FileId, Location, SourceMap, Span, Spanned},
     syntax::{
-        Alias, FormalTemplateParameter, GlobalDeclaration, PathPart, TranslationUnit,
-        TypeExpression,
+        Alias, DeclarationKind, Expression, FormalTemplateParameter, GlobalDeclaration, Import,
+        ImportContent, LiteralExpression, Module, ModuleDirective, ModuleMemberDeclaration,
+        PathPart, TranslationUnit, TypeExpression,
     },
 };
-use mew_types::{CompilerPass, CompilerPassError, InternalCompilerError, mangling::mangle_path};
+use mew_types::{
+    CompilerPass, CompilerPassError, CompilerPassResult, Diagnostics, InternalCompilerError,
+    mangling::{ManglingStrategy, PreserveEntryPoints, ReadableMangling, mangle_path},
+};
 
-#[derive(Default, Debug)]
+#[derive(Default)]
 pub struct MewApi {
     pub translation_unit: TranslationUnit,
+    /// The sources of the modules added as text. Spans in the translation unit are
+    /// offsets in this map.
+    pub source_map: SourceMap,
+    /// Whether to check the generated WGSL with naga. Invalid WGSL is reported as an error
+    /// at the MEW source it was generated from.
+    pub validate: bool,
+    /// Whether to type check the program before generating WGSL. Type errors are reported
+    /// at the MEW source, with the declarations named by their MEW paths.
+    ///
+    /// The checker needs concrete types, so it runs after specialization: the declarations
+    /// the entry points do not use are checked, but templates are only checked in the
+    /// specializations the entry points instantiate.
+    pub type_check: bool,
+    /// Whether to build a source map from the generated WGSL to the MEW sources, returned
+    /// in [`Compilation::source_map`].
+    pub emit_source_map: bool,
+    /// Names the declarations in the generated code. If `None`, they are named with
+    /// [`ReadableMangling`], wrapped in [`PreserveEntryPoints`] so that entry points keep their
+    /// names.
+    pub mangling_strategy: Option<Arc<dyn ManglingStrategy>>,
+    /// The features the conditions of `@if` and `@elif` attributes are evaluated against.
+    /// Features that are not in the map are disabled.
+    pub features: HashMap<String, bool>,
+    /// Provides the modules that are referenced but missing, see [`Self::load_dependencies`].
+    module_provider: Option<Box<dyn ModuleLoader>>,
+    /// The files of the source map that hold each root module and its nested modules.
+    module_files: HashMap<String, Vec<FileId>>,
+}
+
+impl std::fmt::Debug for MewApi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MewApi")
+            .field("translation_unit", &self.translation_unit)
+            .field("source_map", &self.source_map)
+            .field("validate", &self.validate)
+            .field("type_check", &self.type_check)
+            .field("emit_source_map", &self.emit_source_map)
+            .field("mangling_strategy", &self.mangling_strategy)
+            .field("features", &self.features)
+            .field("module_provider", &self.module_provider.is_some())
+            .field("module_files", &self.module_files)
+            .finish()
+    }
 }
 
 pub enum Source<'a> {
@@ -30,14 +87,185 @@ pub enum MewErrorInner {
     MissingRequiredTemplateArgument(FormalTemplateParameter),
     InternalError(InternalCompilerError),
     MalformedTemplateArgument,
+    NameCollision(String),
+    MalformedExport,
+    MalformedCondition,
+    MisplacedCondition(String),
+    UnsatisfiedInterface(String, String),
+    NotAnInterface(Vec<PathPart>),
+    OverrideNotFound(String),
+    InvalidOverrideValue(String, String, String),
+    TypeError(String),
     ParseError(String),
+    LoadError(String),
+    ValidationError(String),
+    TargetError(String),
+    HostCodeError(String),
 }
 
 #[derive(Debug)]
 pub struct MewError {
     pub span: Option<Span>,
+    /// Where `span` starts, if it lies in a source known to the [`MewApi`].
+    pub location: Option<Location>,
     pub module_name: Option<String>,
     pub error: MewErrorInner,
+    pub diagnostic: Diagnostic,
+}
+
+impl MewError {
+    /// Renders the diagnostic of the error, with the sources of the source map.
+    pub fn render(&self, source_map: &SourceMap) -> String {
+        self.diagnostic.render(source_map)
+    }
+}
+
+impl std::fmt::Display for MewErrorInner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MewErrorInner::ModuleNotFound => write!(f, "module not found"),
+            MewErrorInner::SymbolNotFound(path) => write!(
+                f,
+                "symbol `{}` not found",
+                path.iter()
+                    .map(|part| part.to_string())
+                    .collect::<Vec<_>>()
+                    .join("::")
+            ),
+            MewErrorInner::MissingRequiredTemplateArgument(param) => {
+                write!(f, "missing required template argument `{}`", param.name)
+            }
+            MewErrorInner::InternalError(err) => write!(f, "internal compiler error: {err:?}"),
+            MewErrorInner::MalformedTemplateArgument => write!(f, "malformed template argument"),
+            MewErrorInner::NameCollision(name) => write!(f, "name `{name}` collides"),
+            MewErrorInner::MalformedExport => write!(f, "malformed `@export`"),
+            MewErrorInner::MalformedCondition => write!(f, "malformed condition"),
+            MewErrorInner::MisplacedCondition(name) => write!(f, "misplaced `@{name}`"),
+            MewErrorInner::UnsatisfiedInterface(argument, interface) => {
+                write!(f, "`{argument}` does not implement `{interface}`")
+            }
+            MewErrorInner::NotAnInterface(path) => write!(
+                f,
+                "`{}` is not an interface",
+                path.iter()
+                    .map(|part| part.to_string())
+                    .collect::<Vec<_>>()
+                    .join("::")
+            ),
+            MewErrorInner::OverrideNotFound(path) => {
+                write!(f, "`override` declaration `{path}` not found")
+            }
+            MewErrorInner::InvalidOverrideValue(path, value, typ) => {
+                write!(
+                    f,
+                    "invalid value `{value}` for `override` declaration `{path}` of type `{typ}`"
+                )
+            }
+            MewErrorInner::TypeError(err) => write!(f, "{err}"),
+            MewErrorInner::ParseError(err) => write!(f, "{err}"),
+            MewErrorInner::LoadError(err) => write!(f, "failed to load module: {err}"),
+            MewErrorInner::ValidationError(err) => write!(f, "invalid WGSL: {err}"),
+            MewErrorInner::TargetError(err) => write!(f, "failed to write target: {err}"),
+            MewErrorInner::HostCodeError(err) => {
+                write!(f, "failed to generate Rust host code: {err}")
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for MewError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{location}: ")?;
+        }
+        if let Some(module_name) = &self.module_name {
+            write!(f, "in module `{module_name}`: ")?;
+        }
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for MewError {}
+
+/// The result of [`MewApi::compile_with_diagnostics`].
+#[derive(Debug, Default)]
+pub struct Compilation {
+    /// The compiled WGSL, or `None` if there were errors.
+    pub output: Option<String>,
+    /// The output in naga's IR, if it was validated.
+    pub naga: Option<NagaModule>,
+    /// The source map of the output, if [`MewApi::emit_source_map`] is set.
+    pub source_map: Option<OutputSourceMap>,
+    /// The paths of the MEW declarations by their names in the output, which demangles the
+    /// names a [`ManglingStrategy`] cannot demangle alone, like hashed names.
+    pub mew_paths: HashMap<String, String>,
+    pub errors: Vec<Box<MewError>>,
+    pub warnings: Vec<Diagnostic>,
+}
+
+/// A permutation compiled by [`MewApi::compile_permutations`].
+#[derive(Debug, Clone, Default)]
+pub struct Permutation {
+    /// Features that override [`MewApi::features`].
+    pub features: HashMap<String, bool>,
+    /// Values of `override` declarations by their paths, like `module::name`, which
+    /// replace the initializers of the declarations. Values convert to the declared type, like
+    /// the pipeline constants of wgpu, and values of integer types must be whole numbers in
+    /// their range.
+    pub overrides: HashMap<String, f64>,
+}
+
+/// The result of [`MewApi::compile_permutations`].
+#[derive(Debug, Default)]
+pub struct Permutations {
+    /// The distinct compilations. Permutations that compile to the same WGSL share one.
+    pub compilations: Vec<Compilation>,
+    /// The index in `compilations` of the compilation of each permutation, in order.
+    pub indices: Vec<usize>,
+}
+
+impl Permutations {
+    /// The compilation of the permutation at `index`.
+    pub fn compilation(&self, index: usize) -> Option<&Compilation> {
+        self.indices
+            .get(index)
+            .map(|index| &self.compilations[*index])
+    }
+}
+
+/// A compiled module in naga's IR, parsed by naga from the generated WGSL, which naga's
+/// writers or wgpu can consume directly.
+#[derive(Debug, Clone)]
+pub struct NagaModule {
+    pub module: naga::Module,
+    /// The result of validating `module`, needed by naga's writers.
+    pub info: naga::valid::ModuleInfo,
+    /// The paths of the MEW declarations, e.g. `Lighting::phong`, by their names in
+    /// `module`.
+    pub mew_paths: HashMap<String, String>,
+}
+
+/// The result of running the passes: WGSL, its naga IR if it was validated, and its source
+/// map if one was requested.
+struct Output {
+    wgsl: String,
+    naga: Option<NagaModule>,
+    source_map: Option<OutputSourceMap>,
+    mew_paths: HashMap<String, String>,
+}
+
+/// The results of the passes before specialization by the features enabled when they ran:
+/// the errors they reported, and the translation unit along with the paths of the entry
+/// points. Features that are not enabled are false, so permutations that enable the same
+/// features share the results.
+type FrontEndCache =
+    HashMap<Vec<String>, (Diagnostics, Option<(TranslationUnit, Vec<Vec<PathPart>>)>)>;
+
+/// What to produce besides the WGSL.
+#[derive(Clone, Copy)]
+struct OutputOptions {
+    validate: bool,
+    source_map: bool,
 }
 
 pub enum Path {
@@ -47,44 +275,359 @@ pub enum Path {
 
 type Result<T = ()> = std::result::Result<T, Box<MewError>>;
 
+/// Collects the module paths named by an import: the path of the imported item, and of the
+/// items of nested imports.
+fn import_paths(import: &Import, mut prefix: Vec<String>, paths: &mut Vec<Vec<String>>) {
+    prefix.extend(import.path.iter().map(|part| part.name.value.clone()));
+    match import.content.as_ref() {
+        ImportContent::Item(item) => {
+            prefix.push(item.name.value.clone());
+            paths.push(prefix);
+        }
+        ImportContent::Collection(imports) => {
+            for import in imports.iter() {
+                import_paths(import, prefix.clone(), paths);
+            }
+        }
+    }
+}
+
+/// Collects the module paths named by the imports of a module and of its nested modules.
+fn module_import_paths(module: &Module, paths: &mut Vec<Vec<String>>) {
+    for directive in module.directives.iter() {
+        if let ModuleDirective::Import(import) = directive.as_ref() {
+            import_paths(import, vec![], paths);
+        }
+    }
+    for member in module.members.iter() {
+        if let ModuleMemberDeclaration::Module(m) = member.as_ref() {
+            module_import_paths(m, paths);
+        }
+    }
+}
+
+/// Collects the paths of the functions and declarations that are not templates, nor members
+/// of templates, in a module and in its nested modules.
+fn module_declaration_paths(
+    module: &Module,
+    mut prefix: Vec<PathPart>,
+    paths: &mut Vec<Vec<PathPart>>,
+) {
+    if !module.template_parameters.is_empty() {
+        return;
+    }
+    prefix.push(PathPart {
+        name: module.name.clone(),
+        template_args: None,
+        inline_template_args: None,
+    });
+    for member in module.members.iter() {
+        let name = match member.as_ref() {
+            ModuleMemberDeclaration::Function(f) if f.template_parameters.is_empty() => &f.name,
+            ModuleMemberDeclaration::Declaration(d) if d.template_parameters.is_empty() => &d.name,
+            ModuleMemberDeclaration::Module(m) => {
+                module_declaration_paths(m, prefix.clone(), paths);
+                continue;
+            }
+            _ => continue,
+        };
+        let mut path = prefix.clone();
+        path.push(PathPart {
+            name: name.clone(),
+            template_args: None,
+            inline_template_args: None,
+        });
+        paths.push(path);
+    }
+}
+
+/// Collects the paths of the functions and declarations of the translation unit that are
+/// not templates, nor members of templates.
+fn declaration_paths(translation_unit: &TranslationUnit) -> Vec<Vec<PathPart>> {
+    let mut paths = vec![];
+    for decl in translation_unit.global_declarations.iter() {
+        let name = match decl.as_ref() {
+            GlobalDeclaration::Function(f) if f.template_parameters.is_empty() => &f.name,
+            GlobalDeclaration::Declaration(d) if d.template_parameters.is_empty() => &d.name,
+            GlobalDeclaration::Module(m) => {
+                module_declaration_paths(m, vec![], &mut paths);
+                continue;
+            }
+            _ => continue,
+        };
+        paths.push(vec![PathPart {
+            name: name.clone(),
+            template_args: None,
+            inline_template_args: None,
+        }]);
+    }
+    paths
+}
+
+fn find_member_module<'a>(module: &'a mut Module, name: &str) -> Option<&'a mut Module> {
+    module
+        .members
+        .iter_mut()
+        .find_map(|member| match member.as_mut() {
+            ModuleMemberDeclaration::Module(m) if m.name.value == name => Some(m),
+            _ => None,
+        })
+}
+
+/// Sets the initializer of the `override` declaration at `path` to `value`, converted to the
+/// declared type. Fails if there is no such declaration, or if its type cannot hold the value.
+fn set_override(
+    translation_unit: &mut TranslationUnit,
+    path: &str,
+    value: f64,
+) -> CompilerPassResult {
+    let not_found = || CompilerPassError::OverrideNotFound(path.to_string()).into();
+    let parts = path.split("::").collect::<Vec<_>>();
+    let Some((name, module_path)) = parts.split_last() else {
+        return Err(not_found());
+    };
+    let declaration = match module_path.split_first() {
+        None => translation_unit
+            .global_declarations
+            .iter_mut()
+            .find_map(|decl| match decl.as_mut() {
+                GlobalDeclaration::Declaration(d) if d.name.value == *name => Some(d),
+                _ => None,
+            }),
+        Some((root, rest)) => translation_unit
+            .global_declarations
+            .iter_mut()
+            .find_map(|decl| match decl.as_mut() {
+                GlobalDeclaration::Module(m) if m.name.value == *root => Some(m),
+                _ => None,
+            })
+            .and_then(|module| {
+                rest.iter()
+                    .try_fold(module, |module, name| find_member_module(module, name))
+            })
+            .and_then(|module| {
+                module
+                    .members
+                    .iter_mut()
+                    .find_map(|member| match member.as_mut() {
+                        ModuleMemberDeclaration::Declaration(d) if d.name.value == *name => Some(d),
+                        _ => None,
+                    })
+            }),
+    };
+    let Some(declaration) = declaration.filter(|d| d.kind.value == DeclarationKind::Override)
+    else {
+        return Err(not_found());
+    };
+    let typ = declaration
+        .typ
+        .as_ref()
+        .and_then(|typ| match typ.path.as_slice() {
+            [part] if part.template_args.is_none() => Some(part.name.value.as_str()),
+            _ => None,
+        });
+    let in_range = |min: f64, max: f64| value.fract() == 0.0 && (min..=max).contains(&value);
+    let valid = value.is_finite()
+        && match typ {
+            Some("i32") => in_range(i32::MIN.into(), i32::MAX.into()),
+            Some("u32") => in_range(u32::MIN.into(), u32::MAX.into()),
+            _ => true,
+        };
+    if !valid {
+        return Err(CompilerPassError::InvalidOverrideValue(
+            path.to_string(),
+            value.to_string(),
+            declaration
+                .typ
+                .as_ref()
+                .map(|typ| typ.to_string())
+                .unwrap_or_default(),
+        )
+        .into());
+    }
+    let literal = match typ {
+        Some("bool") if value != 0.0 => LiteralExpression::True,
+        Some("bool") => LiteralExpression::False,
+        // the value is a whole number in the range of the type
+        Some("i32") => LiteralExpression::I32(value as i32),
+        Some("u32") => LiteralExpression::U32(value as u32),
+        Some("f32") => LiteralExpression::F32(value.to_string()),
+        Some("f16") => LiteralExpression::F16(value.to_string()),
+        // the abstract types convert to the declared type, if it is an alias
+        _ if value.fract() == 0.0 => LiteralExpression::AbstractInt((value as i64).to_string()),
+        _ => LiteralExpression::AbstractFloat(value.to_string()),
+    };
+    let span = declaration
+        .initializer
+        .as_ref()
+        .map(|init| init.span())
+        .unwrap_or_else(|| declaration.name.span());
+    declaration.initializer = Some(Spanned::new(
+        Expression::Literal(Spanned::new(literal, span.clone())),
+        span,
+    ));
+    Ok(())
+}
+
+/// Adds an alias for each entry path to the translation unit, and returns the paths of the
+/// aliases.
+fn add_entry_point_aliases(
+    translation_unit: &mut TranslationUnit,
+    paths: &[Vec<PathPart>],
+) -> Vec<Vec<PathPart>> {
+    let mut entrypoints = vec![];
+    for path in paths {
+        let mut alias_name_path = path.clone();
+        mangle_path(&mut alias_name_path);
+
+        let alias = Alias {
+            attributes: vec![],
+            name: Spanned::new(
+                alias_name_path
+                    .into_iter()
+                    .map(|x| x.name.value)
+                    .collect::<Vec<String>>()
+                    .join("_"),
+                0..0,
+            ),
+            typ: Spanned::new(
+                TypeExpression {
+                    path: Spanned::new(path.clone(), 0..0),
+                },
+                0..0,
+            ),
+            template_parameters: vec![],
+        };
+
+        entrypoints.push(vec![PathPart {
+            name: alias.name.clone(),
+            template_args: None,
+            inline_template_args: None,
+        }]);
+
+        translation_unit
+            .global_declarations
+            .push(Spanned::new(GlobalDeclaration::Alias(alias), 0..0));
+    }
+    entrypoints
+}
+
 impl From<Box<CompilerPassError>> for Box<MewError> {
     fn from(value: Box<CompilerPassError>) -> Self {
-        Box::new(match value.as_ref() {
-            CompilerPassError::SymbolNotFound(vec, range) => MewError {
-                span: Some(range.clone()),
-                module_name: None,
-                error: MewErrorInner::SymbolNotFound(vec.clone()),
-            },
-            CompilerPassError::UnableToResolvePath(vec) => MewError {
-                span: None,
-                module_name: None,
-                error: MewErrorInner::SymbolNotFound(vec.clone()),
-            },
-            CompilerPassError::MissingRequiredTemplateArgument(spanned, range) => MewError {
-                span: Some(range.clone()),
-                module_name: None,
-                error: MewErrorInner::MissingRequiredTemplateArgument(spanned.value.clone()),
-            },
-            CompilerPassError::InternalError(internal_compiler_error) => MewError {
-                span: None,
-                module_name: None,
-                error: MewErrorInner::InternalError(internal_compiler_error.clone()),
-            },
-            CompilerPassError::MalformedTemplateArgument(range) => MewError {
-                span: Some(range.clone()),
-                module_name: None,
-                error: MewErrorInner::MalformedTemplateArgument,
-            },
-            CompilerPassError::ParseError(parse_err, span) => MewError {
-                span: Some(span.clone()),
-                module_name: None,
-                error: MewErrorInner::ParseError(parse_err.clone()),
-            },
+        let (span, error) = match value.as_ref() {
+            CompilerPassError::SymbolNotFound(vec, range, _) => (
+                Some(range.clone()),
+                MewErrorInner::SymbolNotFound(vec.clone()),
+            ),
+            CompilerPassError::UnableToResolvePath(vec) => {
+                (None, MewErrorInner::SymbolNotFound(vec.clone()))
+            }
+            CompilerPassError::MissingRequiredTemplateArgument(spanned, range) => (
+                Some(range.clone()),
+                MewErrorInner::MissingRequiredTemplateArgument(spanned.value.clone()),
+            ),
+            CompilerPassError::InternalError(internal_compiler_error) => (
+                None,
+                MewErrorInner::InternalError(internal_compiler_error.clone()),
+            ),
+            CompilerPassError::MalformedTemplateArgument(range) => (
+                Some(range.clone()),
+                MewErrorInner::MalformedTemplateArgument,
+            ),
+            CompilerPassError::NameCollision(name, range, _) => (
+                Some(range.clone()),
+                MewErrorInner::NameCollision(name.clone()),
+            ),
+            CompilerPassError::MalformedExport(range) => {
+                (Some(range.clone()), MewErrorInner::MalformedExport)
+            }
+            CompilerPassError::MalformedCondition(range) => {
+                (Some(range.clone()), MewErrorInner::MalformedCondition)
+            }
+            CompilerPassError::MisplacedCondition(name, range) => (
+                Some(range.clone()),
+                MewErrorInner::MisplacedCondition(name.clone()),
+            ),
+            CompilerPassError::UnsatisfiedInterface(argument, range, interface, _, _) => (
+                Some(range.clone()),
+                MewErrorInner::UnsatisfiedInterface(argument.clone(), interface.clone()),
+            ),
+            CompilerPassError::NotAnInterface(path, range) => (
+                Some(range.clone()),
+                MewErrorInner::NotAnInterface(path.clone()),
+            ),
+            CompilerPassError::OverrideNotFound(path) => {
+                (None, MewErrorInner::OverrideNotFound(path.clone()))
+            }
+            CompilerPassError::InvalidOverrideValue(path, value, typ) => (
+                None,
+                MewErrorInner::InvalidOverrideValue(path.clone(), value.clone(), typ.clone()),
+            ),
+            CompilerPassError::TypeError(_, range) => (
+                Some(range.clone()),
+                MewErrorInner::TypeError(value.diagnostic().message),
+            ),
+            CompilerPassError::ParseError(diagnostic) => (
+                diagnostic.primary_span(),
+                MewErrorInner::ParseError(diagnostic.message.clone()),
+            ),
+            CompilerPassError::ValidationError(diagnostic) => (
+                diagnostic.primary_span(),
+                MewErrorInner::ValidationError(diagnostic.message.clone()),
+            ),
+        };
+        Box::new(MewError {
+            span,
+            location: None,
+            module_name: None,
+            error,
+            diagnostic: value.diagnostic(),
         })
     }
 }
 
+/// Provides the modules the resolver cannot find by loading them into the [`MewApi`].
+struct LoaderProvider<'a> {
+    api: &'a mut MewApi,
+    loader: &'a dyn ModuleLoader,
+    /// The first error raised while loading, after which no more modules are loaded.
+    error: Option<Box<MewError>>,
+}
+
+impl mew_resolve::ModuleProvider for LoaderProvider<'_> {
+    fn provide(&mut self, path: &[String]) -> Option<Spanned<Module>> {
+        if self.error.is_some() {
+            return None;
+        }
+        if let Err(err) = self.api.load_paths(self.loader, vec![path.to_vec()]) {
+            self.error = Some(err);
+            return None;
+        }
+        self.api
+            .translation_unit
+            .global_declarations
+            .iter()
+            .find_map(|decl| match decl.as_ref() {
+                GlobalDeclaration::Module(module) if module.name.value == path[0] => {
+                    Some(Spanned::new(module.clone(), decl.span()))
+                }
+                _ => None,
+            })
+    }
+}
+
 impl MewApi {
+    /// Fills in the location of an error raised while processing the translation unit.
+    fn locate(&self, mut error: Box<MewError>) -> Box<MewError> {
+        if error.location.is_none() {
+            error.location = error
+                .span
+                .as_ref()
+                .and_then(|span| self.source_map.location(span));
+        }
+        error
+    }
+
     pub fn remove_module(&mut self, module_name: &String) -> Result {
         let prev_len = self.translation_unit.global_declarations.len();
         self.translation_unit.global_declarations.retain(|x| {
@@ -92,13 +635,19 @@ impl MewApi {
                 || x.name().as_ref().map(|x| &x.value) != Some(module_name)
         });
 
-        if prev_len < self.translation_unit.global_declarations.len() {
+        if self.translation_unit.global_declarations.len() < prev_len {
+            for file in self.module_files.remove(module_name).unwrap_or_default() {
+                self.source_map.remove_file(file);
+            }
             Ok(())
         } else {
             Err(MewError {
                 span: None,
+                location: None,
                 module_name: Some(module_name.clone()),
                 error: MewErrorInner::ModuleNotFound,
+                diagnostic: Diagnostic::error(format!("cannot find module `{module_name}`"))
+                    .with_code("E0200"),
             }
             .into())
         }
@@ -116,88 +665,728 @@ impl MewApi {
                 Ok(())
             }
             Source::Text(text) => {
-                let mut bundler = Bundler {
+                let bundler = Bundler {
                     sources: vec![text],
                     enclosing_module_name: Some(module.module_name.to_string()),
+                    source_map: SourceMap::default(),
+                    source_names: vec![],
                 };
-                bundler.apply_mut(&mut self.translation_unit)?;
-                Ok(())
+                let mut translation_unit = std::mem::take(&mut self.translation_unit);
+                let result = self.bundle(module.module_name, bundler, &mut translation_unit);
+                self.translation_unit = translation_unit;
+                result
             }
         }
     }
 
+    /// Returns the module at `path`, where `path[0]` is a root module.
+    fn find_module_mut(&mut self, path: &[String]) -> Option<&mut Module> {
+        let (root, path) = path.split_first()?;
+        let mut module = self
+            .translation_unit
+            .global_declarations
+            .iter_mut()
+            .find_map(|decl| match decl.as_mut() {
+                GlobalDeclaration::Module(m) if &m.name.value == root => Some(m),
+                _ => None,
+            })?;
+        for name in path {
+            module = find_member_module(module, name)?;
+        }
+        Some(module)
+    }
+
+    /// Runs the bundler with the source map, and records the files it adds as files of the
+    /// root module `root`.
+    fn bundle(
+        &mut self,
+        root: &str,
+        mut bundler: Bundler<'_>,
+        translation_unit: &mut TranslationUnit,
+    ) -> Result {
+        let last_file = self.source_map.files().last().map(|(file, _)| file);
+        bundler.source_map = std::mem::take(&mut self.source_map);
+        let result = bundler.apply_mut(translation_unit);
+        self.source_map = bundler.source_map;
+        self.module_files
+            .entry(root.to_string())
+            .or_default()
+            .extend(
+                self.source_map
+                    .files()
+                    .map(|(file, _)| file)
+                    .filter(|file| Some(*file) > last_file),
+            );
+        result.map_err(|err| self.locate(err.into()))
+    }
+
+    /// Parses a loaded file into the module at `module_path`.
+    fn bundle_module(
+        &mut self,
+        module_path: &[String],
+        loaded: LoadedModule,
+    ) -> Result<Spanned<Module>> {
+        let mut translation_unit = TranslationUnit::default();
+        let bundler = Bundler {
+            sources: vec![&loaded.source],
+            enclosing_module_name: module_path.last().cloned(),
+            source_map: SourceMap::default(),
+            source_names: vec![loaded.file_name.clone()],
+        };
+        self.bundle(&module_path[0], bundler, &mut translation_unit)?;
+        self.translation_unit
+            .global_directives
+            .append(&mut translation_unit.global_directives);
+        match translation_unit.global_declarations.pop() {
+            Some(Spanned {
+                value: GlobalDeclaration::Module(module),
+                span,
+            }) => Ok(Spanned::new(module, span)),
+            _ => Err(Box::<MewError>::from(Box::new(
+                CompilerPassError::InternalError(InternalCompilerError::UnexpectedMember),
+            ))),
+        }
+    }
+
+    /// Loads the modules the entry point at `path` depends on from the loader, like
+    /// [`Self::load_dependencies`]: the modules named by the paths of the loaded modules,
+    /// whether qualified or imported, are loaded transitively.
+    ///
+    /// Modules already in the translation unit, including inline modules, are not loaded
+    /// again. A part of a path the loader has no source for is assumed to be a member of
+    /// the previous module.
+    pub fn load_module(&mut self, loader: &dyn ModuleLoader, path: &Path) -> Result {
+        self.load_dependencies_from(loader, std::slice::from_ref(path))
+    }
+
+    /// Loads the modules on the paths, and the modules named by their `import`s.
+    fn load_paths(
+        &mut self,
+        loader: &(impl ModuleLoader + ?Sized),
+        mut queue: Vec<Vec<String>>,
+    ) -> Result {
+        let mut visited = HashSet::new();
+        while let Some(path) = queue.pop() {
+            if visited.insert(path.clone()) {
+                queue.extend(self.load_path(loader, &path)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads the modules on `path` that are not in the translation unit yet. Returns the
+    /// paths named by the `import`s of the loaded modules.
+    ///
+    /// The loader is not asked for the parts of the path that are declared by the previous
+    /// module, as they are its members.
+    fn load_path(
+        &mut self,
+        loader: &(impl ModuleLoader + ?Sized),
+        path: &[String],
+    ) -> Result<Vec<Vec<String>>> {
+        let mut imports = vec![];
+        for len in 1..=path.len() {
+            let module_path = &path[..len];
+            if self.find_module_mut(module_path).is_some() {
+                continue;
+            }
+            let (name, parent_path) = module_path.split_last().unwrap();
+            if self.declares(parent_path, name) {
+                break;
+            }
+            let loaded = loader.load(module_path).map_err(|err| {
+                let module_name = module_path.join("::");
+                Box::new(MewError {
+                    span: None,
+                    location: None,
+                    module_name: Some(module_name.clone()),
+                    error: MewErrorInner::LoadError(err.to_string()),
+                    diagnostic: Diagnostic::error(format!("failed to load module `{module_name}`"))
+                        .with_code("E0201")
+                        .with_note(err.to_string()),
+                })
+            })?;
+            let Some(loaded) = loaded else {
+                break;
+            };
+            let module = self.bundle_module(module_path, loaded)?;
+            module_import_paths(&module, &mut imports);
+            let span = module.span();
+            match self.find_module_mut(parent_path) {
+                Some(parent) => parent.members.push(Spanned::new(
+                    ModuleMemberDeclaration::Module(module.value),
+                    span,
+                )),
+                None => self
+                    .translation_unit
+                    .global_declarations
+                    .push(Spanned::new(GlobalDeclaration::Module(module.value), span)),
+            }
+        }
+        Ok(imports)
+    }
+
+    /// Whether the module at `path`, or the translation unit if `path` is empty, declares a
+    /// member called `name`.
+    fn declares(&mut self, path: &[String], name: &str) -> bool {
+        let is_named = |decl_name: Option<Spanned<String>>| {
+            decl_name.is_some_and(|decl_name| decl_name.value == name)
+        };
+        if path.is_empty() {
+            return self
+                .translation_unit
+                .global_declarations
+                .iter()
+                .any(|decl| is_named(decl.name()));
+        }
+        self.find_module_mut(path)
+            .is_some_and(|module| module.members.iter().any(|member| is_named(member.name())))
+    }
+
+    /// Sets the provider that [`Self::load_dependencies`] loads the missing modules from.
+    ///
+    /// Compiling does not load modules, as it does not modify the translation unit: call
+    /// [`Self::load_dependencies`] with the entry points before compiling them.
+    pub fn set_module_provider(&mut self, provider: impl ModuleLoader + 'static) {
+        self.module_provider = Some(Box::new(provider));
+    }
+
+    /// Loads the modules the entry points depend on from the module provider, if any, so
+    /// that they can be compiled.
+    ///
+    /// The modules are requested while resolving the entry points: a root module is only
+    /// loaded when a path in the modules loaded so far names it and it is missing, so only
+    /// the modules the entry points depend on are loaded. Paths in declarations that
+    /// conditions could disable are followed too, so that every permutation compiles.
+    pub fn load_dependencies(&mut self, paths: &[Path]) -> Result {
+        let Some(loader) = self.module_provider.take() else {
+            return Ok(());
+        };
+        let result = self.load_dependencies_from(loader.as_ref(), paths);
+        self.module_provider = Some(loader);
+        result
+    }
+
+    fn load_dependencies_from(&mut self, loader: &dyn ModuleLoader, paths: &[Path]) -> Result {
+        let entry_paths = Self::parse_entry_paths(paths)?;
+        let mut translation_unit = self.translation_unit.clone();
+        add_entry_point_aliases(&mut translation_unit, &entry_paths);
+        let mut provider = LoaderProvider {
+            api: self,
+            loader,
+            error: None,
+        };
+        // errors are reported when compiling, except for the modules that failed to load
+        let _ = mew_resolve::Resolver.resolve_with_provider(
+            &mut translation_unit,
+            &mut Diagnostics::default(),
+            &mut provider,
+        );
+        match provider.error {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Compiles the path to WGSL.
+    ///
+    /// Only the modules in the translation unit are compiled. The modules of the module
+    /// provider must be loaded with [`Self::load_dependencies`] first.
     pub fn compile(&self, path: &Path) -> Result<String> {
+        self.compile_many(std::slice::from_ref(path))
+    }
+
+    /// Compiles several entry points into a single WGSL module, in which the declarations
+    /// they share are emitted once.
+    pub fn compile_many(&self, paths: &[Path]) -> Result<String> {
+        let mut compilation = self.compile_many_with_diagnostics(paths);
+        match compilation.output {
+            Some(output) => Ok(output),
+            // safety: compilation only fails with errors
+            None => Err(compilation.errors.swap_remove(0)),
+        }
+    }
+
+    /// Compiles the path to a validated naga module, ready for naga's SPIR-V, GLSL, HLSL
+    /// and MSL writers or for wgpu.
+    ///
+    /// The module is the one naga builds when validating the generated WGSL: MEW prints the
+    /// WGSL and naga's WGSL front end parses it, so callers do not parse the output again.
+    /// The output is validated even if [`Self::validate`] is not set, and its errors are
+    /// reported at the MEW source like those of [`Self::validate`].
+    pub fn compile_to_naga(&self, path: &Path) -> Result<NagaModule> {
+        self.compile_many_to_naga(std::slice::from_ref(path))
+    }
+
+    /// Compiles several entry points to a single naga module, like [`Self::compile_many`].
+    pub fn compile_many_to_naga(&self, paths: &[Path]) -> Result<NagaModule> {
+        let options = OutputOptions {
+            validate: true,
+            source_map: self.emit_source_map,
+        };
+        let mut compilation = self.compile_paths_with_diagnostics(paths, options);
+        match compilation.naga {
+            Some(naga) => Ok(naga),
+            // safety: compilation only fails with errors
+            None => Err(compilation.errors.swap_remove(0)),
+        }
+    }
+
+    /// Compiles the path, along with a source map from the output to the MEW sources.
+    ///
+    /// The source map is built even if [`Self::emit_source_map`] is not set.
+    pub fn compile_with_source_map(&self, path: &Path) -> Result<(String, OutputSourceMap)> {
+        self.compile_many_with_source_map(std::slice::from_ref(path))
+    }
+
+    /// Compiles several entry points like [`Self::compile_many`], along with a source map.
+    pub fn compile_many_with_source_map(
+        &self,
+        paths: &[Path],
+    ) -> Result<(String, OutputSourceMap)> {
+        let options = OutputOptions {
+            validate: self.validate,
+            source_map: true,
+        };
+        let mut compilation = self.compile_paths_with_diagnostics(paths, options);
+        match (compilation.output, compilation.source_map) {
+            (Some(output), Some(source_map)) => Ok((output, source_map)),
+            // safety: compilation only fails with errors
+            _ => Err(compilation.errors.swap_remove(0)),
+        }
+    }
+
+    /// Compiles the path to the target language.
+    pub fn compile_to_target(&self, path: &Path, target: &Target) -> Result<TargetOutput> {
+        self.compile_many_to_target(std::slice::from_ref(path), target)
+    }
+
+    /// Compiles several entry points to the target language, like [`Self::compile_many`].
+    ///
+    /// WGSL is printed by MEW like [`Self::compile_many`] does, other targets are written
+    /// by naga from the validated module.
+    pub fn compile_many_to_target(&self, paths: &[Path], target: &Target) -> Result<TargetOutput> {
+        match target {
+            Target::Wgsl => self.compile_many(paths).map(TargetOutput::Text),
+            _ => target.write(&self.compile_many_to_naga(paths)?),
+        }
+    }
+
+    /// Compiles the path, and reflects the entry points, bindings and struct layouts of the
+    /// output.
+    pub fn reflect(&self, path: &Path) -> Result<Reflection> {
+        self.reflect_many(std::slice::from_ref(path))
+    }
+
+    /// Compiles several entry points like [`Self::compile_many`], and reflects the output.
+    pub fn reflect_many(&self, paths: &[Path]) -> Result<Reflection> {
+        Ok(Reflection::new(&self.compile_many_to_naga(paths)?))
+    }
+
+    /// Compiles the path, and generates Rust mirrors of the structs of the output, along
+    /// with the indices of its resource bindings. See [`crate::host`].
+    pub fn rust_host_code(&self, path: &Path) -> Result<String> {
+        self.rust_host_code_many(std::slice::from_ref(path))
+    }
+
+    /// Compiles several entry points like [`Self::compile_many`], and generates Rust host
+    /// code for the output.
+    pub fn rust_host_code_many(&self, paths: &[Path]) -> Result<String> {
+        crate::host::rust_host_code(&self.compile_many_to_naga(paths)?)
+    }
+
+    /// Compiles the path like [`Self::compile`], but reports every error the passes
+    /// could recover from, along with the warnings.
+    pub fn compile_with_diagnostics(&self, path: &Path) -> Compilation {
+        self.compile_many_with_diagnostics(std::slice::from_ref(path))
+    }
+
+    /// Compiles the paths like [`Self::compile_many`], but reports every error the passes
+    /// could recover from, along with the warnings.
+    pub fn compile_many_with_diagnostics(&self, paths: &[Path]) -> Compilation {
+        let options = OutputOptions {
+            validate: self.validate,
+            source_map: self.emit_source_map,
+        };
+        self.compile_paths_with_diagnostics(paths, options)
+    }
+
+    /// Compiles the path once for each permutation of features and override values,
+    /// reporting the errors and warnings of each like [`Self::compile_with_diagnostics`].
+    ///
+    /// The passes up to specialization run once for each set of features the permutations
+    /// enable, so permutations that differ only in override values, or in features they
+    /// disable, share them. Permutations that compile to the same WGSL share their
+    /// compilation.
+    pub fn compile_permutations(&self, path: &Path, permutations: &[Permutation]) -> Permutations {
+        self.compile_many_permutations(std::slice::from_ref(path), permutations)
+    }
+
+    /// Compiles several entry points once for each permutation, like
+    /// [`Self::compile_permutations`].
+    pub fn compile_many_permutations(
+        &self,
+        paths: &[Path],
+        permutations: &[Permutation],
+    ) -> Permutations {
+        let options = OutputOptions {
+            validate: self.validate,
+            source_map: self.emit_source_map,
+        };
+        let mut cache = FrontEndCache::default();
+        let mut result = Permutations::default();
+        for permutation in permutations {
+            let mut features = self.features.clone();
+            features.extend(permutation.features.iter().map(|(k, v)| (k.clone(), *v)));
+            let compilation = self.compile_permutation(
+                paths,
+                &features,
+                &permutation.overrides,
+                options,
+                &mut cache,
+            );
+            let index = compilation
+                .output
+                .as_ref()
+                .and_then(|output| {
+                    result
+                        .compilations
+                        .iter()
+                        .position(|other| other.output.as_ref() == Some(output))
+                })
+                .unwrap_or_else(|| {
+                    result.compilations.push(compilation);
+                    result.compilations.len() - 1
+                });
+            result.indices.push(index);
+        }
+        result
+    }
+
+    fn compile_paths_with_diagnostics(
+        &self,
+        paths: &[Path],
+        options: OutputOptions,
+    ) -> Compilation {
+        let features = self.features.clone();
+        self.compile_permutation(
+            paths,
+            &features,
+            &HashMap::new(),
+            options,
+            &mut FrontEndCache::default(),
+        )
+    }
+
+    fn compile_permutation(
+        &self,
+        paths: &[Path],
+        features: &HashMap<String, bool>,
+        overrides: &HashMap<String, f64>,
+        options: OutputOptions,
+        cache: &mut FrontEndCache,
+    ) -> Compilation {
+        let paths = match Self::parse_entry_paths(paths) {
+            Ok(paths) => paths,
+            Err(err) => {
+                return Compilation {
+                    errors: vec![err],
+                    ..Default::default()
+                };
+            }
+        };
+        let mut diagnostics = Diagnostics::default();
+        let output = self
+            .compile_paths(
+                &paths,
+                features,
+                overrides,
+                options,
+                cache,
+                &mut diagnostics,
+            )
+            .unwrap_or_else(|err| {
+                diagnostics.error(*err);
+                None
+            });
+        Compilation {
+            output: output.as_ref().map(|output| output.wgsl.clone()),
+            source_map: output.as_ref().and_then(|output| output.source_map.clone()),
+            mew_paths: output
+                .as_ref()
+                .map(|output| output.mew_paths.clone())
+                .unwrap_or_default(),
+            naga: output.and_then(|output| output.naga),
+            errors: diagnostics
+                .errors
+                .into_iter()
+                .map(|err| self.locate(Box::new(err).into()))
+                .collect(),
+            warnings: diagnostics.warnings,
+        }
+    }
+
+    /// Parses the entry paths, without duplicates.
+    fn parse_entry_paths(paths: &[Path]) -> Result<Vec<Vec<PathPart>>> {
+        let mut entry_paths: Vec<Vec<PathPart>> = vec![];
+        for path in paths {
+            let path = Self::parse_entry_path(path)?;
+            if !entry_paths.contains(&path) {
+                entry_paths.push(path);
+            }
+        }
+        Ok(entry_paths)
+    }
+
+    fn parse_entry_path(path: &Path) -> Result<Vec<PathPart>> {
         let path = match path {
             Path::Parsed(path) => path.clone(),
             Path::Text(path) => {
                 mew_parse::Parser::parse_path(path)
-                    .map_err(|err| -> Box<CompilerPassError> {
-                        CompilerPassError::ParseError(format!("{}", err), err.span()).into()
+                    .map_err(|err| {
+                        // the spans of the error are offsets in the path, not in the source map
+                        let mut diagnostic = err.diagnostic();
+                        diagnostic.labels.clear();
+                        Box::new(MewError {
+                            span: None,
+                            location: None,
+                            module_name: None,
+                            error: MewErrorInner::ParseError(diagnostic.message.clone()),
+                            diagnostic: diagnostic
+                                .with_note(format!("while parsing the path `{path}`")),
+                        })
                     })?
                     .path
                     .value
             }
         };
+        Ok(path)
+    }
 
-        let mut alias_name_path = path.clone();
-        mangle_path(&mut alias_name_path);
+    /// Runs the passes, stopping after the first one that reports errors. The passes before
+    /// specialization are skipped if the cache has their results for the features enabled,
+    /// and the override values are set after them.
+    fn compile_paths(
+        &self,
+        paths: &[Vec<PathPart>],
+        features: &HashMap<String, bool>,
+        overrides: &HashMap<String, f64>,
+        options: OutputOptions,
+        cache: &mut FrontEndCache,
+        diagnostics: &mut Diagnostics,
+    ) -> CompilerPassResult<Option<Output>> {
+        let mut enabled = features
+            .iter()
+            .filter(|(_, enabled)| **enabled)
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        enabled.sort();
+        let (front_end_diagnostics, front_end) = match cache.get(&enabled) {
+            Some(cached) => cached.clone(),
+            None => {
+                let mut front_end_diagnostics = Diagnostics::default();
+                let front_end =
+                    self.compile_front_end(features, paths, &mut front_end_diagnostics)?;
+                cache.insert(enabled, (front_end_diagnostics.clone(), front_end.clone()));
+                (front_end_diagnostics, front_end)
+            }
+        };
+        diagnostics.errors.extend(front_end_diagnostics.errors);
+        diagnostics.warnings.extend(front_end_diagnostics.warnings);
+        let Some((mut result, entrypoints)) = front_end else {
+            return Ok(None);
+        };
+        let mut names = overrides.keys().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            if let Err(err) = set_override(&mut result, name, overrides[name]) {
+                diagnostics.error(*err);
+            }
+        }
+        if diagnostics.has_errors() {
+            return Ok(None);
+        }
+        self.compile_back_end(result, entrypoints, options, diagnostics)
+    }
 
-        let mut resolver = mew_resolve::Resolver;
+    /// Evaluates the conditions, adds aliases for the entry points, and runs the passes
+    /// before specialization.
+    fn compile_front_end(
+        &self,
+        features: &HashMap<String, bool>,
+        paths: &[Vec<PathPart>],
+        diagnostics: &mut Diagnostics,
+    ) -> CompilerPassResult<Option<(TranslationUnit, Vec<Vec<PathPart>>)>> {
         let mut result = self.translation_unit.clone();
 
-        let alias = Alias {
-            name: Spanned::new(
-                alias_name_path
-                    .into_iter()
-                    .map(|x| x.name.value)
-                    .collect::<Vec<String>>()
-                    .join("_"),
-                0..0,
-            ),
-            typ: Spanned::new(
-                TypeExpression {
-                    path: Spanned::new(path, 0..0),
-                },
-                0..0,
-            ),
-            template_parameters: vec![],
+        let mut translator = mew_conditional::ConditionalTranslator {
+            features: features.clone(),
         };
+        translator.apply_mut_with_diagnostics(&mut result, diagnostics)?;
+        if diagnostics.has_errors() {
+            return Ok(None);
+        }
 
-        let entry_path = vec![PathPart {
-            name: alias.name.clone(),
-            template_args: None,
-            inline_template_args: None,
-        }];
-
-        result
-            .global_declarations
-            .push(Spanned::new(GlobalDeclaration::Alias(alias), 0..0));
+        let entrypoints = add_entry_point_aliases(&mut result, paths);
 
-        resolver.apply_mut(&mut result)?;
+        let mut resolver = mew_resolve::Resolver;
+        resolver.apply_mut_with_diagnostics(&mut result, diagnostics)?;
+        if diagnostics.has_errors() {
+            return Ok(None);
+        }
 
         let mut inliner = mew_inline::Inliner;
-        inliner.apply_mut(&mut result)?;
+        inliner.apply_mut_with_diagnostics(&mut result, diagnostics)?;
+        if diagnostics.has_errors() {
+            return Ok(None);
+        }
 
         let mut normalizer = mew_template_normalize::TemplateNormalizer;
-        normalizer.apply_mut(&mut result)?;
+        normalizer.apply_mut_with_diagnostics(&mut result, diagnostics)?;
+        if diagnostics.has_errors() {
+            return Ok(None);
+        }
+
+        Ok(Some((result, entrypoints)))
+    }
 
-        let mut specializer = mew_specialize::Specializer {
-            entrypoint: Some(entry_path),
+    fn mangler(&self) -> mew_mangle::Mangler {
+        mew_mangle::Mangler::new(
+            self.mangling_strategy
+                .clone()
+                .unwrap_or_else(|| Arc::new(PreserveEntryPoints(ReadableMangling))),
+        )
+    }
+
+    /// Prepares a copy of the normalized translation unit for the type checker, in which
+    /// the declarations the entry points do not use are specialized too, with the MEW paths
+    /// of its declarations by their mangled names.
+    ///
+    /// Returns `None` if the copy cannot be compiled, as when the names of unused
+    /// declarations collide, in which case only the used declarations are checked.
+    fn type_check_copy(
+        &self,
+        translation_unit: &TranslationUnit,
+        entrypoints: &[Vec<PathPart>],
+    ) -> Option<(TranslationUnit, HashMap<String, String>)> {
+        let mut translation_unit = translation_unit.clone();
+        let mut paths = entrypoints.to_vec();
+        paths.extend(declaration_paths(&translation_unit));
+        // the errors of the used declarations are reported when compiling them
+        let mut diagnostics = Diagnostics::default();
+        mew_specialize::Specializer::new(paths)
+            .apply_mut_with_diagnostics(&mut translation_unit, &mut diagnostics)
+            .ok()?;
+        mew_dealias::Dealiaser
+            .apply_mut_with_diagnostics(&mut translation_unit, &mut diagnostics)
+            .ok()?;
+        let mut mangler = self.mangler();
+        let names = mangler
+            .mangled_names(&translation_unit)
+            .into_iter()
+            .map(|(name, path)| (name, path.join("::")))
+            .collect();
+        mangler
+            .apply_mut_with_diagnostics(&mut translation_unit, &mut diagnostics)
+            .ok()?;
+        mew_flatten::Flattener
+            .apply_mut_with_diagnostics(&mut translation_unit, &mut diagnostics)
+            .ok()?;
+        (!diagnostics.has_errors()).then_some((translation_unit, names))
+    }
+
+    /// Runs the passes from specialization on, and prints and validates the output.
+    fn compile_back_end(
+        &self,
+        mut result: TranslationUnit,
+        entrypoints: Vec<Vec<PathPart>>,
+        options: OutputOptions,
+        diagnostics: &mut Diagnostics,
+    ) -> CompilerPassResult<Option<Output>> {
+        // the declarations the entry points do not use are type checked too
+        let checked = if self.type_check {
+            self.type_check_copy(&result, &entrypoints)
+        } else {
+            None
         };
 
-        specializer.apply_mut(&mut result)?;
+        let mut specializer = mew_specialize::Specializer::new(entrypoints.clone());
 
+        specializer.apply_mut_with_diagnostics(&mut result, diagnostics)?;
+        if diagnostics.has_errors() {
+            return Ok(None);
+        }
+
+        // the aliases of the entry points are removed by dealiasing
+        let entrypoints = mew_dealias::Dealiaser::dealias_paths(&result, &entrypoints)?;
         let mut dealiaser = mew_dealias::Dealiaser;
 
-        dealiaser.apply_mut(&mut result)?;
+        dealiaser.apply_mut_with_diagnostics(&mut result, diagnostics)?;
+        if diagnostics.has_errors() {
+            return Ok(None);
+        }
+
+        let mut eliminator = mew_dce::DeadCodeEliminator { entrypoints };
+        eliminator.apply_mut_with_diagnostics(&mut result, diagnostics)?;
+        if diagnostics.has_errors() {
+            return Ok(None);
+        }
 
-        let mut mangler = mew_mangle::Mangler;
+        let mut mangler = self.mangler();
+        let mew_paths: HashMap<String, String> = mangler
+            .mangled_names(&result)
+            .into_iter()
+            .map(|(name, path)| (name, path.join("::")))
+            .collect();
 
-        mangler.apply_mut(&mut result)?;
+        mangler.apply_mut_with_diagnostics(&mut result, diagnostics)?;
+        if diagnostics.has_errors() {
+            return Ok(None);
+        }
 
         let mut flattener = mew_flatten::Flattener;
-        flattener.apply_mut(&mut result)?;
+        flattener.apply_mut_with_diagnostics(&mut result, diagnostics)?;
+        if diagnostics.has_errors() {
+            return Ok(None);
+        }
 
-        Ok(format!("{result}"))
+        if self.type_check {
+            let (mut checked, names) =
+                checked.unwrap_or_else(|| (result.clone(), mew_paths.clone()));
+            let mut type_checker = mew_typecheck::TypeChecker { names };
+            type_checker.apply_mut_with_diagnostics(&mut checked, diagnostics)?;
+            if diagnostics.has_errors() {
+                return Ok(None);
+            }
+        }
+
+        let (wgsl, output_map) = OutputMap::print(&result);
+        let source_map = options
+            .source_map
+            .then(|| OutputSourceMap::new(&wgsl, &output_map, &self.source_map, &mew_paths));
+        if !options.validate {
+            return Ok(Some(Output {
+                wgsl,
+                naga: None,
+                source_map,
+                mew_paths,
+            }));
+        }
+        match validation::validate(&wgsl, &output_map) {
+            Ok((module, info)) => Ok(Some(Output {
+                wgsl,
+                naga: Some(NagaModule {
+                    module,
+                    info,
+                    mew_paths: mew_paths.clone(),
+                }),
+                source_map,
+                mew_paths,
+            })),
+            Err(diagnostic) => {
+                diagnostics.error(CompilerPassError::ValidationError(*diagnostic));
+                Ok(None)
+            }
+        }
     }
 }
@@ -0,0 +1,303 @@
+//! Generation of Rust host code from a compiled module: `#[repr(C)]` mirrors of its structs,
+//! laid out like WGSL lays them out in uniform and storage buffers, and the group and binding
+//! indices of its resources.
+//!
+//! The generated code is nested in modules following the MEW module paths, with the names of
+//! MEW modules in snake case, so `Camera::Uniforms` becomes `camera::Uniforms`. Structs derive
+//! `bytemuck::Pod`, with explicit padding fields. Padding of any length requires the
+//! `min_const_generics` feature of bytemuck.
+//!
+//! Rust has no stable `f16` type, so structs with `f16` members are rejected. A struct ending
+//! with a runtime-sized array only mirrors the members before the array.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use naga::{ArraySize, Handle, Module, ScalarKind, Type, TypeInner, VectorSize, proc::Layouter};
+
+use mew_parse::diagnostic::Diagnostic;
+
+use crate::{MewError, MewErrorInner, NagaModule, Reflection};
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "static", "struct", "super", "trait", "true", "type", "unsafe",
+    "use", "where", "while", "yield",
+];
+
+/// The items of a generated Rust module.
+#[derive(Default)]
+struct RustModule {
+    items: Vec<String>,
+    modules: BTreeMap<String, RustModule>,
+}
+
+impl RustModule {
+    fn module_mut(&mut self, path: &[String]) -> &mut RustModule {
+        path.iter().fold(self, |module, name| {
+            module.modules.entry(snake_case(name)).or_default()
+        })
+    }
+
+    fn write(&self, output: &mut String, depth: usize) {
+        let indent = "    ".repeat(depth);
+        let mut first = true;
+        for item in self.items.iter() {
+            if !first {
+                output.push('\n');
+            }
+            first = false;
+            for line in item.lines() {
+                if line.is_empty() {
+                    output.push('\n');
+                } else {
+                    let _ = writeln!(output, "{indent}{line}");
+                }
+            }
+        }
+        for (name, module) in self.modules.iter() {
+            if !first {
+                output.push('\n');
+            }
+            first = false;
+            let _ = writeln!(output, "{indent}pub mod {name} {{");
+            module.write(output, depth + 1);
+            let _ = writeln!(output, "{indent}}}");
+        }
+    }
+}
+
+/// Splits a MEW path like `scene::Camera::Uniforms` into its module path and its name.
+fn split_path(mew_path: Option<&str>, fallback: &str) -> (Vec<String>, String) {
+    match mew_path {
+        Some(path) => {
+            let mut parts = path.split("::").map(str::to_string).collect::<Vec<_>>();
+            let name = parts.pop().unwrap_or_default();
+            (parts, name)
+        }
+        None => (vec![], fallback.to_string()),
+    }
+}
+
+/// Converts a name to snake case, e.g. `SumBinaryOp` to `sum_binary_op`.
+fn snake_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut prev: Option<char> = None;
+    for c in name.chars() {
+        if c.is_uppercase() {
+            if prev.is_some_and(|prev| prev.is_lowercase() || prev.is_ascii_digit()) {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+        prev = Some(c);
+    }
+    result
+}
+
+fn field_name(name: &str) -> String {
+    if RUST_KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+/// The Rust type of a scalar, or `None` for `f16`, which has no stable Rust type.
+fn scalar_type(scalar: naga::Scalar) -> Option<&'static str> {
+    Some(match (scalar.kind, scalar.width) {
+        (ScalarKind::Float, 2) => return None,
+        (ScalarKind::Float, 8) => "f64",
+        (ScalarKind::Float, _) => "f32",
+        (ScalarKind::Sint, 8) => "i64",
+        (ScalarKind::Sint, _) => "i32",
+        (ScalarKind::Uint, 8) => "u64",
+        (ScalarKind::Uint, _) => "u32",
+        // booleans are not host-shareable, but have the size of a `u32` in naga's layouts
+        _ => "u32",
+    })
+}
+
+fn unsupported_f16(name: &str, member: &str) -> Box<MewError> {
+    let message = format!("`{name}::{member}` is or contains an `f16`, which has no Rust type");
+    Box::new(MewError {
+        span: None,
+        location: None,
+        module_name: None,
+        error: MewErrorInner::HostCodeError(message.clone()),
+        diagnostic: Diagnostic::error("failed to generate Rust host code")
+            .with_code("E0303")
+            .with_note(message),
+    })
+}
+
+/// Writes Rust host code for the structs and resource bindings of a compiled module.
+struct HostWriter<'a> {
+    module: &'a Module,
+    layouter: Layouter,
+    /// The module path and name of the Rust struct generated for each WGSL struct.
+    struct_paths: BTreeMap<Handle<Type>, (Vec<String>, String)>,
+}
+
+impl HostWriter<'_> {
+    /// The Rust type of a WGSL type, as seen from a module at `depth`, and its size, or
+    /// `None` if the type contains an `f16`.
+    fn rust_type(&self, ty: Handle<Type>, depth: usize) -> Option<(String, u32)> {
+        let size = self.layouter[ty].size;
+        let rust_type = match &self.module.types[ty].inner {
+            TypeInner::Scalar(scalar) | TypeInner::Atomic(scalar) => {
+                scalar_type(*scalar)?.to_string()
+            }
+            TypeInner::Vector { size, scalar } => {
+                format!("[{}; {}]", scalar_type(*scalar)?, *size as u8)
+            }
+            // the columns of a matrix are aligned like vectors, so 3 rows take the room of 4
+            TypeInner::Matrix {
+                columns,
+                rows,
+                scalar,
+            } => {
+                let rows = match rows {
+                    VectorSize::Tri => 4,
+                    rows => *rows as u8,
+                };
+                format!("[[{}; {rows}]; {}]", scalar_type(*scalar)?, *columns as u8)
+            }
+            TypeInner::Array {
+                base,
+                size: ArraySize::Constant(count),
+                stride,
+            } => {
+                let (base, base_size) = self.rust_type(*base, depth)?;
+                if base_size == *stride {
+                    format!("[{base}; {count}]")
+                } else {
+                    // elements padded to their stride, like `vec3f` in an array
+                    format!("[[u8; {stride}]; {count}]")
+                }
+            }
+            TypeInner::Struct { .. } => match self.struct_paths.get(&ty) {
+                Some((path, name)) => {
+                    let mut rust_path = "super::".repeat(depth);
+                    for module in path.iter() {
+                        rust_path.push_str(&snake_case(module));
+                        rust_path.push_str("::");
+                    }
+                    rust_path.push_str(name);
+                    rust_path
+                }
+                None => format!("[u8; {size}]"),
+            },
+            _ => format!("[u8; {size}]"),
+        };
+        Some((rust_type, size))
+    }
+
+    fn write_struct(
+        &self,
+        ty: Handle<Type>,
+        depth: usize,
+        name: &str,
+    ) -> Result<Option<String>, Box<MewError>> {
+        let TypeInner::Struct { members, span } = &self.module.types[ty].inner else {
+            return Ok(None);
+        };
+        let mut size = *span;
+        let mut fields = String::new();
+        let mut offset = 0;
+        let mut padding = 0;
+        let mut pad = |fields: &mut String, len: u32| {
+            let _ = writeln!(fields, "    pub _pad{padding}: [u8; {len}],");
+            padding += 1;
+        };
+        for member in members.iter() {
+            if member.offset > offset {
+                pad(&mut fields, member.offset - offset);
+            }
+            let member_name = field_name(member.name.as_deref().unwrap_or_default());
+            let unsupported = || unsupported_f16(name, &member_name);
+            // a runtime-sized array is always the last member, and its elements follow the
+            // struct rather than being part of it
+            if let TypeInner::Array {
+                base,
+                size: ArraySize::Dynamic,
+                ..
+            } = &self.module.types[member.ty].inner
+            {
+                let (base, _) = self.rust_type(*base, depth).ok_or_else(unsupported)?;
+                let _ = writeln!(
+                    fields,
+                    "    // `{member_name}` is a runtime-sized array of `{base}` that follows"
+                );
+                offset = member.offset;
+                size = member.offset;
+                break;
+            }
+            let (rust_type, member_size) =
+                self.rust_type(member.ty, depth).ok_or_else(unsupported)?;
+            let _ = writeln!(fields, "    pub {member_name}: {rust_type},");
+            offset = member.offset + member_size;
+        }
+        if size > offset {
+            pad(&mut fields, size - offset);
+        }
+        Ok(Some(format!(
+            "#[repr(C)]\n\
+             #[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]\n\
+             pub struct {name} {{\n\
+             {fields}}}\n\
+             \n\
+             const _: () = assert!(std::mem::size_of::<{name}>() == {size});\n"
+        )))
+    }
+}
+
+/// Generates Rust host code for the structs and the resource bindings of the module.
+///
+/// Struct members get Rust types with the size of their WGSL types, so `vec3f` is `[f32; 3]`,
+/// and the space WGSL leaves between members is filled with `_padN` fields.
+///
+/// Fails if a struct has a member that contains an `f16`.
+pub fn rust_host_code(naga: &NagaModule) -> Result<String, Box<MewError>> {
+    let module = &naga.module;
+    let mut layouter = Layouter::default();
+    // the module is validated, so every type has a layout
+    let _ = layouter.update(module.to_ctx());
+
+    let struct_paths = module
+        .types
+        .iter()
+        .filter(|(_, ty)| matches!(ty.inner, TypeInner::Struct { .. }))
+        .map(|(handle, ty)| {
+            let name = ty.name.as_deref().unwrap_or_default();
+            let path = split_path(naga.mew_paths.get(name).map(String::as_str), name);
+            (handle, path)
+        })
+        .collect::<BTreeMap<_, _>>();
+    let writer = HostWriter {
+        module,
+        layouter,
+        struct_paths,
+    };
+
+    let mut root = RustModule::default();
+    for (handle, (path, name)) in writer.struct_paths.iter() {
+        if let Some(item) = writer.write_struct(*handle, path.len(), name)? {
+            root.module_mut(path).items.push(item);
+        }
+    }
+    for binding in Reflection::new(naga).bindings.iter() {
+        let (path, name) = split_path(binding.name.mew_path.as_deref(), &binding.name.name);
+        let name = snake_case(&name).to_uppercase();
+        root.module_mut(&path).items.push(format!(
+            "pub const {name}_GROUP: u32 = {};\npub const {name}_BINDING: u32 = {};\n",
+            binding.group, binding.binding
+        ));
+    }
+
+    let mut output = String::from("// Generated by MEW, do not edit.\n\n");
+    root.write(&mut output, 0);
+    Ok(output)
+}
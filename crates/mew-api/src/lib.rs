@@ -1,2 +1,11 @@
 pub mod api;
+pub mod host;
+pub mod reflect;
+pub mod source_map;
+pub mod target;
+mod validation;
 pub use api::*;
+pub use naga;
+pub use reflect::*;
+pub use source_map::*;
+pub use target::*;
@@ -0,0 +1,329 @@
+//! Reflection of a compiled module: its entry points, resource bindings, vertex inputs and
+//! struct layouts, so host code does not need to hard-code them.
+//!
+//! Every reflected item has the name it has in the generated code, and the path of the MEW
+//! declaration it was generated from. The lookup methods of [`Reflection`] accept either.
+
+use naga::{
+    AddressSpace, Binding, ImageClass, Module, ScalarKind, StorageAccess, TypeInner, VectorSize,
+    proc::Layouter,
+};
+
+use crate::NagaModule;
+
+/// The name of a reflected item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReflectedName {
+    /// The name in the generated code.
+    pub name: String,
+    /// The path of the MEW declaration, e.g. `Lighting::phong`.
+    pub mew_path: Option<String>,
+}
+
+impl ReflectedName {
+    fn new(naga: &NagaModule, name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            mew_path: naga.mew_paths.get(name).cloned(),
+        }
+    }
+
+    /// Whether `name` is the generated name or the MEW path of the item.
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name || self.mew_path.as_deref() == Some(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryPointReflection {
+    pub name: ReflectedName,
+    pub stage: naga::ShaderStage,
+    /// The workgroup size of compute shaders, `[0, 0, 0]` for other stages.
+    pub workgroup_size: [u32; 3],
+    /// The inputs with a `@location`, which are the vertex attributes of vertex shaders.
+    pub inputs: Vec<LocationReflection>,
+}
+
+/// A value passed between stages with a `@location`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocationReflection {
+    /// The name of the parameter, or of the struct member.
+    pub name: String,
+    pub location: u32,
+    pub ty: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl From<StorageAccess> for Access {
+    fn from(access: StorageAccess) -> Self {
+        match (
+            access.contains(StorageAccess::LOAD),
+            access.contains(StorageAccess::STORE),
+        ) {
+            (_, false) => Access::Read,
+            (false, true) => Access::Write,
+            (true, true) => Access::ReadWrite,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    UniformBuffer,
+    StorageBuffer { access: Access },
+    Texture,
+    StorageTexture { access: Access },
+    Sampler { comparison: bool },
+    Other,
+}
+
+/// A resource bound with `@group` and `@binding`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingReflection {
+    pub name: ReflectedName,
+    pub group: u32,
+    pub binding: u32,
+    pub kind: ResourceKind,
+    pub ty: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberLayout {
+    pub name: String,
+    pub ty: String,
+    pub offset: u32,
+    pub size: u32,
+    pub alignment: u32,
+}
+
+/// The layout of a struct in host-shareable memory, like uniform and storage buffers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLayout {
+    pub name: ReflectedName,
+    pub size: u32,
+    pub alignment: u32,
+    pub members: Vec<MemberLayout>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reflection {
+    pub entry_points: Vec<EntryPointReflection>,
+    pub bindings: Vec<BindingReflection>,
+    pub structs: Vec<StructLayout>,
+}
+
+impl Reflection {
+    pub fn new(naga: &NagaModule) -> Self {
+        let module = &naga.module;
+        let mut layouter = Layouter::default();
+        // the module is validated, so every type has a layout
+        let _ = layouter.update(module.to_ctx());
+
+        let entry_points = module
+            .entry_points
+            .iter()
+            .map(|entry_point| {
+                let mut inputs = vec![];
+                for argument in entry_point.function.arguments.iter() {
+                    let name = argument.name.clone().unwrap_or_default();
+                    location_inputs(
+                        module,
+                        name,
+                        argument.ty,
+                        argument.binding.as_ref(),
+                        &mut inputs,
+                    );
+                }
+                EntryPointReflection {
+                    name: ReflectedName::new(naga, &entry_point.name),
+                    stage: entry_point.stage,
+                    workgroup_size: entry_point.workgroup_size,
+                    inputs,
+                }
+            })
+            .collect();
+
+        let bindings = module
+            .global_variables
+            .iter()
+            .filter_map(|(_, var)| {
+                let binding = var.binding.as_ref()?;
+                let kind = match (var.space, &module.types[var.ty].inner) {
+                    (AddressSpace::Uniform, _) => ResourceKind::UniformBuffer,
+                    (AddressSpace::Storage { access }, _) => ResourceKind::StorageBuffer {
+                        access: access.into(),
+                    },
+                    (_, TypeInner::Image { class, .. }) => match class {
+                        ImageClass::Storage { access, .. } => ResourceKind::StorageTexture {
+                            access: (*access).into(),
+                        },
+                        _ => ResourceKind::Texture,
+                    },
+                    (_, TypeInner::Sampler { comparison }) => ResourceKind::Sampler {
+                        comparison: *comparison,
+                    },
+                    _ => ResourceKind::Other,
+                };
+                Some(BindingReflection {
+                    name: ReflectedName::new(naga, var.name.as_deref().unwrap_or_default()),
+                    group: binding.group,
+                    binding: binding.binding,
+                    kind,
+                    ty: type_name(module, var.ty),
+                })
+            })
+            .collect();
+
+        let structs = module
+            .types
+            .iter()
+            .filter_map(|(handle, ty)| {
+                let TypeInner::Struct { members, span } = &ty.inner else {
+                    return None;
+                };
+                Some(StructLayout {
+                    name: ReflectedName::new(naga, ty.name.as_deref().unwrap_or_default()),
+                    size: *span,
+                    alignment: layouter[handle].alignment.round_up(1),
+                    members: members
+                        .iter()
+                        .map(|member| MemberLayout {
+                            name: member.name.clone().unwrap_or_default(),
+                            ty: type_name(module, member.ty),
+                            offset: member.offset,
+                            size: layouter[member.ty].size,
+                            alignment: layouter[member.ty].alignment.round_up(1),
+                        })
+                        .collect(),
+                })
+            })
+            .collect();
+
+        Self {
+            entry_points,
+            bindings,
+            structs,
+        }
+    }
+
+    /// Finds an entry point by its generated name or by its MEW path.
+    pub fn entry_point(&self, name: &str) -> Option<&EntryPointReflection> {
+        self.entry_points
+            .iter()
+            .find(|item| item.name.matches(name))
+    }
+
+    /// Finds a binding by its generated name or by its MEW path.
+    pub fn binding(&self, name: &str) -> Option<&BindingReflection> {
+        self.bindings.iter().find(|item| item.name.matches(name))
+    }
+
+    /// Finds the layout of a struct by its generated name or by its MEW path.
+    pub fn struct_layout(&self, name: &str) -> Option<&StructLayout> {
+        self.structs.iter().find(|item| item.name.matches(name))
+    }
+}
+
+/// Collects the `@location` inputs of an entry point argument, which are either the argument
+/// itself or the members of its struct type.
+fn location_inputs(
+    module: &Module,
+    name: String,
+    ty: naga::Handle<naga::Type>,
+    binding: Option<&Binding>,
+    inputs: &mut Vec<LocationReflection>,
+) {
+    match (binding, &module.types[ty].inner) {
+        (Some(Binding::Location { location, .. }), _) => inputs.push(LocationReflection {
+            name,
+            location: *location,
+            ty: type_name(module, ty),
+        }),
+        (None, TypeInner::Struct { members, .. }) => {
+            for member in members.iter() {
+                location_inputs(
+                    module,
+                    member.name.clone().unwrap_or_default(),
+                    member.ty,
+                    member.binding.as_ref(),
+                    inputs,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+fn scalar_name(scalar: naga::Scalar) -> String {
+    match scalar.kind {
+        ScalarKind::Bool => "bool".to_string(),
+        ScalarKind::Sint => format!("i{}", scalar.width * 8),
+        ScalarKind::Uint => format!("u{}", scalar.width * 8),
+        ScalarKind::Float => format!("f{}", scalar.width * 8),
+        ScalarKind::AbstractInt => "AbstractInt".to_string(),
+        ScalarKind::AbstractFloat => "AbstractFloat".to_string(),
+    }
+}
+
+fn vector_size(size: VectorSize) -> u8 {
+    size as u8
+}
+
+/// The WGSL spelling of a type.
+fn type_name(module: &Module, ty: naga::Handle<naga::Type>) -> String {
+    let ty = &module.types[ty];
+    if let Some(name) = ty.name.as_ref() {
+        return name.clone();
+    }
+    match &ty.inner {
+        TypeInner::Scalar(scalar) => scalar_name(*scalar),
+        TypeInner::Vector { size, scalar } => {
+            format!("vec{}<{}>", vector_size(*size), scalar_name(*scalar))
+        }
+        TypeInner::Matrix {
+            columns,
+            rows,
+            scalar,
+        } => format!(
+            "mat{}x{}<{}>",
+            vector_size(*columns),
+            vector_size(*rows),
+            scalar_name(*scalar)
+        ),
+        TypeInner::Atomic(scalar) => format!("atomic<{}>", scalar_name(*scalar)),
+        TypeInner::Array { base, size, .. } | TypeInner::BindingArray { base, size } => {
+            let prefix = match &ty.inner {
+                TypeInner::BindingArray { .. } => "binding_array",
+                _ => "array",
+            };
+            match size {
+                naga::ArraySize::Constant(size) => {
+                    format!("{prefix}<{}, {size}>", type_name(module, *base))
+                }
+                naga::ArraySize::Dynamic => format!("{prefix}<{}>", type_name(module, *base)),
+            }
+        }
+        TypeInner::Sampler { comparison: true } => "sampler_comparison".to_string(),
+        TypeInner::Sampler { comparison: false } => "sampler".to_string(),
+        TypeInner::Image { dim, class, .. } => {
+            let dim = match dim {
+                naga::ImageDimension::D1 => "1d",
+                naga::ImageDimension::D2 => "2d",
+                naga::ImageDimension::D3 => "3d",
+                naga::ImageDimension::Cube => "cube",
+            };
+            match class {
+                ImageClass::Sampled { .. } => format!("texture_{dim}"),
+                ImageClass::Depth { .. } => format!("texture_depth_{dim}"),
+                ImageClass::Storage { .. } => format!("texture_storage_{dim}"),
+            }
+        }
+        inner => format!("{inner:?}"),
+    }
+}
@@ -0,0 +1,326 @@
+//! Source maps from the generated WGSL back to the MEW sources, in the
+//! [v3 format](https://tc39.es/source-map/) consumed by browsers and debuggers.
+//!
+//! The passes rename, inline and reorder declarations, so the spans of the output tree point
+//! at the declarations they were generated from, not at the emitted text. The tokens of each
+//! emitted declaration are aligned with the tokens of its source: tokens match if they are
+//! equal, or if the emitted identifier is the mangled name of a path ending with the source
+//! identifier. Tokens
+//! without a match, like the names of template instantiations, map to the closest preceding
+//! token that has one.
+
+use std::{collections::HashMap, fmt::Write, ops::Range};
+
+use mew_parse::{
+    lexer::{Lexer, Token},
+    span::{FileId, SourceMap},
+};
+
+use mew_types::mangling::demangle;
+
+use crate::validation::OutputMap;
+
+/// How many source tokens are searched for a match of an emitted token, so that a token
+/// without a match does not resynchronize far ahead of where it was emitted.
+const LOOKAHEAD: usize = 16;
+
+/// A source map from the generated WGSL to the MEW sources.
+///
+/// Lines and columns are 0-based, and columns count UTF-16 code units, as in the v3 format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputSourceMap {
+    /// The names of the source files.
+    pub sources: Vec<String>,
+    /// The text of each of `sources`.
+    pub sources_content: Vec<String>,
+    /// The source names of the identifiers that were renamed in the output, e.g. `Color::mix`
+    /// for `Color_mix`, and of the declarations that kept their names, like entry points.
+    pub names: Vec<String>,
+    /// The mappings of each line of the output, ordered by column.
+    pub lines: Vec<Vec<Mapping>>,
+}
+
+/// Maps the token starting at `generated_column` to a position in a source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping {
+    pub generated_column: u32,
+    /// The index of the file in [`OutputSourceMap::sources`].
+    pub source: u32,
+    pub line: u32,
+    pub column: u32,
+    /// The index of the source name in [`OutputSourceMap::names`], for renamed identifiers.
+    pub name: Option<u32>,
+}
+
+impl OutputSourceMap {
+    /// Returns the mapping of the token at a 0-based line and UTF-16 column of the output.
+    pub fn lookup(&self, line: u32, column: u32) -> Option<&Mapping> {
+        let mappings = self.lines.get(line as usize)?;
+        let idx = mappings.partition_point(|mapping| mapping.generated_column <= column);
+        mappings.get(idx.checked_sub(1)?)
+    }
+
+    /// Encodes the mappings as Base64 VLQs, the `mappings` field of the v3 format.
+    pub fn mappings(&self) -> String {
+        let mut output = String::new();
+        let (mut source, mut line, mut column, mut name) = (0i64, 0i64, 0i64, 0i64);
+        for (idx, mappings) in self.lines.iter().enumerate() {
+            if idx > 0 {
+                output.push(';');
+            }
+            let mut generated_column = 0i64;
+            for (idx, mapping) in mappings.iter().enumerate() {
+                if idx > 0 {
+                    output.push(',');
+                }
+                encode_vlq(
+                    &mut output,
+                    mapping.generated_column as i64 - generated_column,
+                );
+                encode_vlq(&mut output, mapping.source as i64 - source);
+                encode_vlq(&mut output, mapping.line as i64 - line);
+                encode_vlq(&mut output, mapping.column as i64 - column);
+                generated_column = mapping.generated_column as i64;
+                source = mapping.source as i64;
+                line = mapping.line as i64;
+                column = mapping.column as i64;
+                if let Some(mapping_name) = mapping.name {
+                    encode_vlq(&mut output, mapping_name as i64 - name);
+                    name = mapping_name as i64;
+                }
+            }
+        }
+        output
+    }
+
+    /// Serializes the source map as v3 JSON. `file` is the name of the generated file, if any.
+    pub fn to_json(&self, file: Option<&str>) -> String {
+        let strings = |strings: &[String]| {
+            strings
+                .iter()
+                .map(|string| json_string(string))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let mut output = String::from("{\"version\":3,");
+        if let Some(file) = file {
+            let _ = write!(output, "\"file\":{},", json_string(file));
+        }
+        let _ = write!(
+            output,
+            "\"sources\":[{}],\"sourcesContent\":[{}],\"names\":[{}],\"mappings\":{}}}",
+            strings(&self.sources),
+            strings(&self.sources_content),
+            strings(&self.names),
+            json_string(&self.mappings())
+        );
+        output
+    }
+
+    /// Builds the source map of the output printed by [`OutputMap::print`].
+    /// `mew_paths` are the paths of the mangled names of the declarations.
+    pub(crate) fn new(
+        output: &str,
+        output_map: &OutputMap,
+        source_map: &SourceMap,
+        mew_paths: &HashMap<String, String>,
+    ) -> Self {
+        let mut builder = Builder {
+            source_map,
+            mew_paths,
+            result: OutputSourceMap {
+                lines: vec![vec![]; line_starts(output).len()],
+                ..Default::default()
+            },
+            output_lines: line_starts(output),
+            files: HashMap::new(),
+            names: HashMap::new(),
+        };
+        for (range, span) in output_map.declarations() {
+            let Some(source) = source_map.text(span) else {
+                continue;
+            };
+            let emitted = tokens(&output[range.clone()], range.start);
+            let original = tokens(source, span.start);
+            builder.map_tokens(output, &emitted, &original);
+        }
+        for mappings in builder.result.lines.iter_mut() {
+            mappings.sort_by_key(|mapping| mapping.generated_column);
+            mappings.dedup_by_key(|mapping| mapping.generated_column);
+        }
+        builder.result
+    }
+}
+
+struct Builder<'a> {
+    source_map: &'a SourceMap,
+    mew_paths: &'a HashMap<String, String>,
+    result: OutputSourceMap,
+    /// The offsets at which the lines of the output start.
+    output_lines: Vec<usize>,
+    /// The index in `sources` of each file, and the offsets at which its lines start.
+    files: HashMap<FileId, (u32, Vec<usize>)>,
+    names: HashMap<String, u32>,
+}
+
+impl Builder<'_> {
+    /// Maps the emitted tokens of a declaration to the tokens of its source.
+    fn map_tokens(
+        &mut self,
+        output: &str,
+        emitted: &[(Range<usize>, Token)],
+        original: &[(Range<usize>, Token)],
+    ) {
+        let mut next = 0;
+        // the source offset and name of the last matched token
+        let mut previous = original.first().map(|(range, _)| (range.start, None));
+        for (range, token) in emitted.iter() {
+            let end = original.len().min(next + LOOKAHEAD);
+            if let Some(idx) = (next..end).find(|idx| self.tokens_match(token, &original[*idx].1)) {
+                let start = path_start(original, next, idx);
+                let source_range = original[start].0.start..original[idx].0.end;
+                // declarations that keep their names, like entry points, are named too
+                let name = match token {
+                    Token::Ident(ident) => self
+                        .source_map
+                        .text(&source_range)
+                        .filter(|source| source != ident || self.mew_paths.contains_key(ident))
+                        .map(str::to_string),
+                    _ => None,
+                };
+                previous = Some((source_range.start, name));
+                next = idx + 1;
+            }
+            if let Some((offset, name)) = previous.clone() {
+                self.push(output, range.start, offset, name);
+            }
+        }
+    }
+
+    /// Whether an emitted token was printed from a source token. Identifiers match the names
+    /// they were mangled to, e.g. `Color_mix` or `Color_mix_1f32_2` emitted for `mix`.
+    fn tokens_match(&self, emitted: &Token, original: &Token) -> bool {
+        match (emitted, original) {
+            (Token::Ident(emitted), Token::Ident(original)) => {
+                emitted == original
+                    || self
+                        .mew_paths
+                        .get(emitted)
+                        .cloned()
+                        .or_else(|| demangle(emitted))
+                        .is_some_and(|path| {
+                            let path = path.split_once('<').map(|(path, _)| path).unwrap_or(&path);
+                            path.rsplit("::").next() == Some(original)
+                        })
+            }
+            _ => emitted == original,
+        }
+    }
+
+    fn push(&mut self, output: &str, output_offset: usize, offset: usize, name: Option<String>) {
+        let Some(file) = self.source_map.file_id(&(offset..offset)) else {
+            return;
+        };
+        let Some(source_file) = self.source_map.file(file) else {
+            return;
+        };
+        let (source, lines) = self.files.entry(file).or_insert_with(|| {
+            self.result.sources.push(source_file.name.clone());
+            self.result.sources_content.push(source_file.source.clone());
+            (
+                self.result.sources.len() as u32 - 1,
+                line_starts(&source_file.source),
+            )
+        });
+        let (line, column) = line_column(&source_file.source, lines, offset - source_file.start);
+        let name = name.map(|name| {
+            *self.names.entry(name.clone()).or_insert_with(|| {
+                self.result.names.push(name);
+                self.result.names.len() as u32 - 1
+            })
+        });
+        let (generated_line, generated_column) =
+            line_column(output, &self.output_lines, output_offset);
+        self.result.lines[generated_line as usize].push(Mapping {
+            generated_column,
+            source: *source,
+            line,
+            column,
+            name,
+        });
+    }
+}
+
+/// Lexes the text, shifting the spans of the tokens by `offset`.
+fn tokens(text: &str, offset: usize) -> Vec<(Range<usize>, Token)> {
+    Lexer::with_offset(text, offset)
+        .filter_map(|token| token.ok())
+        .map(|(start, token, end)| (start..end, token))
+        .collect()
+}
+
+/// Returns the first token of the path `A::B::c` ending with the identifier at `idx`, without
+/// going back before `first`.
+fn path_start(original: &[(Range<usize>, Token)], first: usize, mut idx: usize) -> usize {
+    while idx >= first + 2
+        && original[idx - 1].1 == Token::SymDoubleColon
+        && matches!(original[idx - 2].1, Token::Ident(_))
+    {
+        idx -= 2;
+    }
+    idx
+}
+
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(idx, _)| idx + 1))
+        .collect()
+}
+
+/// The 0-based line and UTF-16 column of an offset in the text.
+fn line_column(text: &str, line_starts: &[usize], offset: usize) -> (u32, u32) {
+    let line = line_starts.partition_point(|start| *start <= offset) - 1;
+    let column = text[line_starts[line]..offset].encode_utf16().count();
+    (line as u32, column as u32)
+}
+
+fn encode_vlq(output: &mut String, value: i64) {
+    const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    // the sign is stored in the lowest bit
+    let mut value = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    };
+    loop {
+        let mut digit = value & 0b11111;
+        value >>= 5;
+        if value > 0 {
+            digit |= 0b100000;
+        }
+        output.push(BASE64[digit as usize] as char);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut output = String::with_capacity(value.len() + 2);
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(output, "\\u{:04x}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
@@ -0,0 +1,245 @@
+//! The languages a module can be compiled to, written by naga's backends from a [`NagaModule`].
+
+use naga::back::{glsl, hlsl, msl, spv};
+
+use crate::{MewError, MewErrorInner, NagaModule};
+use mew_parse::diagnostic::Diagnostic;
+
+/// The language to compile to.
+#[derive(Debug, Clone, Default)]
+pub enum Target {
+    #[default]
+    Wgsl,
+    SpirV(SpirVOptions),
+    Glsl(GlslOptions),
+    Hlsl(HlslOptions),
+    Msl(MslOptions),
+}
+
+#[derive(Debug, Clone)]
+pub struct SpirVOptions {
+    /// The (major, minor) version of SPIR-V.
+    pub version: (u8, u8),
+    /// The entry point to write, or `None` to write all of them.
+    pub entry_point: Option<String>,
+}
+
+impl Default for SpirVOptions {
+    fn default() -> Self {
+        Self {
+            version: (1, 0),
+            entry_point: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GlslOptions {
+    pub version: glsl::Version,
+    /// The entry point to write. A GLSL shader has a single entry point, so this may only
+    /// be `None` if the module has one entry point.
+    pub entry_point: Option<String>,
+}
+
+impl Default for GlslOptions {
+    fn default() -> Self {
+        Self {
+            version: glsl::Version::Desktop(450),
+            entry_point: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HlslOptions {
+    pub shader_model: hlsl::ShaderModel,
+    /// The entry point to write, or `None` to write all of them.
+    pub entry_point: Option<String>,
+}
+
+impl Default for HlslOptions {
+    fn default() -> Self {
+        Self {
+            shader_model: hlsl::ShaderModel::V5_1,
+            entry_point: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MslOptions {
+    /// The (major, minor) version of the Metal Shading Language.
+    pub version: (u8, u8),
+    /// The entry point to write, or `None` to write all of them.
+    pub entry_point: Option<String>,
+}
+
+impl Default for MslOptions {
+    fn default() -> Self {
+        Self {
+            version: (1, 0),
+            entry_point: None,
+        }
+    }
+}
+
+/// The compiled shader, as source code or as a SPIR-V binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetOutput {
+    Text(String),
+    SpirV(Vec<u32>),
+}
+
+impl Target {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Target::Wgsl => "WGSL",
+            Target::SpirV(_) => "SPIR-V",
+            Target::Glsl(_) => "GLSL",
+            Target::Hlsl(_) => "HLSL",
+            Target::Msl(_) => "MSL",
+        }
+    }
+
+    fn entry_point(&self) -> Option<&str> {
+        match self {
+            Target::Wgsl => None,
+            Target::SpirV(options) => options.entry_point.as_deref(),
+            Target::Glsl(options) => options.entry_point.as_deref(),
+            Target::Hlsl(options) => options.entry_point.as_deref(),
+            Target::Msl(options) => options.entry_point.as_deref(),
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> Box<MewError> {
+        let message = message.into();
+        Box::new(MewError {
+            span: None,
+            location: None,
+            module_name: None,
+            error: MewErrorInner::TargetError(message.clone()),
+            diagnostic: Diagnostic::error(format!("failed to write {}", self.name()))
+                .with_code("E0302")
+                .with_note(message),
+        })
+    }
+
+    /// Writes the module with the backend of the target.
+    ///
+    /// The names of the entry points are their names in the generated module.
+    pub fn write(&self, naga: &NagaModule) -> Result<TargetOutput, Box<MewError>> {
+        let selected;
+        let naga = match self.entry_point() {
+            Some(name) => {
+                selected = self.select_entry_point(naga, name)?;
+                &selected
+            }
+            None => naga,
+        };
+        let NagaModule { module, info, .. } = naga;
+        match self {
+            Target::Wgsl => {
+                naga::back::wgsl::write_string(module, info, naga::back::wgsl::WriterFlags::empty())
+                    .map(TargetOutput::Text)
+                    .map_err(|err| self.error(err.to_string()))
+            }
+            Target::SpirV(options) => {
+                let spv_options = spv::Options {
+                    lang_version: options.version,
+                    ..Default::default()
+                };
+                spv::write_vec(module, info, &spv_options, None)
+                    .map(TargetOutput::SpirV)
+                    .map_err(|err| self.error(err.to_string()))
+            }
+            Target::Glsl(options) => {
+                let entry_point = match module.entry_points.as_slice() {
+                    [entry_point] => entry_point,
+                    entry_points => {
+                        return Err(self.error(format!(
+                            "a GLSL shader has a single entry point, select one of {}",
+                            entry_point_names(entry_points)
+                        )));
+                    }
+                };
+                let glsl_options = glsl::Options {
+                    version: options.version,
+                    ..Default::default()
+                };
+                let pipeline_options = glsl::PipelineOptions {
+                    shader_stage: entry_point.stage,
+                    entry_point: entry_point.name.clone(),
+                    multiview: None,
+                };
+                let mut output = String::new();
+                glsl::Writer::new(
+                    &mut output,
+                    module,
+                    info,
+                    &glsl_options,
+                    &pipeline_options,
+                    naga::proc::BoundsCheckPolicies::default(),
+                )
+                .and_then(|mut writer| writer.write())
+                .map_err(|err| self.error(err.to_string()))?;
+                Ok(TargetOutput::Text(output))
+            }
+            Target::Hlsl(options) => {
+                let hlsl_options = hlsl::Options {
+                    shader_model: options.shader_model,
+                    ..Default::default()
+                };
+                let mut output = String::new();
+                hlsl::Writer::new(&mut output, &hlsl_options)
+                    .write(module, info, None)
+                    .map_err(|err| self.error(err.to_string()))?;
+                Ok(TargetOutput::Text(output))
+            }
+            Target::Msl(options) => {
+                let msl_options = msl::Options {
+                    lang_version: options.version,
+                    ..Default::default()
+                };
+                msl::write_string(module, info, &msl_options, &Default::default())
+                    .map(|(output, _)| TargetOutput::Text(output))
+                    .map_err(|err| self.error(err.to_string()))
+            }
+        }
+    }
+
+    /// Removes the entry points other than `name`, which leaves the functions they use in
+    /// the module.
+    fn select_entry_point(
+        &self,
+        naga: &NagaModule,
+        name: &str,
+    ) -> Result<NagaModule, Box<MewError>> {
+        if !naga.module.entry_points.iter().any(|ep| ep.name == name) {
+            return Err(self.error(format!(
+                "there is no entry point `{name}`, the entry points are {}",
+                entry_point_names(&naga.module.entry_points)
+            )));
+        }
+        let mut module = naga.module.clone();
+        module.entry_points.retain(|ep| ep.name == name);
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .map_err(|err| self.error(err.as_inner().to_string()))?;
+        Ok(NagaModule {
+            module,
+            info,
+            mew_paths: naga.mew_paths.clone(),
+        })
+    }
+}
+
+fn entry_point_names(entry_points: &[naga::EntryPoint]) -> String {
+    entry_points
+        .iter()
+        .map(|ep| format!("`{}`", ep.name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,208 @@
+//! Validation of the generated WGSL with naga.
+//!
+//! naga reports errors at offsets in the generated WGSL, whose names are mangled and whose
+//! declarations come from many files. An [`OutputMap`] records which declaration, and which
+//! statement of a function body, produced each part of the output, so the errors can be
+//! reported at the MEW source instead.
+
+use std::ops::Range;
+
+use mew_parse::{
+    diagnostic::Diagnostic,
+    span::{Span, Spanned},
+    syntax::{GlobalDeclaration, Statement, TranslationUnit},
+};
+
+/// Maps ranges of the generated WGSL to the spans of the MEW source they were printed from.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct OutputMap {
+    segments: Vec<(Range<usize>, Span)>,
+    /// The segments of whole declarations, in the order they are printed.
+    declarations: Vec<(Range<usize>, Span)>,
+}
+
+impl OutputMap {
+    /// Prints the translation unit like its `Display` impl does, recording where each
+    /// declaration is printed.
+    pub(crate) fn print(translation_unit: &TranslationUnit) -> (String, OutputMap) {
+        let mut output = translation_unit
+            .global_directives
+            .iter()
+            .map(|directive| directive.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        output.push_str("\n\n");
+        let mut map = OutputMap::default();
+        for (idx, declaration) in translation_unit.global_declarations.iter().enumerate() {
+            if idx > 0 {
+                output.push_str("\n\n");
+            }
+            let start = output.len();
+            output.push_str(&declaration.to_string());
+            map.push(start..output.len(), declaration.span());
+            if declaration.span() != (0..0) {
+                map.declarations
+                    .push((start..output.len(), declaration.span()));
+            }
+            if let GlobalDeclaration::Function(function) = declaration.as_ref() {
+                map.push_statements(&output, start, &function.body.statements);
+            }
+        }
+        output.push('\n');
+        (output, map)
+    }
+
+    fn push(&mut self, range: Range<usize>, span: Span) {
+        // synthetic nodes have no source to point at
+        if span != (0..0) {
+            self.segments.push((range, span));
+        }
+    }
+
+    /// Records the statements of a function body, printed after `start` with one level of
+    /// indentation. The statements of nested blocks are attributed to the enclosing one.
+    fn push_statements(&mut self, output: &str, start: usize, statements: &[Spanned<Statement>]) {
+        let mut chunks = vec![];
+        flatten_statements(statements, &mut chunks);
+        let mut cursor = start;
+        for (text, span) in chunks {
+            let text = text
+                .lines()
+                .map(|line| {
+                    if line.is_empty() {
+                        String::new()
+                    } else {
+                        format!("    {line}")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let Some(offset) = output[cursor..].find(&text) else {
+                return;
+            };
+            let chunk_start = cursor + offset;
+            // declarations also print the statements that follow them, so only the first
+            // line is theirs
+            let chunk_end = chunk_start + text.find('\n').unwrap_or(text.len());
+            self.push(chunk_start..chunk_end, span);
+            cursor = chunk_end;
+        }
+    }
+
+    pub(crate) fn declarations(&self) -> &[(Range<usize>, Span)] {
+        &self.declarations
+    }
+
+    /// Returns the span of the MEW source that printed the output at `range`, picking the
+    /// narrowest part of the output that contains its start.
+    pub(crate) fn source_span(&self, range: &Range<usize>) -> Option<Span> {
+        self.segments
+            .iter()
+            .filter(|(output, _)| output.start <= range.start && range.start < output.end.max(1))
+            .min_by_key(|(output, _)| output.len())
+            .map(|(_, span)| span.clone())
+    }
+}
+
+/// Lists the statements in the order they are printed, with the declarations of declaration
+/// statements before the statements nested in them.
+fn flatten_statements(statements: &[Spanned<Statement>], chunks: &mut Vec<(String, Span)>) {
+    for statement in statements {
+        match statement.as_ref() {
+            Statement::Declaration(declaration) => {
+                chunks.push((
+                    declaration.declaration.to_string(),
+                    declaration.declaration.span(),
+                ));
+                flatten_statements(&declaration.statements, chunks);
+            }
+            _ => chunks.push((statement.to_string(), statement.span())),
+        }
+    }
+}
+
+/// The line of the output that contains `offset`.
+fn output_line(output: &str, offset: usize) -> &str {
+    let start = output[..offset].rfind('\n').map(|x| x + 1).unwrap_or(0);
+    let end = output[offset..]
+        .find('\n')
+        .map(|x| x + offset)
+        .unwrap_or(output.len());
+    output[start..end].trim()
+}
+
+/// Builds a diagnostic from naga's message and labels, moving the labels onto the MEW source.
+///
+/// naga labels the enclosing function as well as the faulty expression, so the narrowest
+/// label is the primary one. Labels naming naga handles, like `naga::Expression [0]`, are
+/// only picked if no other label maps onto the source.
+fn diagnostic<'a>(
+    message: String,
+    code: &str,
+    labels: impl IntoIterator<Item = (Range<usize>, &'a str)>,
+    output: &str,
+    map: &OutputMap,
+) -> Diagnostic {
+    let labels = labels
+        .into_iter()
+        .filter_map(|(range, message)| Some((range.clone(), map.source_span(&range)?, message)))
+        .collect::<Vec<_>>();
+    let primary = labels
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, (_, span, message))| (message.starts_with("naga::"), span.len()))
+        .map(|(idx, _)| idx);
+    let mut diagnostic = Diagnostic::error(message).with_code(code);
+    for (idx, (range, span, message)) in labels.into_iter().enumerate() {
+        diagnostic = if Some(idx) == primary {
+            diagnostic
+                .with_note(format!(
+                    "in the generated WGSL: `{}`",
+                    output_line(output, range.start)
+                ))
+                .with_primary_label(span, message)
+        } else {
+            diagnostic.with_secondary_label(span, message)
+        };
+    }
+    diagnostic
+}
+
+/// Parses and validates the generated WGSL with naga.
+pub(crate) fn validate(
+    output: &str,
+    map: &OutputMap,
+) -> Result<(naga::Module, naga::valid::ModuleInfo), Box<Diagnostic>> {
+    let module = naga::front::wgsl::parse_str(output).map_err(|err| {
+        let labels = err
+            .labels()
+            .filter_map(|(span, message)| Some((span.to_range()?, message)))
+            .collect::<Vec<_>>();
+        Box::new(diagnostic(
+            err.message().to_string(),
+            "E0300",
+            labels,
+            output,
+            map,
+        ))
+    })?;
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .map_err(|err| {
+        let labels = err
+            .spans()
+            .filter_map(|(span, message)| Some((span.to_range()?, message.as_str())))
+            .collect::<Vec<_>>();
+        let mut diagnostic = diagnostic(err.as_inner().to_string(), "E0301", labels, output, map);
+        let mut source: &dyn std::error::Error = err.as_inner();
+        while let Some(next) = source.source() {
+            diagnostic = diagnostic.with_note(next.to_string());
+            source = next;
+        }
+        Box::new(diagnostic)
+    })?;
+    Ok((module, info))
+}
@@ -1,5 +1,5 @@
 use mew_parse::{
-    span::Spanned,
+    span::{SourceMap, Span, Spanned},
     syntax::{self, Module, ModuleDirective, TranslationUnit},
 };
 use mew_types::CompilerPass;
@@ -8,6 +8,34 @@ use mew_types::CompilerPass;
 pub struct Bundler<'a> {
     pub sources: Vec<&'a str>,
     pub enclosing_module_name: Option<String>,
+    /// The sources are added to this map before being parsed, so the spans of the bundled
+    /// declarations never overlap with those of files that are already in it.
+    pub source_map: SourceMap,
+    /// The names of the sources in the source map. Sources without a name are named after
+    /// the enclosing module.
+    pub source_names: Vec<String>,
+}
+
+/// Extends `span` so that it also covers `other`.
+fn cover(span: Option<Span>, other: &Span) -> Span {
+    match span {
+        Some(span) => usize::min(span.start, other.start)..usize::max(span.end, other.end),
+        None => other.clone(),
+    }
+}
+
+impl Bundler<'_> {
+    fn file_name(&self, idx: usize) -> String {
+        if let Some(name) = self.source_names.get(idx) {
+            return name.clone();
+        }
+        let name = self.enclosing_module_name.as_deref().unwrap_or("<bundle>");
+        if self.sources.len() > 1 {
+            format!("{name}#{idx}")
+        } else {
+            name.to_string()
+        }
+    }
 }
 
 impl<'a> CompilerPass for Bundler<'a> {
@@ -17,17 +45,13 @@ impl<'a> CompilerPass for Bundler<'a> {
     ) -> mew_types::CompilerPassResult {
         let mut result: TranslationUnit = TranslationUnit::default();
 
-        let mut ws: String = String::new();
-
-        for file in self.sources.iter() {
-            let file_len = file.len();
-            let mut file_with_starting_ws = ws.clone();
-            file_with_starting_ws.push_str(file);
-            ws.extend((0..file_len).map(|_| ' '));
-            let mut local_translation_unit = mew_parse::Parser::parse_str(&file_with_starting_ws)
-                .map_err(|err| {
-                mew_types::CompilerPassError::ParseError(format!("{}", err), err.span())
-            })?;
+        for (idx, source) in self.sources.iter().enumerate() {
+            let name = self.file_name(idx);
+            let file = self.source_map.add_file(name, *source);
+            // safety: the file was just added
+            let source_file = self.source_map.file(file).unwrap();
+            let mut local_translation_unit = mew_parse::Parser::parse_file(source_file)
+                .map_err(|err| mew_types::CompilerPassError::ParseError(err.diagnostic()))?;
             result
                 .global_declarations
                 .append(&mut local_translation_unit.global_declarations);
@@ -41,11 +65,10 @@ impl<'a> CompilerPass for Bundler<'a> {
                 name: Spanned::new(module_name.to_owned(), 0..0),
                 ..Module::default()
             };
-            let mut module_span = 0..0;
+            let mut module_span: Option<Span> = None;
             for declaration in result.global_declarations {
                 let span = declaration.span();
-                module_span.start = usize::min(span.start, module_span.start);
-                module_span.end = usize::max(span.end, module_span.end);
+                module_span = Some(cover(module_span, &span));
                 module
                     .members
                     .push(Spanned::new(declaration.value.into(), span));
@@ -53,8 +76,7 @@ impl<'a> CompilerPass for Bundler<'a> {
             for directive in result.global_directives {
                 match TryInto::<Spanned<ModuleDirective>>::try_into(directive) {
                     Ok(dir) => {
-                        module_span.start = usize::min(dir.span().start, module_span.start);
-                        module_span.end = usize::max(dir.span().end, module_span.end);
+                        module_span = Some(cover(module_span, &dir.span()));
                         module.directives.push(dir);
                     }
                     Err(directive) => {
@@ -64,7 +86,7 @@ impl<'a> CompilerPass for Bundler<'a> {
             }
             translation_unit.global_declarations.push(Spanned::new(
                 syntax::GlobalDeclaration::Module(module),
-                module_span,
+                module_span.unwrap_or(0..0),
             ));
             Ok(())
         } else {
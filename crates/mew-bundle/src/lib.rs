@@ -1,3 +1,5 @@
 pub mod bundler;
+pub mod loader;
 
 pub use bundler::*;
+pub use loader::*;
@@ -0,0 +1,101 @@
+use std::path::PathBuf;
+
+/// The source of a module returned by a [`ModuleLoader`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoadedModule {
+    /// The name of the file, used to report errors.
+    pub file_name: String,
+    pub source: String,
+}
+
+/// Finds the source of modules from their path.
+pub trait ModuleLoader {
+    /// Loads the module at `path`, where `path[0]` is a root module and the following parts
+    /// are modules nested in it.
+    ///
+    /// Returns `Ok(None)` if the loader has no source for the module, e.g. because it is
+    /// declared inline in its parent module, or because the path does not name a module.
+    fn load(&self, path: &[String]) -> std::io::Result<Option<LoadedModule>>;
+}
+
+/// Any function from a module path to its source is a loader, e.g. to provide modules
+/// from memory or from the network.
+impl<F> ModuleLoader for F
+where
+    F: Fn(&[String]) -> std::io::Result<Option<LoadedModule>>,
+{
+    fn load(&self, path: &[String]) -> std::io::Result<Option<LoadedModule>> {
+        self(path)
+    }
+}
+
+/// Loads modules from the files of a directory, where the module `Foo::Bar` is the file
+/// `foo/bar.mew`.
+///
+/// Module names are converted to snake case to get file names, so `MyLib::SumOp` is
+/// `my_lib/sum_op.mew`. A directory without a matching file, like `my_lib/`, is an empty
+/// module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirectoryLoader {
+    pub root: PathBuf,
+    pub extension: String,
+}
+
+impl DirectoryLoader {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            extension: "mew".to_string(),
+        }
+    }
+
+    /// The file that holds the module at `path`.
+    pub fn file_path(&self, path: &[String]) -> PathBuf {
+        let mut file_path = self.root.clone();
+        file_path.extend(path.iter().map(|name| file_stem(name)));
+        file_path.set_extension(&self.extension);
+        file_path
+    }
+}
+
+impl ModuleLoader for DirectoryLoader {
+    fn load(&self, path: &[String]) -> std::io::Result<Option<LoadedModule>> {
+        if path.is_empty() {
+            return Ok(None);
+        }
+        let file_path = self.file_path(path);
+        match std::fs::read_to_string(&file_path) {
+            Ok(source) => Ok(Some(LoadedModule {
+                file_name: file_path.display().to_string(),
+                source,
+            })),
+            // a directory without a file is a module that only has nested modules
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                let dir_path = file_path.with_extension("");
+                Ok(dir_path.is_dir().then(|| LoadedModule {
+                    file_name: dir_path.display().to_string(),
+                    source: String::new(),
+                }))
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Converts a module name to snake case, e.g. `SumBinaryOp` to `sum_binary_op`.
+fn file_stem(module_name: &str) -> String {
+    let mut stem = String::with_capacity(module_name.len());
+    let mut prev: Option<char> = None;
+    for c in module_name.chars() {
+        if c.is_uppercase() {
+            if prev.is_some_and(|prev| prev.is_lowercase() || prev.is_ascii_digit()) {
+                stem.push('_');
+            }
+            stem.extend(c.to_lowercase());
+        } else {
+            stem.push(c);
+        }
+        prev = Some(c);
+    }
+    stem
+}
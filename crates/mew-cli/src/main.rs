@@ -0,0 +1,49 @@
+use std::io::BufRead;
+
+use mew_types::mangling::demangle;
+
+const USAGE: &str = "\
+usage: mew <command> [args]
+
+commands:
+    demangle [NAME]...    print the MEW paths of mangled WGSL identifiers, read one per
+                          line from stdin if no name is given";
+
+/// Prints the demangled path of a name, or the name itself if it is not mangled.
+fn print_demangled(name: &str) {
+    let name = name.trim();
+    if !name.is_empty() {
+        println!("{}", demangle(name).unwrap_or_else(|| name.to_string()));
+    }
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("demangle") => {
+            let names = args.collect::<Vec<_>>();
+            if names.is_empty() {
+                for line in std::io::stdin().lock().lines() {
+                    match line {
+                        Ok(line) => print_demangled(&line),
+                        Err(err) => {
+                            eprintln!("error: {err}");
+                            std::process::exit(1);
+                        }
+                    }
+                }
+            } else {
+                names.iter().for_each(|name| print_demangled(name));
+            }
+        }
+        Some("help" | "-h" | "--help") => println!("{USAGE}"),
+        Some(command) => {
+            eprintln!("error: unknown command `{command}`\n\n{USAGE}");
+            std::process::exit(2);
+        }
+        None => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,434 @@
+use std::collections::HashMap;
+
+use mew_parse::{
+    span::Spanned,
+    syntax::{
+        Attribute, BinaryOperator, CompoundDirective, CompoundStatement, Expression,
+        GlobalDeclaration, GlobalDirective, LiteralExpression, Module, ModuleDirective,
+        ModuleMemberDeclaration, ModuleTypeSignature, ModuleTypeSignatureMemberDeclaration,
+        Statement, TranslationUnit, UnaryOperator,
+    },
+};
+use mew_types::{CompilerPass, CompilerPassError, CompilerPassResult, Diagnostics};
+
+/// Evaluates the `@if`, `@elif` and `@else` attributes of the translation unit against a
+/// feature map, as in the conditional translation of the WESL spec.
+///
+/// An element with `@if(condition)` is kept if its condition is true. An element with
+/// `@elif(condition)` or `@else` follows an element with `@if` or `@elif`, and is kept if the
+/// conditions before it are false and its own is true. The attributes are removed from the
+/// elements that are kept.
+///
+/// Global declarations, module members, struct members, statements, the initializers and
+/// updates of `for` statements, imports and `extend` directives can be conditional.
+/// Conditions are made of features, `true`, `false`, `!`, `&&`, `||` and parentheses.
+/// Features that are not in the map are false.
+#[derive(Debug, Default, Clone)]
+pub struct ConditionalTranslator {
+    pub features: HashMap<String, bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConditionKind {
+    If,
+    Elif,
+    Else,
+}
+
+impl ConditionKind {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "if" => Some(ConditionKind::If),
+            "elif" => Some(ConditionKind::Elif),
+            "else" => Some(ConditionKind::Else),
+            _ => None,
+        }
+    }
+}
+
+/// A statement that declares a variable owns the statements that follow it in its scope, so
+/// they are moved back after it before the conditions of a list of statements are evaluated.
+fn flatten_statements(statements: Vec<Spanned<Statement>>, flat: &mut Vec<Spanned<Statement>>) {
+    for mut statement in statements {
+        let rest = match statement.as_mut() {
+            Statement::Declaration(decl) => decl.statements.drain(..).collect(),
+            _ => vec![],
+        };
+        flat.push(statement);
+        flatten_statements(rest, flat);
+    }
+}
+
+/// The parser wraps a conditional statement that declares nothing in a compound statement
+/// which holds its attributes and ends where it ends. Once the conditions are removed, the
+/// statement takes the place of the compound statement, which would add a block.
+fn unwrap_conditional_statement(statement: Spanned<Statement>) -> Spanned<Statement> {
+    let span = statement.span();
+    match statement.into_inner() {
+        Statement::Compound(mut compound)
+            if compound.attributes.is_empty()
+                && compound.directives.is_empty()
+                && compound.statements.len() == 1
+                && compound.statements[0].span().end == span.end =>
+        {
+            compound.statements.remove(0)
+        }
+        statement => Spanned::new(statement, span),
+    }
+}
+
+fn statement_attributes(statement: &mut Statement) -> Option<&mut Vec<Spanned<Attribute>>> {
+    match statement {
+        Statement::Compound(c) => Some(&mut c.attributes),
+        Statement::If(i) => Some(&mut i.attributes),
+        Statement::Switch(s) => Some(&mut s.attributes),
+        Statement::Loop(l) => Some(&mut l.attributes),
+        Statement::For(f) => Some(&mut f.attributes),
+        Statement::While(w) => Some(&mut w.attributes),
+        Statement::Declaration(d) => Some(&mut d.declaration.attributes),
+        Statement::ConstAssert(c) => Some(&mut c.attributes),
+        _ => None,
+    }
+}
+
+impl ConditionalTranslator {
+    /// Evaluates a condition, or returns the span of the part that is not a condition.
+    fn evaluate(&self, expr: &Spanned<Expression>) -> Result<bool, mew_parse::span::Span> {
+        match expr.as_ref() {
+            Expression::Literal(literal) => match literal.as_ref() {
+                LiteralExpression::True => Ok(true),
+                LiteralExpression::False => Ok(false),
+                _ => Err(expr.span()),
+            },
+            Expression::Parenthesized(inner) => self.evaluate(inner),
+            Expression::Unary(unary) if unary.operator.value == UnaryOperator::LogicalNegation => {
+                Ok(!self.evaluate(&unary.operand)?)
+            }
+            Expression::Binary(binary) => match binary.operator.as_ref() {
+                BinaryOperator::ShortCircuitAnd => {
+                    Ok(self.evaluate(&binary.left)? & self.evaluate(&binary.right)?)
+                }
+                BinaryOperator::ShortCircuitOr => {
+                    Ok(self.evaluate(&binary.left)? | self.evaluate(&binary.right)?)
+                }
+                _ => Err(expr.span()),
+            },
+            Expression::Identifier(ident) => match ident.path.as_slice() {
+                [part] if part.template_args.is_none() && part.inline_template_args.is_none() => {
+                    Ok(self
+                        .features
+                        .get(&part.name.value)
+                        .copied()
+                        .unwrap_or(false))
+                }
+                _ => Err(expr.span()),
+            },
+            _ => Err(expr.span()),
+        }
+    }
+
+    /// Removes the conditional attribute of an element, and returns its kind and the value
+    /// of its condition.
+    fn take_condition(
+        &self,
+        attributes: &mut Vec<Spanned<Attribute>>,
+        diagnostics: &mut Diagnostics,
+    ) -> Option<(ConditionKind, bool)> {
+        let mut condition = None;
+        let mut others = vec![];
+        for attribute in attributes.drain(..) {
+            let Some(kind) = ConditionKind::from_name(&attribute.name) else {
+                others.push(attribute);
+                continue;
+            };
+            if condition.is_some() {
+                diagnostics.error(CompilerPassError::MisplacedCondition(
+                    attribute.name.value.clone(),
+                    attribute.span(),
+                ));
+                continue;
+            }
+            let value = match (kind, attribute.arguments.as_deref()) {
+                (ConditionKind::Else, None) => true,
+                (ConditionKind::If | ConditionKind::Elif, Some([argument])) => {
+                    match self.evaluate(argument) {
+                        Ok(value) => value,
+                        Err(span) => {
+                            diagnostics.error(CompilerPassError::MalformedCondition(span));
+                            false
+                        }
+                    }
+                }
+                _ => {
+                    diagnostics.error(CompilerPassError::MalformedCondition(attribute.span()));
+                    false
+                }
+            };
+            condition = Some((kind, value));
+        }
+        *attributes = others;
+        condition
+    }
+
+    /// Keeps the elements of a list whose conditions hold. `attributes` returns the attributes
+    /// of an element, or `None` if it cannot be conditional.
+    fn retain<T>(
+        &self,
+        items: &mut Vec<Spanned<T>>,
+        attributes: impl Fn(&mut T) -> Option<&mut Vec<Spanned<Attribute>>>,
+        diagnostics: &mut Diagnostics,
+    ) {
+        // whether an element of the current `@if` chain was kept, if in a chain
+        let mut chain: Option<bool> = None;
+        let mut kept = Vec::with_capacity(items.len());
+        for mut item in items.drain(..) {
+            let span = item.span();
+            let condition = match attributes(&mut item) {
+                Some(attributes) => self.take_condition(attributes, diagnostics),
+                None => None,
+            };
+            let keep = match (condition, chain) {
+                (None, _) => {
+                    chain = None;
+                    true
+                }
+                (Some((ConditionKind::If, value)), _) => {
+                    chain = Some(value);
+                    value
+                }
+                (Some((ConditionKind::Elif, value)), Some(taken)) => {
+                    chain = Some(taken || value);
+                    !taken && value
+                }
+                (Some((ConditionKind::Else, _)), Some(taken)) => {
+                    chain = None;
+                    !taken
+                }
+                (Some((kind, _)), None) => {
+                    let name = match kind {
+                        ConditionKind::Elif => "elif",
+                        _ => "else",
+                    };
+                    diagnostics.error(CompilerPassError::MisplacedCondition(
+                        name.to_string(),
+                        span,
+                    ));
+                    false
+                }
+            };
+            if keep {
+                kept.push(item);
+            }
+        }
+        *items = kept;
+    }
+
+    fn translate_compound(&self, compound: &mut CompoundStatement, diagnostics: &mut Diagnostics) {
+        self.retain(
+            &mut compound.directives,
+            |directive| match directive {
+                CompoundDirective::Import(import) => Some(&mut import.attributes),
+            },
+            diagnostics,
+        );
+        let mut statements = vec![];
+        flatten_statements(compound.statements.drain(..).collect(), &mut statements);
+        self.retain(&mut statements, statement_attributes, diagnostics);
+        let mut statements = statements
+            .into_iter()
+            .map(unwrap_conditional_statement)
+            .collect::<Vec<_>>();
+        for statement in statements.iter_mut() {
+            self.translate_statement(statement, diagnostics);
+        }
+        compound.statements = statements;
+        compound.construct_scope_tree();
+    }
+
+    fn translate_statement(&self, statement: &mut Statement, diagnostics: &mut Diagnostics) {
+        match statement {
+            Statement::Compound(compound) => self.translate_compound(compound, diagnostics),
+            Statement::If(iff) => {
+                self.translate_compound(&mut iff.if_clause.1, diagnostics);
+                for (_, else_if_statements) in iff.else_if_clauses.iter_mut() {
+                    self.translate_compound(else_if_statements, diagnostics);
+                }
+                if let Some(else_clause) = iff.else_clause.as_mut() {
+                    self.translate_compound(else_clause, diagnostics);
+                }
+            }
+            Statement::Switch(s) => {
+                for clause in s.clauses.iter_mut() {
+                    self.translate_compound(&mut clause.body, diagnostics);
+                }
+            }
+            Statement::Loop(l) => {
+                self.translate_compound(&mut l.body, diagnostics);
+                if let Some(cont) = l.continuing.as_mut() {
+                    self.translate_compound(&mut cont.body, diagnostics);
+                }
+            }
+            Statement::For(f) => {
+                self.translate_for_part(&mut f.initializer, diagnostics);
+                self.translate_for_part(&mut f.update, diagnostics);
+                self.translate_compound(&mut f.body, diagnostics);
+            }
+            Statement::While(w) => self.translate_compound(&mut w.body, diagnostics),
+            _ => {
+                // NO ACTION REQUIRED
+            }
+        }
+    }
+
+    /// Removes the initializer or update of a `for` statement if its condition is false.
+    fn translate_for_part(
+        &self,
+        part: &mut Option<Box<Spanned<Statement>>>,
+        diagnostics: &mut Diagnostics,
+    ) {
+        let Some(statement) = part.take() else {
+            return;
+        };
+        let mut statements = vec![*statement];
+        self.retain(&mut statements, statement_attributes, diagnostics);
+        *part = statements.pop().map(|statement| {
+            let mut statement = unwrap_conditional_statement(statement);
+            self.translate_statement(&mut statement, diagnostics);
+            Box::new(statement)
+        });
+    }
+
+    fn translate_interface(
+        &self,
+        interface: &mut ModuleTypeSignature,
+        diagnostics: &mut Diagnostics,
+    ) {
+        self.retain(
+            &mut interface.members,
+            |member| match member {
+                ModuleTypeSignatureMemberDeclaration::Declaration(d) => Some(&mut d.attributes),
+                ModuleTypeSignatureMemberDeclaration::Struct(s) => Some(&mut s.attributes),
+                ModuleTypeSignatureMemberDeclaration::Function(f) => Some(&mut f.attributes),
+                ModuleTypeSignatureMemberDeclaration::DefaultFunction(f) => Some(&mut f.attributes),
+                ModuleTypeSignatureMemberDeclaration::Void
+                | ModuleTypeSignatureMemberDeclaration::Alias(_) => None,
+            },
+            diagnostics,
+        );
+        for member in interface.members.iter_mut() {
+            match member.as_mut() {
+                ModuleTypeSignatureMemberDeclaration::Struct(s) => {
+                    self.retain(&mut s.members, |m| Some(&mut m.attributes), diagnostics);
+                }
+                ModuleTypeSignatureMemberDeclaration::DefaultFunction(f) => {
+                    self.translate_compound(&mut f.body, diagnostics);
+                }
+                _ => {
+                    // NO ACTION REQUIRED
+                }
+            }
+        }
+    }
+
+    fn translate_module(&self, module: &mut Module, diagnostics: &mut Diagnostics) {
+        self.retain(
+            &mut module.directives,
+            |directive| match directive {
+                ModuleDirective::Import(import) => Some(&mut import.attributes),
+                ModuleDirective::Extend(extend) => Some(&mut extend.attributes),
+            },
+            diagnostics,
+        );
+        self.retain(
+            &mut module.members,
+            |decl| match decl {
+                ModuleMemberDeclaration::Declaration(d) => Some(&mut d.attributes),
+                ModuleMemberDeclaration::Alias(a) => Some(&mut a.attributes),
+                ModuleMemberDeclaration::Struct(s) => Some(&mut s.attributes),
+                ModuleMemberDeclaration::Function(f) => Some(&mut f.attributes),
+                ModuleMemberDeclaration::ConstAssert(c) => Some(&mut c.attributes),
+                ModuleMemberDeclaration::Module(m) => Some(&mut m.attributes),
+                ModuleMemberDeclaration::Interface(i) => Some(&mut i.attributes),
+                ModuleMemberDeclaration::Void | ModuleMemberDeclaration::Error(_) => None,
+            },
+            diagnostics,
+        );
+        for decl in module.members.iter_mut() {
+            match decl.as_mut() {
+                ModuleMemberDeclaration::Struct(s) => {
+                    self.retain(&mut s.members, |m| Some(&mut m.attributes), diagnostics);
+                }
+                ModuleMemberDeclaration::Function(f) => {
+                    self.translate_compound(&mut f.body, diagnostics);
+                }
+                ModuleMemberDeclaration::Module(m) => self.translate_module(m, diagnostics),
+                ModuleMemberDeclaration::Interface(i) => self.translate_interface(i, diagnostics),
+                _ => {
+                    // NO ACTION REQUIRED
+                }
+            }
+        }
+    }
+
+    pub fn translate_mut(
+        &self,
+        translation_unit: &mut TranslationUnit,
+        diagnostics: &mut Diagnostics,
+    ) {
+        self.retain(
+            &mut translation_unit.global_directives,
+            |directive| match directive {
+                GlobalDirective::Import(import) => Some(&mut import.attributes),
+                GlobalDirective::Extend(extend) => Some(&mut extend.attributes),
+                _ => None,
+            },
+            diagnostics,
+        );
+        self.retain(
+            &mut translation_unit.global_declarations,
+            |decl| match decl {
+                GlobalDeclaration::Declaration(d) => Some(&mut d.attributes),
+                GlobalDeclaration::Alias(a) => Some(&mut a.attributes),
+                GlobalDeclaration::Struct(s) => Some(&mut s.attributes),
+                GlobalDeclaration::Function(f) => Some(&mut f.attributes),
+                GlobalDeclaration::ConstAssert(c) => Some(&mut c.attributes),
+                GlobalDeclaration::Module(m) => Some(&mut m.attributes),
+                GlobalDeclaration::Interface(i) => Some(&mut i.attributes),
+                GlobalDeclaration::Void | GlobalDeclaration::Error(_) => None,
+            },
+            diagnostics,
+        );
+        for decl in translation_unit.global_declarations.iter_mut() {
+            match decl.as_mut() {
+                GlobalDeclaration::Struct(s) => {
+                    self.retain(&mut s.members, |m| Some(&mut m.attributes), diagnostics);
+                }
+                GlobalDeclaration::Function(f) => {
+                    self.translate_compound(&mut f.body, diagnostics);
+                }
+                GlobalDeclaration::Module(m) => self.translate_module(m, diagnostics),
+                GlobalDeclaration::Interface(i) => self.translate_interface(i, diagnostics),
+                _ => {
+                    // NO ACTION REQUIRED
+                }
+            }
+        }
+    }
+}
+
+impl CompilerPass for ConditionalTranslator {
+    fn apply_mut(&mut self, translation_unit: &mut TranslationUnit) -> CompilerPassResult {
+        let mut diagnostics = Diagnostics::default();
+        self.apply_mut_with_diagnostics(translation_unit, &mut diagnostics)?;
+        diagnostics.first_error()
+    }
+
+    fn apply_mut_with_diagnostics(
+        &mut self,
+        translation_unit: &mut TranslationUnit,
+        diagnostics: &mut Diagnostics,
+    ) -> CompilerPassResult {
+        self.translate_mut(translation_unit, diagnostics);
+        Ok(())
+    }
+}
@@ -0,0 +1,2 @@
+pub mod conditional;
+pub use conditional::*;
@@ -0,0 +1,439 @@
+use std::collections::{HashMap, HashSet};
+
+use mew_parse::{
+    span::Spanned,
+    syntax::{
+        Attribute, CaseSelector, CompoundStatement, ConstAssert, Declaration, Expression, Function,
+        GlobalDeclaration, Module, ModuleMemberDeclaration, PathPart, Statement, Struct,
+        TranslationUnit, TypeExpression,
+    },
+};
+use mew_types::{CompilerPass, CompilerPassResult, mangling::maybe_mangle_template_args_if_needed};
+
+/// Removes the declarations that the entry points do not use.
+///
+/// Runs after dealiasing, when declarations are referred to by their full paths. The
+/// functions, types and globals used by the entry points are found by walking them and what
+/// they use. The const assertions of a module are kept, and walked, if any of its declarations
+/// is used. Modules left empty are removed.
+#[derive(Debug, Default, Clone)]
+pub struct DeadCodeEliminator {
+    /// The dealiased paths of the entry points. If a path names a module, all of its
+    /// declarations are kept.
+    pub entrypoints: Vec<Vec<PathPart>>,
+}
+
+/// The names of the parts of the path of a declaration, with their template arguments.
+type ItemPath = Vec<String>;
+
+#[derive(Clone, Copy)]
+enum Item<'a> {
+    Declaration(&'a Declaration),
+    Struct(&'a Struct),
+    Function(&'a Function),
+}
+
+/// Finds the declarations used by the entry points.
+#[derive(Default)]
+struct Reachability<'a> {
+    items: HashMap<ItemPath, Item<'a>>,
+    /// The const assertions of each module.
+    const_asserts: HashMap<ItemPath, Vec<&'a ConstAssert>>,
+    used_items: HashSet<ItemPath>,
+    /// The modules with a used declaration, and the modules that contain them.
+    used_modules: HashSet<ItemPath>,
+    /// The used declarations that have not been walked yet.
+    queue: Vec<ItemPath>,
+}
+
+impl<'a> Reachability<'a> {
+    fn new(translation_unit: &'a TranslationUnit) -> Self {
+        let mut reachability = Self::default();
+        for decl in translation_unit.global_declarations.iter() {
+            match decl.as_ref() {
+                GlobalDeclaration::Declaration(d) => {
+                    reachability.add_item(&[], &d.name, Item::Declaration(d));
+                }
+                GlobalDeclaration::Struct(s) => {
+                    reachability.add_item(&[], &s.name, Item::Struct(s));
+                }
+                GlobalDeclaration::Function(f) => {
+                    reachability.add_item(&[], &f.name, Item::Function(f));
+                }
+                GlobalDeclaration::ConstAssert(c) => {
+                    reachability
+                        .const_asserts
+                        .entry(vec![])
+                        .or_default()
+                        .push(c);
+                }
+                GlobalDeclaration::Module(m) => {
+                    reachability.add_module(&[], m);
+                }
+                GlobalDeclaration::Void
+                | GlobalDeclaration::Error(_)
+                | GlobalDeclaration::Alias(_)
+                | GlobalDeclaration::Interface(_) => {
+                    // NO ACTION REQUIRED
+                }
+            }
+        }
+        reachability
+    }
+
+    fn add_item(&mut self, module_path: &[String], name: &str, item: Item<'a>) {
+        let mut path = module_path.to_vec();
+        path.push(name.to_string());
+        self.items.insert(path, item);
+    }
+
+    fn add_module(&mut self, module_path: &[String], module: &'a Module) {
+        let mut path = module_path.to_vec();
+        path.push(module.name.value.clone());
+        for decl in module.members.iter() {
+            match decl.as_ref() {
+                ModuleMemberDeclaration::Declaration(d) => {
+                    self.add_item(&path, &d.name, Item::Declaration(d));
+                }
+                ModuleMemberDeclaration::Struct(s) => {
+                    self.add_item(&path, &s.name, Item::Struct(s));
+                }
+                ModuleMemberDeclaration::Function(f) => {
+                    self.add_item(&path, &f.name, Item::Function(f));
+                }
+                ModuleMemberDeclaration::ConstAssert(c) => {
+                    self.const_asserts.entry(path.clone()).or_default().push(c);
+                }
+                ModuleMemberDeclaration::Module(m) => {
+                    self.add_module(&path, m);
+                }
+                ModuleMemberDeclaration::Void
+                | ModuleMemberDeclaration::Error(_)
+                | ModuleMemberDeclaration::Alias(_)
+                | ModuleMemberDeclaration::Interface(_) => {
+                    // NO ACTION REQUIRED
+                }
+            }
+        }
+    }
+
+    /// Marks the declarations at the paths of the entry points as used, and everything they
+    /// use.
+    fn walk_entrypoints(&mut self, entrypoints: &[Vec<PathPart>]) {
+        // the const assertions of the root are always kept
+        self.use_module(vec![]);
+        for entrypoint in entrypoints {
+            let path = Self::item_path(entrypoint);
+            if self.items.contains_key(&path) {
+                self.use_item(path);
+            } else {
+                let members = self
+                    .items
+                    .keys()
+                    .filter(|item| item.len() > path.len() && item.starts_with(&path))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                for member in members {
+                    self.use_item(member);
+                }
+            }
+        }
+        while let Some(path) = self.queue.pop() {
+            match self.items[&path] {
+                Item::Declaration(d) => self.walk_declaration(d),
+                Item::Struct(s) => self.walk_struct(s),
+                Item::Function(f) => self.walk_function(f),
+            }
+        }
+    }
+
+    fn item_path(path: &[PathPart]) -> ItemPath {
+        path.iter()
+            .map(maybe_mangle_template_args_if_needed)
+            .collect()
+    }
+
+    fn use_item(&mut self, path: ItemPath) {
+        if self.items.contains_key(&path) && !self.used_items.contains(&path) {
+            self.use_module(path[..path.len() - 1].to_vec());
+            self.used_items.insert(path.clone());
+            self.queue.push(path);
+        }
+    }
+
+    fn use_module(&mut self, mut path: ItemPath) {
+        loop {
+            // the modules that contain a used module are used already
+            if !self.used_modules.insert(path.clone()) {
+                return;
+            }
+            for const_assert in self.const_asserts.get(&path).cloned().unwrap_or_default() {
+                self.walk_expression(&const_assert.expression);
+            }
+            if path.pop().is_none() {
+                return;
+            }
+        }
+    }
+
+    fn walk_path(&mut self, path: &[PathPart]) {
+        for part in path.iter() {
+            for arg in part.template_args.iter().flatten() {
+                self.walk_expression(&arg.expression);
+            }
+        }
+        // single-part paths may also name local variables, which keep a root declaration
+        // of the same name
+        self.use_item(Self::item_path(path));
+    }
+
+    fn walk_type(&mut self, typ: &TypeExpression) {
+        self.walk_path(&typ.path);
+    }
+
+    fn walk_attributes(&mut self, attributes: &[Spanned<Attribute>]) {
+        for attribute in attributes.iter() {
+            for argument in attribute.arguments.iter().flatten() {
+                self.walk_expression(argument);
+            }
+        }
+    }
+
+    fn walk_expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Literal(_) => {
+                // No action required
+            }
+            Expression::Parenthesized(spanned) => {
+                self.walk_expression(spanned);
+            }
+            Expression::NamedComponent(named_component_expression) => {
+                self.walk_expression(&named_component_expression.base);
+            }
+            Expression::Indexing(indexing_expression) => {
+                self.walk_expression(&indexing_expression.base);
+                self.walk_expression(&indexing_expression.index);
+            }
+            Expression::Unary(unary_expression) => {
+                self.walk_expression(&unary_expression.operand);
+            }
+            Expression::Binary(binary_expression) => {
+                self.walk_expression(&binary_expression.left);
+                self.walk_expression(&binary_expression.right);
+            }
+            Expression::FunctionCall(function_call_expression) => {
+                self.walk_path(&function_call_expression.path);
+                for arg in function_call_expression.arguments.iter() {
+                    self.walk_expression(arg);
+                }
+            }
+            Expression::Identifier(identifier_expression) => {
+                self.walk_path(&identifier_expression.path);
+            }
+            Expression::Type(type_expression) => {
+                self.walk_type(type_expression);
+            }
+        }
+    }
+
+    fn walk_compound_statement(&mut self, compound: &CompoundStatement) {
+        self.walk_attributes(&compound.attributes);
+        for statement in compound.statements.iter() {
+            self.walk_statement(statement);
+        }
+    }
+
+    fn walk_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Void
+            | Statement::Error(_)
+            | Statement::Break
+            | Statement::Continue
+            | Statement::Discard => {
+                // No action required
+            }
+            Statement::Compound(compound_statement) => {
+                self.walk_compound_statement(compound_statement);
+            }
+            Statement::Assignment(assignment_statement) => {
+                self.walk_expression(&assignment_statement.lhs);
+                self.walk_expression(&assignment_statement.rhs);
+            }
+            Statement::Increment(expression) | Statement::Decrement(expression) => {
+                self.walk_expression(expression);
+            }
+            Statement::If(iff) => {
+                self.walk_attributes(&iff.attributes);
+                self.walk_expression(&iff.if_clause.0);
+                self.walk_compound_statement(&iff.if_clause.1);
+                for (else_if_expr, else_if_statements) in iff.else_if_clauses.iter() {
+                    self.walk_expression(else_if_expr);
+                    self.walk_compound_statement(else_if_statements);
+                }
+                if let Some(else_clause) = iff.else_clause.as_ref() {
+                    self.walk_compound_statement(else_clause);
+                }
+            }
+            Statement::Switch(s) => {
+                self.walk_attributes(&s.attributes);
+                self.walk_expression(&s.expression);
+                for clause in s.clauses.iter() {
+                    for selector in clause.case_selectors.iter() {
+                        if let CaseSelector::Expression(e) = selector.as_ref() {
+                            self.walk_expression(e);
+                        }
+                    }
+                    self.walk_compound_statement(&clause.body);
+                }
+            }
+            Statement::Loop(l) => {
+                self.walk_attributes(&l.attributes);
+                self.walk_compound_statement(&l.body);
+                if let Some(cont) = l.continuing.as_ref() {
+                    self.walk_compound_statement(&cont.body);
+                    if let Some(expr) = cont.break_if.as_ref() {
+                        self.walk_expression(expr);
+                    }
+                }
+            }
+            Statement::For(f) => {
+                self.walk_attributes(&f.attributes);
+                if let Some(init) = f.initializer.as_ref() {
+                    self.walk_statement(init);
+                }
+                if let Some(cond) = f.condition.as_ref() {
+                    self.walk_expression(cond);
+                }
+                if let Some(update) = f.update.as_ref() {
+                    self.walk_statement(update);
+                }
+                self.walk_compound_statement(&f.body);
+            }
+            Statement::While(w) => {
+                self.walk_attributes(&w.attributes);
+                self.walk_expression(&w.condition);
+                self.walk_compound_statement(&w.body);
+            }
+            Statement::Return(spanned) => {
+                if let Some(expr) = spanned.as_ref() {
+                    self.walk_expression(expr);
+                }
+            }
+            Statement::FunctionCall(function_call_expression) => {
+                self.walk_path(&function_call_expression.path);
+                for arg in function_call_expression.arguments.iter() {
+                    self.walk_expression(arg);
+                }
+            }
+            Statement::ConstAssert(const_assert) => {
+                self.walk_expression(&const_assert.expression);
+            }
+            Statement::Declaration(declaration_statement) => {
+                self.walk_declaration(&declaration_statement.declaration);
+                for statement in declaration_statement.statements.iter() {
+                    self.walk_statement(statement);
+                }
+            }
+        }
+    }
+
+    fn walk_declaration(&mut self, decl: &Declaration) {
+        self.walk_attributes(&decl.attributes);
+        for arg in decl.template_args.iter().flatten() {
+            self.walk_expression(&arg.expression);
+        }
+        if let Some(typ) = decl.typ.as_ref() {
+            self.walk_type(typ);
+        }
+        if let Some(init) = decl.initializer.as_ref() {
+            self.walk_expression(init);
+        }
+    }
+
+    fn walk_struct(&mut self, strct: &Struct) {
+        for m in strct.members.iter() {
+            self.walk_attributes(&m.attributes);
+            self.walk_type(&m.typ);
+        }
+    }
+
+    fn walk_function(&mut self, func: &Function) {
+        self.walk_attributes(&func.attributes);
+        for p in func.parameters.iter() {
+            self.walk_attributes(&p.attributes);
+            self.walk_type(&p.typ);
+        }
+        self.walk_attributes(&func.return_attributes);
+        if let Some(ret) = func.return_type.as_ref() {
+            self.walk_type(ret);
+        }
+        self.walk_compound_statement(&func.body);
+    }
+}
+
+/// The declarations and modules to keep.
+struct Used {
+    items: HashSet<ItemPath>,
+    modules: HashSet<ItemPath>,
+}
+
+impl Used {
+    fn is_used(&self, module_path: &[String], name: &str) -> bool {
+        let mut path = module_path.to_vec();
+        path.push(name.to_string());
+        self.items.contains(&path)
+    }
+
+    /// Removes the unused members of the module, and returns whether any are left.
+    fn retain_module(&self, module_path: &[String], module: &mut Module) -> bool {
+        let mut path = module_path.to_vec();
+        path.push(module.name.value.clone());
+        module.members.retain_mut(|decl| match decl.as_mut() {
+            ModuleMemberDeclaration::Declaration(d) => self.is_used(&path, &d.name),
+            ModuleMemberDeclaration::Struct(s) => self.is_used(&path, &s.name),
+            ModuleMemberDeclaration::Function(f) => self.is_used(&path, &f.name),
+            ModuleMemberDeclaration::ConstAssert(_) => self.modules.contains(&path),
+            ModuleMemberDeclaration::Module(m) => self.retain_module(&path, m),
+            ModuleMemberDeclaration::Void
+            | ModuleMemberDeclaration::Error(_)
+            | ModuleMemberDeclaration::Alias(_)
+            | ModuleMemberDeclaration::Interface(_) => false,
+        });
+        !module.members.is_empty()
+    }
+}
+
+impl DeadCodeEliminator {
+    fn used(&self, translation_unit: &TranslationUnit) -> Used {
+        let mut reachability = Reachability::new(translation_unit);
+        reachability.walk_entrypoints(&self.entrypoints);
+        Used {
+            items: reachability.used_items,
+            modules: reachability.used_modules,
+        }
+    }
+
+    pub fn eliminate_mut(&self, translation_unit: &mut TranslationUnit) {
+        let used = self.used(translation_unit);
+        translation_unit
+            .global_declarations
+            .retain_mut(|decl| match decl.as_mut() {
+                GlobalDeclaration::Declaration(d) => used.is_used(&[], &d.name),
+                GlobalDeclaration::Struct(s) => used.is_used(&[], &s.name),
+                GlobalDeclaration::Function(f) => used.is_used(&[], &f.name),
+                GlobalDeclaration::ConstAssert(_) => true,
+                GlobalDeclaration::Module(m) => used.retain_module(&[], m),
+                GlobalDeclaration::Void
+                | GlobalDeclaration::Error(_)
+                | GlobalDeclaration::Alias(_)
+                | GlobalDeclaration::Interface(_) => false,
+            });
+    }
+}
+
+impl CompilerPass for DeadCodeEliminator {
+    fn apply_mut(&mut self, translation_unit: &mut TranslationUnit) -> CompilerPassResult {
+        self.eliminate_mut(translation_unit);
+        Ok(())
+    }
+}
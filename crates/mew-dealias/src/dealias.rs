@@ -227,7 +227,9 @@ impl Dealiaser {
     ) -> Result<(), Box<CompilerPassError>> {
         for decl in module.members.iter_mut() {
             match decl.as_mut() {
-                ModuleMemberDeclaration::Void => {
+                ModuleMemberDeclaration::Void
+                | ModuleMemberDeclaration::Error(_)
+                | ModuleMemberDeclaration::Interface(_) => {
                     // NO ACTION REQUIRED REQUIRED
                 }
                 ModuleMemberDeclaration::Declaration(decl) => {
@@ -298,7 +300,7 @@ impl Dealiaser {
         tree: &AliasTree,
     ) -> Result<(), Box<CompilerPassError>> {
         match statement {
-            Statement::Void => {
+            Statement::Void | Statement::Error(_) => {
                 // No action required
             }
             Statement::Compound(compound_statement) => {
@@ -507,7 +509,9 @@ impl Dealiaser {
     ) -> Result<(), Box<CompilerPassError>> {
         for decl in translation_unit.global_declarations.iter_mut() {
             match decl.as_mut() {
-                GlobalDeclaration::Void => {
+                GlobalDeclaration::Void
+                | GlobalDeclaration::Error(_)
+                | GlobalDeclaration::Interface(_) => {
                     // NO ACTION REQUIRED
                 }
                 GlobalDeclaration::Declaration(decl) => {
@@ -534,6 +538,27 @@ impl Dealiaser {
     }
 }
 
+impl Dealiaser {
+    /// Resolves the aliases of the translation unit in paths, as the pass resolves them in
+    /// the paths used by declarations. This is how the paths of the entry points are found
+    /// after their aliases are removed.
+    pub fn dealias_paths(
+        translation_unit: &TranslationUnit,
+        paths: &[Vec<PathPart>],
+    ) -> Result<Vec<Vec<PathPart>>, Box<CompilerPassError>> {
+        let mut tree = AliasTree::default();
+        Self::populate_aliases_from_translation_unit(&mut translation_unit.clone(), &mut tree)?;
+        paths
+            .iter()
+            .map(|path| {
+                let mut path = Spanned::new(path.clone(), 0..0);
+                Self::replace_path_with_alias(&mut path, &tree)?;
+                Ok(path.value)
+            })
+            .collect()
+    }
+}
+
 impl CompilerPass for Dealiaser {
     fn apply_mut(
         &mut self,
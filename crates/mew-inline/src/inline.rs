@@ -96,7 +96,7 @@ impl<'a> Parent<'a> {
         statement: &mut Statement,
     ) -> Result<(), Box<CompilerPassError>> {
         match statement {
-            Statement::Void => Ok(()),
+            Statement::Void | Statement::Error(_) => Ok(()),
             Statement::Compound(compound_statement) => {
                 self.inline_compound_statement(compound_statement)
             }
@@ -427,7 +427,9 @@ impl<'a> Parent<'a> {
                 {
                     let mut parent: Parent<'_> = Parent::Module(m);
                     match &mut member.value {
-                        ModuleMemberDeclaration::Void => {}
+                        ModuleMemberDeclaration::Void
+                        | ModuleMemberDeclaration::Error(_)
+                        | ModuleMemberDeclaration::Interface(_) => {}
                         ModuleMemberDeclaration::Declaration(declaration) => {
                             parent.declaration_to_inline(declaration)?;
                         }
@@ -479,7 +481,9 @@ impl<'a> Parent<'a> {
                 {
                     let mut parent: Parent<'_> = Parent::TranslationUnit(t);
                     match &mut member.value {
-                        GlobalDeclaration::Void => {}
+                        GlobalDeclaration::Void
+                        | GlobalDeclaration::Error(_)
+                        | GlobalDeclaration::Interface(_) => {}
                         GlobalDeclaration::Declaration(declaration) => {
                             parent.declaration_to_inline(declaration)?;
                         }
@@ -1,70 +1,269 @@
 use mew_parse::syntax::{
-    Alias, CompoundStatement, ConstAssert, Declaration, Expression, Function, GlobalDeclaration,
-    IdentifierExpression, Module, ModuleMemberDeclaration, PathPart, Statement, Struct,
-    TranslationUnit, TypeExpression,
+    Alias, Attribute, CompoundStatement, ConstAssert, Declaration, Expression, Function,
+    GlobalDeclaration, IdentifierExpression, LiteralExpression, Module, ModuleMemberDeclaration,
+    PathPart, Statement, Struct, TranslationUnit, TypeExpression,
 };
-use mew_types::{CompilerPass, CompilerPassResult, mangling::mangle_path};
+use std::{collections::HashMap, sync::Arc};
 
-#[derive(Debug, Default, Clone, Copy)]
-pub struct Mangler;
+use mew_parse::span::{Span, Spanned};
+use mew_types::{
+    CompilerPass, CompilerPassError, CompilerPassResult, Diagnostics,
+    mangling::{
+        self, ManglingStrategy, ReadableMangling, mangle_path, mangle_path_with, mangle_str,
+    },
+};
+
+/// Renames the declarations of the translation unit after their paths, so that they can be
+/// flattened into a single WGSL module.
+///
+/// Declarations annotated with `@export("name")` are named `name` instead, and those annotated
+/// with a bare `@export` keep their own names. Entry points keep their names if the strategy
+/// gives them one, like [`PreserveEntryPoints`] does.
+#[derive(Debug, Clone)]
+pub struct Mangler {
+    /// Names the declarations.
+    pub strategy: Arc<dyn ManglingStrategy>,
+    /// The names of the declarations that are not mangled, by the text of their paths.
+    fixed_names: HashMap<String, String>,
+    /// The spans of the `@export` attributes whose argument is not a single string.
+    malformed_exports: Vec<Span>,
+}
+
+impl Default for Mangler {
+    fn default() -> Self {
+        Self::new(Arc::new(ReadableMangling))
+    }
+}
 
 #[derive(Debug, PartialEq, Clone)]
 struct ModulePath(im::Vector<PathPart>);
 
 impl Mangler {
-    fn mangle_name(name: &mut String, path: ModulePath) {
-        let mut path: Vec<PathPart> = path.0.into_iter().collect();
-        mangle_path(&mut path);
-        let mut result = String::new();
-        result.push_str(path[0].name.value.as_str());
-        if !result.is_empty() {
-            result.push('_');
+    pub fn new(strategy: Arc<dyn ManglingStrategy>) -> Self {
+        Self {
+            strategy,
+            fixed_names: HashMap::new(),
+            malformed_exports: Vec::new(),
+        }
+    }
+
+    /// Mangles the name of a declaration in the module at `path`.
+    ///
+    /// Single-part paths name root declarations or local variables, which the mangler cannot
+    /// tell apart, so they are encoded alike with [`mangle_str`] whatever the strategy.
+    fn mangle_name(&self, name: &mut String, path: ModulePath) {
+        let text = Self::path_text(name, &path);
+        *name = if let Some(fixed_name) = self.fixed_names.get(&text) {
+            fixed_name.clone()
+        } else if path.0.is_empty() {
+            mangle_str(name)
+        } else {
+            self.strategy.mangle(&text)
+        };
+    }
+
+    /// Mangles a path to a declaration, or to a local variable or parameter in `locals`.
+    fn mangle_path(&self, path: &mut Vec<PathPart>, locals: &im::HashSet<String>) {
+        // fixed names are looked up by path, which a local can shadow
+        let fixed_name = match path.as_slice() {
+            [part] if locals.contains(&part.name.value) => None,
+            _ => self
+                .fixed_names
+                .get(&mangling::path_text(path.iter()))
+                .cloned(),
+        };
+        if path.len() == 1 {
+            mangle_path(path);
+        } else {
+            mangle_path_with(path, self.strategy.as_ref());
+        }
+        if let (Some(fixed_name), Some(part)) = (fixed_name, path.first_mut()) {
+            part.name.value = fixed_name;
+        }
+    }
+
+    /// The text of the path of a declaration in the module at `path`.
+    fn path_text(name: &str, path: &ModulePath) -> String {
+        let mut text = mangling::path_text(path.0.iter());
+        if !text.is_empty() {
+            text.push_str("::");
+        }
+        text.push_str(name);
+        text
+    }
+
+    fn is_entry_point(attributes: &[Spanned<Attribute>]) -> bool {
+        attributes
+            .iter()
+            .any(|attribute| matches!(attribute.name.as_str(), "vertex" | "fragment" | "compute"))
+    }
+
+    /// The name given by an `@export` attribute, or `None` for a bare `@export`. Fails with
+    /// the span of the attribute if its argument is not a single string.
+    fn export_name(export: &Spanned<Attribute>) -> Result<Option<&str>, Span> {
+        match export.arguments.as_deref() {
+            None => Ok(None),
+            Some([argument]) => match &argument.value {
+                Expression::Literal(literal) => match &literal.value {
+                    LiteralExpression::String(export_name) => Ok(Some(export_name)),
+                    _ => Err(export.span()),
+                },
+                _ => Err(export.span()),
+            },
+            Some(_) => Err(export.span()),
+        }
+    }
+
+    /// The name a declaration keeps instead of being mangled: the name given by its `@export`
+    /// attribute, or the name the strategy gives entry points.
+    ///
+    /// A malformed `@export` is recorded, and the declaration keeps its own name.
+    fn fixed_name(
+        &mut self,
+        attributes: &[Spanned<Attribute>],
+        name: &str,
+        path: &ModulePath,
+    ) -> Option<String> {
+        if let Some(export) = attributes
+            .iter()
+            .find(|attribute| attribute.name.value == "export")
+        {
+            return Some(match Self::export_name(export) {
+                Ok(export_name) => export_name.unwrap_or(name).to_string(),
+                Err(span) => {
+                    self.malformed_exports.push(span);
+                    name.to_string()
+                }
+            });
+        }
+        if Self::is_entry_point(attributes) {
+            return self
+                .strategy
+                .entry_point_name(&Self::path_text(name, path), name);
+        }
+        None
+    }
+
+    fn collect_fixed_name(
+        &mut self,
+        attributes: &[Spanned<Attribute>],
+        name: &str,
+        path: &ModulePath,
+    ) {
+        if let Some(fixed_name) = self.fixed_name(attributes, name, path) {
+            self.fixed_names
+                .insert(Self::path_text(name, path), fixed_name);
         }
-        result.push_str(&name.replace('_', "__"));
-        *name = result;
     }
 
-    fn mangle_compound(compound: &mut CompoundStatement) {
+    fn collect_module_fixed_names(&mut self, m: &Module, mut path: ModulePath) {
+        path.0.push_back(PathPart {
+            name: m.name.clone(),
+            template_args: None,
+            inline_template_args: None,
+        });
+        for decl in m.members.iter() {
+            match decl.as_ref() {
+                ModuleMemberDeclaration::Module(module) => {
+                    self.collect_module_fixed_names(module, path.clone());
+                }
+                ModuleMemberDeclaration::Function(f) => {
+                    self.collect_fixed_name(&f.attributes, &f.name, &path);
+                }
+                ModuleMemberDeclaration::Declaration(d) => {
+                    self.collect_fixed_name(&d.attributes, &d.name, &path);
+                }
+                ModuleMemberDeclaration::Struct(s) => {
+                    self.collect_fixed_name(&s.attributes, &s.name, &path);
+                }
+                ModuleMemberDeclaration::Alias(a) => {
+                    self.collect_fixed_name(&a.attributes, &a.name, &path);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The mangler, with the fixed names of the declarations of the translation unit.
+    fn with_fixed_names(&self, translation_unit: &TranslationUnit) -> Self {
+        let mut mangler = Self::new(self.strategy.clone());
+        let path = ModulePath(im::Vector::new());
+        for decl in translation_unit.global_declarations.iter() {
+            match decl.as_ref() {
+                GlobalDeclaration::Module(module) => {
+                    mangler.collect_module_fixed_names(module, path.clone());
+                }
+                GlobalDeclaration::Function(f) => {
+                    mangler.collect_fixed_name(&f.attributes, &f.name, &path);
+                }
+                GlobalDeclaration::Declaration(d) => {
+                    mangler.collect_fixed_name(&d.attributes, &d.name, &path);
+                }
+                GlobalDeclaration::Struct(s) => {
+                    mangler.collect_fixed_name(&s.attributes, &s.name, &path);
+                }
+                GlobalDeclaration::Alias(a) => {
+                    mangler.collect_fixed_name(&a.attributes, &a.name, &path);
+                }
+                _ => {}
+            }
+        }
+        mangler
+    }
+
+    fn remove_export_attributes(attributes: &mut Vec<Spanned<Attribute>>) {
+        attributes.retain(|attribute| attribute.name.value != "export");
+    }
+
+    /// Whether a single-part path names a builtin type, which keeps its name.
+    fn is_builtin_type(name: &str) -> bool {
+        let builtin_tokens = mew_types::builtins::get_builtin_tokens();
+        builtin_tokens.type_generators.iter().any(|ty| ty == name)
+            || builtin_tokens.type_aliases.contains_key(name)
+            || builtin_tokens.primitive_types.iter().any(|ty| ty == name)
+    }
+
+    fn mangle_compound(&self, compound: &mut CompoundStatement, locals: &im::HashSet<String>) {
         for c in compound.statements.iter_mut() {
-            Self::mangle_statement(c);
+            self.mangle_statement(c, locals);
         }
     }
 
-    fn mangle_statement(statement: &mut Statement) {
+    fn mangle_statement(&self, statement: &mut Statement, locals: &im::HashSet<String>) {
         match statement {
-            Statement::Void => {
+            Statement::Void | Statement::Error(_) => {
                 // DO NOTHING
             }
-            Statement::Compound(c) => Self::mangle_compound(c),
+            Statement::Compound(c) => self.mangle_compound(c, locals),
             Statement::Assignment(a) => {
-                Self::mangle_expression(&mut a.lhs);
-                Self::mangle_expression(&mut a.rhs);
+                self.mangle_expression(&mut a.lhs, locals);
+                self.mangle_expression(&mut a.rhs, locals);
             }
             Statement::Increment(i) => {
-                Self::mangle_expression(i);
+                self.mangle_expression(i, locals);
             }
             Statement::Decrement(d) => {
-                Self::mangle_expression(d);
+                self.mangle_expression(d, locals);
             }
             Statement::If(iff) => {
-                Self::mangle_expression(&mut iff.if_clause.0);
+                self.mangle_expression(&mut iff.if_clause.0, locals);
                 for c in iff.if_clause.1.statements.iter_mut() {
-                    Self::mangle_statement(c);
+                    self.mangle_statement(c, locals);
                 }
                 if let Some(else_clause) = iff.else_clause.as_mut() {
                     for c in else_clause.statements.iter_mut() {
-                        Self::mangle_statement(c);
+                        self.mangle_statement(c, locals);
                     }
                 }
                 for (elif_expr, elif_statment) in iff.else_if_clauses.iter_mut() {
                     for c in elif_statment.statements.iter_mut() {
-                        Self::mangle_statement(c);
+                        self.mangle_statement(c, locals);
                     }
-                    Self::mangle_expression(elif_expr);
+                    self.mangle_expression(elif_expr, locals);
                 }
             }
             Statement::Switch(s) => {
-                Self::mangle_expression(&mut s.expression);
+                self.mangle_expression(&mut s.expression, locals);
                 for c in s.clauses.iter_mut() {
                     for select in c.case_selectors.iter_mut() {
                         match select.as_mut() {
@@ -72,47 +271,57 @@ impl Mangler {
                                 // DO NOTHING
                             }
                             mew_parse::syntax::CaseSelector::Expression(expr) => {
-                                Self::mangle_expression(expr);
+                                self.mangle_expression(expr, locals);
                             }
                         }
                     }
                     for c in c.body.statements.iter_mut() {
-                        Self::mangle_statement(c);
+                        self.mangle_statement(c, locals);
                     }
                 }
             }
             Statement::Loop(l) => {
                 for c in l.body.statements.iter_mut() {
-                    Self::mangle_statement(c);
+                    self.mangle_statement(c, locals);
                 }
                 if let Some(cont) = l.continuing.as_mut() {
+                    // the continuing statement is in the scope of the body
+                    let mut locals = locals.clone();
+                    for c in l.body.statements.iter() {
+                        Self::collect_locals(c, &mut locals);
+                    }
                     for c in cont.body.statements.iter_mut() {
-                        Self::mangle_statement(c);
+                        self.mangle_statement(c, &locals);
+                    }
+                    for c in cont.body.statements.iter() {
+                        Self::collect_locals(c, &mut locals);
                     }
                     if let Some(break_if) = cont.break_if.as_mut() {
-                        Self::mangle_expression(break_if);
+                        self.mangle_expression(break_if, &locals);
                     }
                 }
             }
             Statement::For(f) => {
+                let mut locals = locals.clone();
+                if let Some(statement) = f.initializer.as_mut() {
+                    self.mangle_statement(statement.as_mut(), &locals);
+                    Self::collect_locals(statement, &mut locals);
+                }
                 for c in f.body.statements.iter_mut() {
-                    Self::mangle_statement(c);
+                    self.mangle_statement(c, &locals);
                 }
                 if let Some(cond) = f.condition.as_mut() {
-                    Self::mangle_expression(cond);
-                }
-                if let Some(statement) = f.initializer.as_mut() {
-                    Self::mangle_statement(statement.as_mut());
+                    self.mangle_expression(cond, &locals);
                 }
                 if let Some(update) = f.update.as_mut() {
-                    Self::mangle_statement(update.as_mut());
+                    self.mangle_statement(update.as_mut(), &locals);
                 }
             }
             Statement::While(w) => {
                 for c in w.body.statements.iter_mut() {
-                    Self::mangle_statement(c);
+                    self.mangle_statement(c, locals);
                 }
-                Self::mangle_expression(&mut w.condition);
+                self.mangle_expression(&mut w.condition, locals);
             }
             Statement::Break => {
                 // DO NOTHING
@@ -122,57 +331,73 @@ impl Mangler {
             }
             Statement::Return(ret) => {
                 if let Some(ret) = ret.as_mut() {
-                    Self::mangle_expression(ret);
+                    self.mangle_expression(ret, locals);
                 }
             }
             Statement::Discard => {
                 // DO NOTHING
             }
             Statement::FunctionCall(f) => {
-                mangle_path(&mut f.path);
+                self.mangle_path(&mut f.path, locals);
                 for arg in f.arguments.iter_mut() {
-                    Self::mangle_expression(arg);
+                    self.mangle_expression(arg, locals);
                 }
             }
             Statement::ConstAssert(assrt) => {
-                Self::mangle_expression(&mut assrt.expression);
+                self.mangle_expression(&mut assrt.expression, locals);
             }
             Statement::Declaration(decl) => {
+                let mut locals = locals.clone();
+                locals.insert(decl.declaration.name.value.clone());
+                // local names are used by single-part paths, which are mangled like root names
+                decl.declaration.name.value = mangle_str(&decl.declaration.name);
                 if let Some(typ) = decl.declaration.typ.as_mut() {
-                    Self::mangle_type(typ);
+                    self.mangle_type(typ, &locals);
                 }
 
                 if let Some(init) = decl.declaration.initializer.as_mut() {
-                    Self::mangle_expression(init);
+                    self.mangle_expression(init, &locals);
                 }
+                // the statements following the declaration are nested in it
                 for statement in decl.statements.iter_mut() {
-                    Self::mangle_statement(statement);
+                    self.mangle_statement(statement, &locals);
                 }
             }
         }
     }
 
-    fn mangle_expression(expr: &mut Expression) {
+    /// Adds the names of the local declarations in a statement and in the statements nested
+    /// in it, which are in scope of the continuing statement of a loop.
+    fn collect_locals(statement: &Statement, locals: &mut im::HashSet<String>) {
+        if let Statement::Declaration(decl) = statement {
+            locals.insert(decl.declaration.name.value.clone());
+            for statement in decl.statements.iter() {
+                Self::collect_locals(statement, locals);
+            }
+        }
+    }
+
+    fn mangle_expression(&self, expr: &mut Expression, locals: &im::HashSet<String>) {
         match expr {
             Expression::Literal(_) => {
                 // DO NOTHING
             }
             Expression::Parenthesized(p) => {
-                Self::mangle_expression(p.as_mut());
+                self.mangle_expression(p.as_mut(), locals);
             }
             Expression::NamedComponent(n) => {
-                Self::mangle_expression(&mut n.base);
+                self.mangle_expression(&mut n.base, locals);
             }
             Expression::Indexing(idx) => {
-                Self::mangle_expression(&mut idx.base);
-                Self::mangle_expression(&mut idx.index);
+                self.mangle_expression(&mut idx.base, locals);
+                self.mangle_expression(&mut idx.index, locals);
             }
             Expression::Unary(u) => {
-                Self::mangle_expression(&mut u.operand);
+                self.mangle_expression(&mut u.operand, locals);
             }
             Expression::Binary(b) => {
-                Self::mangle_expression(&mut b.left);
-                Self::mangle_expression(&mut b.right);
+                self.mangle_expression(&mut b.left, locals);
+                self.mangle_expression(&mut b.right, locals);
             }
             Expression::FunctionCall(f) => {
                 let mut mangle_function_path = true;
@@ -183,110 +408,99 @@ impl Mangler {
                         .contains_key(&f.path[0].name.value.clone());
                 }
                 if mangle_function_path {
-                    mangle_path(&mut f.path);
+                    self.mangle_path(&mut f.path, locals);
                 } else if let Some(args) = f.path[0].template_args.as_mut() {
                     for arg in args {
-                        Self::mangle_expression(&mut arg.expression);
+                        self.mangle_expression(&mut arg.expression, locals);
                     }
                 }
 
                 for arg in f.arguments.iter_mut() {
-                    Self::mangle_expression(arg);
+                    self.mangle_expression(arg, locals);
                 }
             }
             Expression::Identifier(id) => {
-                Self::mangle_identifier_expression(id);
+                self.mangle_identifier_expression(id, locals);
             }
             Expression::Type(typ) => {
-                Self::mangle_type(typ);
+                self.mangle_type(typ, locals);
             }
         }
     }
 
-    fn mangle_type(typ: &mut TypeExpression) {
-        let mut mangle_type_path = true;
-        if typ.path.len() == 1 {
-            let builtin_tokens = mew_types::builtins::get_builtin_tokens();
-            mangle_type_path = !builtin_tokens
-                .type_generators
-                .contains(&typ.path[0].name.value.clone());
-            if mangle_type_path {
-                mangle_type_path = !builtin_tokens
-                    .type_aliases
-                    .contains_key(&typ.path[0].name.value.clone());
-            }
-        }
+    fn mangle_type(&self, typ: &mut TypeExpression, locals: &im::HashSet<String>) {
+        let mangle_type_path = typ.path.len() != 1 || !Self::is_builtin_type(&typ.path[0].name);
         if mangle_type_path {
-            mangle_path(&mut typ.path);
+            self.mangle_path(&mut typ.path, locals);
         } else if let Some(args) = typ.path[0].template_args.as_mut() {
             for arg in args {
-                Self::mangle_expression(&mut arg.expression);
+                self.mangle_expression(&mut arg.expression, locals);
             }
         }
     }
-    fn mangle_identifier_expression(id: &mut IdentifierExpression) {
-        let mut mangle_type_path = true;
-        if id.path.len() == 1 {
-            let builtin_tokens = mew_types::builtins::get_builtin_tokens();
-            mangle_type_path = !builtin_tokens
-                .type_generators
-                .contains(&id.path[0].name.value.clone());
-            if mangle_type_path {
-                mangle_type_path = !builtin_tokens
-                    .type_aliases
-                    .contains_key(&id.path[0].name.value.clone());
-            }
-        }
+    fn mangle_identifier_expression(
+        &self,
+        id: &mut IdentifierExpression,
+        locals: &im::HashSet<String>,
+    ) {
+        let mangle_type_path = id.path.len() != 1 || !Self::is_builtin_type(&id.path[0].name);
         if mangle_type_path {
-            mangle_path(&mut id.path);
+            self.mangle_path(&mut id.path, locals);
         } else if let Some(args) = id.path[0].template_args.as_mut() {
             for arg in args {
-                Self::mangle_expression(&mut arg.expression);
+                self.mangle_expression(&mut arg.expression, locals);
             }
         }
     }
 
-    fn mangle_decl(decl: &mut Declaration, path: ModulePath) {
+    fn mangle_decl(&self, decl: &mut Declaration, path: ModulePath) {
         if let Some(init) = decl.initializer.as_mut() {
-            Self::mangle_expression(init);
+            self.mangle_expression(init, &im::HashSet::new());
         }
         if let Some(typ) = decl.typ.as_mut() {
-            Self::mangle_type(typ);
+            self.mangle_type(typ, &im::HashSet::new());
         }
-        Self::mangle_name(&mut decl.name, path);
+        Self::remove_export_attributes(&mut decl.attributes);
+        self.mangle_name(&mut decl.name, path);
     }
 
-    fn mangle_alias(a: &mut Alias, path: ModulePath) {
-        Self::mangle_name(&mut a.name, path);
-        Self::mangle_type(&mut a.typ);
+    fn mangle_alias(&self, a: &mut Alias, path: ModulePath) {
+        Self::remove_export_attributes(&mut a.attributes);
+        self.mangle_name(&mut a.name, path);
+        self.mangle_type(&mut a.typ, &im::HashSet::new());
     }
 
-    fn mangle_struct(s: &mut Struct, path: ModulePath) {
+    fn mangle_struct(&self, s: &mut Struct, path: ModulePath) {
         for member in s.members.iter_mut() {
-            Self::mangle_type(&mut member.typ);
+            self.mangle_type(&mut member.typ, &im::HashSet::new());
         }
-        Self::mangle_name(&mut s.name, path);
+        Self::remove_export_attributes(&mut s.attributes);
+        self.mangle_name(&mut s.name, path);
     }
 
-    fn mangle_func(f: &mut Function, path: ModulePath) {
-        Self::mangle_name(&mut f.name, path);
+    fn mangle_func(&self, f: &mut Function, path: ModulePath) {
+        Self::remove_export_attributes(&mut f.attributes);
+        self.mangle_name(&mut f.name, path);
 
         if let Some(ret) = f.return_type.as_mut() {
-            Self::mangle_type(ret);
+            self.mangle_type(ret, &im::HashSet::new());
         }
+        let mut locals = im::HashSet::new();
         for arg in f.parameters.iter_mut() {
-            Self::mangle_type(&mut arg.typ);
+            locals.insert(arg.name.value.clone());
+            arg.name.value = mangle_str(&arg.name);
+            self.mangle_type(&mut arg.typ, &im::HashSet::new());
         }
         for statement in f.body.statements.iter_mut() {
-            Self::mangle_statement(statement);
+            self.mangle_statement(statement, &locals);
         }
     }
 
-    fn mangle_const_assert(a: &mut ConstAssert) {
-        Self::mangle_expression(&mut a.expression);
+    fn mangle_const_assert(&self, a: &mut ConstAssert) {
+        self.mangle_expression(&mut a.expression, &im::HashSet::new());
     }
 
-    fn mangle_module(m: &mut Module, mut path: ModulePath) {
+    fn mangle_module(&self, m: &mut Module, mut path: ModulePath) {
         path.0.push_back(PathPart {
             name: m.name.clone(),
             template_args: None,
@@ -294,50 +508,190 @@ impl Mangler {
         });
         for decl in m.members.iter_mut() {
             match decl.as_mut() {
-                ModuleMemberDeclaration::Void => {}
+                ModuleMemberDeclaration::Void
+                | ModuleMemberDeclaration::Error(_)
+                | ModuleMemberDeclaration::Interface(_) => {}
                 ModuleMemberDeclaration::Declaration(decl) => {
-                    Self::mangle_decl(decl, path.clone());
+                    self.mangle_decl(decl, path.clone());
                 }
                 ModuleMemberDeclaration::Alias(a) => {
-                    Self::mangle_alias(a, path.clone());
+                    self.mangle_alias(a, path.clone());
                 }
                 ModuleMemberDeclaration::Struct(strct) => {
-                    Self::mangle_struct(strct, path.clone());
+                    self.mangle_struct(strct, path.clone());
                 }
                 ModuleMemberDeclaration::Function(f) => {
-                    Self::mangle_func(f, path.clone());
+                    self.mangle_func(f, path.clone());
                 }
                 ModuleMemberDeclaration::ConstAssert(assrt) => {
-                    Self::mangle_const_assert(assrt);
+                    self.mangle_const_assert(assrt);
                 }
                 ModuleMemberDeclaration::Module(module) => {
-                    Self::mangle_module(module, path.clone());
+                    self.mangle_module(module, path.clone());
                 }
             }
         }
     }
 
-    fn mangle_translation_unit(translation_unit: &mut TranslationUnit, path: ModulePath) {
+    fn mangle_translation_unit(&self, translation_unit: &mut TranslationUnit, path: ModulePath) {
         for decl in translation_unit.global_declarations.iter_mut() {
             match decl.as_mut() {
-                GlobalDeclaration::Void => {}
+                GlobalDeclaration::Void
+                | GlobalDeclaration::Error(_)
+                | GlobalDeclaration::Interface(_) => {}
                 GlobalDeclaration::Declaration(decl) => {
-                    Self::mangle_decl(decl, path.clone());
+                    self.mangle_decl(decl, path.clone());
                 }
                 GlobalDeclaration::Alias(a) => {
-                    Self::mangle_alias(a, path.clone());
+                    self.mangle_alias(a, path.clone());
                 }
                 GlobalDeclaration::Struct(strct) => {
-                    Self::mangle_struct(strct, path.clone());
+                    self.mangle_struct(strct, path.clone());
                 }
                 GlobalDeclaration::Function(f) => {
-                    Self::mangle_func(f, path.clone());
+                    self.mangle_func(f, path.clone());
                 }
                 GlobalDeclaration::ConstAssert(assrt) => {
-                    Self::mangle_const_assert(assrt);
+                    self.mangle_const_assert(assrt);
+                }
+                GlobalDeclaration::Module(module) => {
+                    self.mangle_module(module, path.clone());
+                }
+            }
+        }
+    }
+
+    fn collect_module_names(
+        &self,
+        m: &Module,
+        mut path: ModulePath,
+        names: &mut HashMap<String, Vec<String>>,
+    ) {
+        path.0.push_back(PathPart {
+            name: m.name.clone(),
+            template_args: None,
+            inline_template_args: None,
+        });
+        for decl in m.members.iter() {
+            match decl.as_ref() {
+                ModuleMemberDeclaration::Module(module) => {
+                    self.collect_module_names(module, path.clone(), names);
+                }
+                member => {
+                    if let Some(name) = member.name() {
+                        self.collect_name(name.value, path.clone(), names);
+                    }
+                }
+            }
+        }
+    }
+
+    fn collect_name(
+        &self,
+        name: String,
+        path: ModulePath,
+        names: &mut HashMap<String, Vec<String>>,
+    ) {
+        let mut mew_path: Vec<String> = path.0.iter().map(|part| part.name.value.clone()).collect();
+        mew_path.push(name.clone());
+        let mut mangled = name;
+        self.mangle_name(&mut mangled, path);
+        names.insert(mangled, mew_path);
+    }
+
+    /// Maps the names the declarations of the translation unit will have once mangled to
+    /// their paths, e.g. `Lighting_phong` to `["Lighting", "phong"]`.
+    pub fn mangled_names(
+        &self,
+        translation_unit: &TranslationUnit,
+    ) -> HashMap<String, Vec<String>> {
+        let mangler = self.with_fixed_names(translation_unit);
+        let mut names = HashMap::new();
+        for decl in translation_unit.global_declarations.iter() {
+            match decl.as_ref() {
+                GlobalDeclaration::Module(module) => {
+                    mangler.collect_module_names(module, ModulePath(im::Vector::new()), &mut names);
+                }
+                declaration => {
+                    if let Some(name) = declaration.name() {
+                        mangler.collect_name(name.value, ModulePath(im::Vector::new()), &mut names);
+                    }
                 }
+            }
+        }
+        names
+    }
+
+    /// Whether a name is taken by a WGSL keyword, builtin type or builtin function.
+    fn is_reserved(name: &str) -> bool {
+        let builtin_tokens = mew_types::builtins::get_builtin_tokens();
+        builtin_tokens
+            .keywords
+            .iter()
+            .any(|keyword| keyword == name)
+            || Self::is_builtin_type(name)
+            || mew_types::builtins::get_builtin_functions()
+                .functions
+                .contains_key(name)
+    }
+
+    /// Reports a collision of the name of `decl` with the names seen before. Copies of a
+    /// declaration, like two uses of a specialization, are identical and do not collide.
+    fn check_name(
+        name: Spanned<String>,
+        decl: GlobalDeclaration,
+        seen: &mut HashMap<String, Spanned<GlobalDeclaration>>,
+        diagnostics: &mut Diagnostics,
+    ) {
+        let span = name.span();
+        if Self::is_reserved(&name) {
+            diagnostics.error(CompilerPassError::NameCollision(name.value, span, None));
+            return;
+        }
+        match seen.get(&name.value) {
+            Some(other) if other.value == decl => {}
+            Some(other) => diagnostics.error(CompilerPassError::NameCollision(
+                name.value,
+                span,
+                Some(other.span()),
+            )),
+            None => {
+                seen.insert(name.value, Spanned::new(decl, span));
+            }
+        }
+    }
+
+    fn check_module_names(
+        module: &Module,
+        seen: &mut HashMap<String, Spanned<GlobalDeclaration>>,
+        diagnostics: &mut Diagnostics,
+    ) {
+        for decl in module.members.iter() {
+            match decl.as_ref() {
+                ModuleMemberDeclaration::Module(module) => {
+                    Self::check_module_names(module, seen, diagnostics);
+                }
+                member => {
+                    if let Some(name) = member.name() {
+                        Self::check_name(name, member.clone().into(), seen, diagnostics);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reports the mangled declarations whose names collide with each other, or with WGSL.
+    fn check_names(translation_unit: &TranslationUnit, diagnostics: &mut Diagnostics) {
+        let mut seen = HashMap::new();
+        for decl in translation_unit.global_declarations.iter() {
+            match decl.as_ref() {
                 GlobalDeclaration::Module(module) => {
-                    Self::mangle_module(module, path.clone());
+                    Self::check_module_names(module, &mut seen, diagnostics);
+                }
+                declaration => {
+                    if let Some(name) = declaration.name() {
+                        Self::check_name(name, declaration.clone(), &mut seen, diagnostics);
+                    }
                 }
             }
         }
@@ -345,13 +699,29 @@ impl Mangler {
 
     pub fn mangle_mut(&self, translation_unit: &mut TranslationUnit) {
         let path = ModulePath(im::Vector::new());
-        Self::mangle_translation_unit(translation_unit, path);
+        self.with_fixed_names(translation_unit)
+            .mangle_translation_unit(translation_unit, path);
     }
 }
 
 impl CompilerPass for Mangler {
     fn apply_mut(&mut self, translation_unit: &mut TranslationUnit) -> CompilerPassResult {
-        self.mangle_mut(translation_unit);
+        let mut diagnostics = Diagnostics::default();
+        self.apply_mut_with_diagnostics(translation_unit, &mut diagnostics)?;
+        diagnostics.first_error()
+    }
+
+    fn apply_mut_with_diagnostics(
+        &mut self,
+        translation_unit: &mut TranslationUnit,
+        diagnostics: &mut Diagnostics,
+    ) -> CompilerPassResult {
+        let mangler = self.with_fixed_names(translation_unit);
+        for span in mangler.malformed_exports.iter() {
+            diagnostics.error(CompilerPassError::MalformedExport(span.clone()));
+        }
+        mangler.mangle_translation_unit(translation_unit, ModulePath(im::Vector::new()));
+        Self::check_names(translation_unit, diagnostics);
         Ok(())
     }
 }
@@ -0,0 +1,185 @@
+//! A lossless view of a source file, where every token keeps the whitespace and
+//! comments ("trivia") that precede it.
+//!
+//! The [syntax tree] drops trivia, so printing it loses the original layout. The
+//! [`ConcreteSyntax`] keeps it: printing it reproduces the source byte-for-byte, and
+//! [`ConcreteSyntax::leading_trivia`] finds the comments attached to any syntax node
+//! from the node's [`Span`].
+//!
+//! [syntax tree]: crate::syntax
+
+use std::fmt::Display;
+
+use crate::{
+    lexer::{Lexer, Token},
+    span::Span,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TriviaKind {
+    Whitespace,
+    LineComment,
+    BlockComment,
+    /// A character the lexer skipped, which is neither whitespace nor part of a comment.
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub span: Span,
+}
+
+impl Trivia {
+    pub fn is_comment(&self) -> bool {
+        matches!(
+            self.kind,
+            TriviaKind::LineComment | TriviaKind::BlockComment
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenWithTrivia {
+    /// The token, or `None` if the lexer could not recognize this part of the source.
+    pub token: Option<Token>,
+    pub span: Span,
+    pub leading_trivia: Vec<Trivia>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConcreteSyntax {
+    source: String,
+    tokens: Vec<TokenWithTrivia>,
+    trailing_trivia: Vec<Trivia>,
+}
+
+/// Splits a slice of the source that contains no tokens into trivia.
+fn split_trivia(source: &str, span: Span) -> Vec<Trivia> {
+    let mut result = Vec::new();
+    let mut offset = span.start;
+    while offset < span.end {
+        let rest = &source[offset..span.end];
+        let (kind, len) = if let Some(comment) = rest.strip_prefix("//") {
+            let len = comment.find(['\n', '\r']).unwrap_or(comment.len());
+            (TriviaKind::LineComment, len + 2)
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let len = comment.find("*/").map(|x| x + 2).unwrap_or(comment.len());
+            (TriviaKind::BlockComment, len + 2)
+        } else {
+            match rest
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len())
+            {
+                0 => (
+                    TriviaKind::Unknown,
+                    rest.chars().next().map(char::len_utf8).unwrap_or(0),
+                ),
+                len => (TriviaKind::Whitespace, len),
+            }
+        };
+        result.push(Trivia {
+            kind,
+            span: offset..offset + len,
+        });
+        offset += len;
+    }
+    result
+}
+
+impl ConcreteSyntax {
+    pub fn parse(source: &str) -> Self {
+        let mut spans: Vec<(Option<Token>, Span)> = Lexer::new(source)
+            .map(|token| match token {
+                Ok((start, token, end)) => (Some(token), start..end),
+                Err((start, _, end)) => (None, start..end),
+            })
+            .collect();
+
+        // the lexer splits tokens such as `>>` when they close template lists, in which
+        // case the two resulting tokens overlap.
+        for i in 1..spans.len() {
+            let next_start = spans[i].1.start;
+            let prev = &mut spans[i - 1].1;
+            prev.end = prev.end.min(next_start);
+        }
+
+        let mut offset = 0;
+        let mut tokens = Vec::with_capacity(spans.len());
+        for (token, span) in spans {
+            let leading_trivia = split_trivia(source, offset..span.start);
+            offset = span.end;
+            tokens.push(TokenWithTrivia {
+                token,
+                span,
+                leading_trivia,
+            });
+        }
+        let trailing_trivia = split_trivia(source, offset..source.len());
+
+        Self {
+            source: source.to_string(),
+            tokens,
+            trailing_trivia,
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn tokens(&self) -> &[TokenWithTrivia] {
+        &self.tokens
+    }
+
+    pub fn trailing_trivia(&self) -> &[Trivia] {
+        &self.trailing_trivia
+    }
+
+    pub fn text(&self, span: &Span) -> &str {
+        &self.source[span.clone()]
+    }
+
+    /// Returns the trivia right before the token starting at `span.start`, typically the
+    /// span of a syntax node. Returns an empty slice if no token starts there.
+    pub fn leading_trivia(&self, span: &Span) -> &[Trivia] {
+        match self
+            .tokens
+            .binary_search_by_key(&span.start, |token| token.span.start)
+        {
+            Ok(idx) => &self.tokens[idx].leading_trivia,
+            Err(_) => &[],
+        }
+    }
+
+    /// Returns the text of the comments right before the node starting at `span.start`.
+    pub fn leading_comments(&self, span: &Span) -> impl Iterator<Item = &str> {
+        self.leading_trivia(span)
+            .iter()
+            .filter(|trivia| trivia.is_comment())
+            .map(|trivia| self.text(&trivia.span))
+    }
+
+    pub fn comments(&self) -> impl Iterator<Item = &Trivia> {
+        self.tokens
+            .iter()
+            .flat_map(|token| token.leading_trivia.iter())
+            .chain(self.trailing_trivia.iter())
+            .filter(|trivia| trivia.is_comment())
+    }
+}
+
+impl Display for ConcreteSyntax {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for token in self.tokens.iter() {
+            for trivia in token.leading_trivia.iter() {
+                f.write_str(self.text(&trivia.span))?;
+            }
+            f.write_str(self.text(&token.span))?;
+        }
+        for trivia in self.trailing_trivia.iter() {
+            f.write_str(self.text(&trivia.span))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+//! A [`Diagnostic`] is a compiler message about the source code, rendered with
+//! annotate-snippets like parse errors.
+//!
+//! Spans in a diagnostic are offsets in a [`SourceMap`], so a single diagnostic can
+//! point at several files, e.g. at the use of a generic and at its declaration.
+
+use std::fmt::Display;
+
+use annotate_snippets::*;
+
+use crate::span::{SourceMap, Span};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+    /// Primary labels point at the cause of the diagnostic, secondary labels give context.
+    pub primary: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Option<String>,
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+}
+
+/// The text of a file, with the offset of its first byte in the diagnostic spans.
+struct SourceText<'a> {
+    name: Option<&'a str>,
+    source: &'a str,
+    start: usize,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    pub fn with_primary_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+            primary: true,
+        });
+        self
+    }
+
+    pub fn with_secondary_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+            primary: false,
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    /// The span of the first primary label, if any.
+    pub fn primary_span(&self) -> Option<Span> {
+        self.labels
+            .iter()
+            .find(|label| label.primary)
+            .map(|label| label.span.clone())
+    }
+
+    /// Renders the diagnostic, showing the labels in the files of the source map.
+    ///
+    /// Labels that lie outside of every file are left out.
+    pub fn render(&self, source_map: &SourceMap) -> String {
+        let mut files = source_map
+            .files()
+            .filter(|(id, _)| {
+                self.labels
+                    .iter()
+                    .any(|label| source_map.file_id(&label.span) == Some(*id))
+            })
+            .map(|(_, file)| SourceText {
+                name: Some(file.name.as_str()),
+                source: file.source.as_str(),
+                start: file.start,
+            })
+            .collect::<Vec<_>>();
+        // show the file of the primary label first
+        if let Some(span) = self.primary_span() {
+            files.sort_by_key(|file| {
+                !(file.start <= span.start && span.start <= file.start + file.source.len())
+            });
+        }
+        self.render_files(&files)
+    }
+
+    /// Renders the diagnostic against a single source, whose first byte is at `offset`.
+    pub fn render_source(&self, source: &str, offset: usize) -> String {
+        self.render_files(&[SourceText {
+            name: None,
+            source,
+            start: offset,
+        }])
+    }
+
+    fn render_files(&self, files: &[SourceText<'_>]) -> String {
+        let level = match self.severity {
+            Severity::Error => Level::ERROR,
+            Severity::Warning => Level::WARNING,
+        };
+        let mut title = level.primary_title(self.message.as_str());
+        if let Some(code) = &self.code {
+            title = title.id(code.as_str());
+        }
+        let mut group = Group::with_title(title);
+        for file in files {
+            let end = file.start + file.source.len();
+            let annotations = self
+                .labels
+                .iter()
+                .filter(|label| file.start <= label.span.start && label.span.end <= end)
+                .map(|label| {
+                    let kind = if label.primary {
+                        AnnotationKind::Primary
+                    } else {
+                        AnnotationKind::Context
+                    };
+                    let span = label.span.start - file.start..label.span.end - file.start;
+                    kind.span(span).label(label.message.as_str())
+                })
+                .collect::<Vec<_>>();
+            if annotations.is_empty() {
+                continue;
+            }
+            group = group.element(
+                Snippet::source(file.source)
+                    .path(file.name)
+                    .fold(true)
+                    .annotations(annotations),
+            );
+        }
+        for note in self.notes.iter() {
+            group = group.element(Level::NOTE.message(note.as_str()));
+        }
+        for help in self.help.iter() {
+            group = group.element(Level::HELP.message(help.as_str()));
+        }
+        let renderer = Renderer::styled();
+        renderer.render(&[group]).to_string()
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.severity {
+            Severity::Error => write!(f, "error")?,
+            Severity::Warning => write!(f, "warning")?,
+        }
+        if let Some(code) = &self.code {
+            write!(f, "[{code}]")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
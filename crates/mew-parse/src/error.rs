@@ -2,11 +2,10 @@
 
 use std::fmt::Display;
 
-use annotate_snippets::*;
 use itertools::Itertools;
 use thiserror::Error;
 
-use crate::{lexer::Token, span::Span};
+use crate::{diagnostic::Diagnostic, lexer::Token, span::Span};
 
 #[derive(Error, Clone, Debug, Default, PartialEq)]
 pub enum ParseError {
@@ -18,16 +17,28 @@ pub enum ParseError {
 }
 
 type LalrError = lalrpop_util::ParseError<usize, Token, (usize, ParseError, usize)>;
+pub(crate) type LalrErrorRecovery =
+    lalrpop_util::ErrorRecovery<usize, Token, (usize, ParseError, usize)>;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct SpannedError<'s> {
     inner: LalrError,
     source: &'s str,
+    offset: usize,
 }
 
 impl<'s> SpannedError<'s> {
     pub(crate) fn new(inner: LalrError, source: &'s str) -> Self {
-        Self { inner, source }
+        Self::with_offset(inner, source, 0)
+    }
+
+    /// Creates an error whose locations are shifted by `offset` relative to `source`.
+    pub(crate) fn with_offset(inner: LalrError, source: &'s str, offset: usize) -> Self {
+        Self {
+            inner,
+            source,
+            offset,
+        }
     }
 
     pub fn span(&self) -> Span {
@@ -43,76 +54,62 @@ impl<'s> SpannedError<'s> {
 
 impl<'s> std::error::Error for SpannedError<'s> {}
 
-impl<'s> Display for SpannedError<'s> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let err = &self.inner;
+impl<'s> SpannedError<'s> {
+    /// Describes the error as a [`Diagnostic`], with spans shifted like [`Self::span`].
+    pub fn diagnostic(&self) -> Diagnostic {
+        let offset = self.offset;
         let source = self.source;
-        let renderer = Renderer::styled();
-        match err {
+        let text = |start: usize, end: usize| &source[start - offset..end - offset];
+        match &self.inner {
             LalrError::InvalidToken { location } => {
-                let end = (*location + 1..)
+                let end = (*location - offset + 1..)
                     .find(|e| source.is_char_boundary(*e))
+                    .map(|e| e + offset)
                     .unwrap_or(*location);
-                let message = Group::with_title(Level::ERROR.primary_title("invalid token"))
-                    .element(
-                        Snippet::source(source).fold(true).annotation(
-                            AnnotationKind::Context
-                                .span(*location..end)
-                                .label("this token is unknown"),
-                        ),
-                    );
-                write!(f, "{}", renderer.render(&[message]))
+                Diagnostic::error("invalid token")
+                    .with_code("E0001")
+                    .with_primary_label(*location..end, "this token is unknown")
             }
             LalrError::UnrecognizedEof { location, expected } => {
-                let annot = format!("expected {}", expected.iter().format(", "));
-                let message =
-                    Group::with_title(Level::ERROR.primary_title("unexpected end of file"))
-                        .element(
-                            Snippet::source(source).fold(true).annotation(
-                                AnnotationKind::Context
-                                    .span(*location..*location)
-                                    .label(&annot),
-                            ),
-                        );
-                write!(f, "{}", renderer.render(&[message]))
+                Diagnostic::error("unexpected end of file")
+                    .with_code("E0002")
+                    .with_primary_label(
+                        *location..*location,
+                        format!("expected {}", expected.iter().format(", ")),
+                    )
             }
             LalrError::UnrecognizedToken { token, expected } => {
-                let title = format!("unexpected token `{}`", &source[token.0..token.2]);
-                let annot = format!(
-                    "expected {}, found {}",
-                    expected.iter().format(", "),
-                    token.1
-                );
-                let message = Group::with_title(Level::ERROR.primary_title(&title)).element(
-                    Snippet::source(source)
-                        .fold(true)
-                        .annotation(AnnotationKind::Context.span(token.0..token.2).label(&annot)),
-                );
-                write!(f, "{}", renderer.render(&[message]))
+                Diagnostic::error(format!("unexpected token `{}`", text(token.0, token.2)))
+                    .with_code("E0003")
+                    .with_primary_label(
+                        token.0..token.2,
+                        format!(
+                            "expected {}, found {}",
+                            expected.iter().format(", "),
+                            token.1
+                        ),
+                    )
             }
             LalrError::ExtraToken { token } => {
-                let title = format!("extra token `{}`", &source[token.0..token.2]);
-                let annot = format!("extra {} here", token.1);
-                let message = Group::with_title(Level::ERROR.primary_title(&title)).element(
-                    Snippet::source(source)
-                        .fold(true)
-                        .annotation(AnnotationKind::Context.span(token.0..token.2).label(&annot)),
-                );
-                write!(f, "{}", renderer.render(&[message]))
+                Diagnostic::error(format!("extra token `{}`", text(token.0, token.2)))
+                    .with_code("E0004")
+                    .with_primary_label(token.0..token.2, format!("extra {} here", token.1))
             }
             LalrError::User {
                 error: (start, error, end),
-            } => {
-                let title = error.to_string();
-                let message = Group::with_title(Level::ERROR.primary_title(&title)).element(
-                    Snippet::source(source).fold(true).annotation(
-                        AnnotationKind::Context
-                            .span(*start..*end)
-                            .label("while parsing this token"),
-                    ),
-                );
-                write!(f, "{}", renderer.render(&[message]))
-            }
+            } => Diagnostic::error(error.to_string())
+                .with_code("E0005")
+                .with_primary_label(*start..*end, "while parsing this token"),
         }
     }
 }
+
+impl<'s> Display for SpannedError<'s> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.diagnostic().render_source(self.source, self.offset)
+        )
+    }
+}
@@ -320,6 +320,8 @@ pub enum Token {
     KwImport,
     #[token("module")]
     KwModule,
+    #[token("interface")]
+    KwInterface,
     #[token("extend")]
     KwExtend,
     #[token("sig")]
@@ -337,6 +339,9 @@ pub enum Token {
     // XXX: should we also register reserved words as tokens?
     #[regex(r#"([_\p{XID_Start}][\p{XID_Continue}]+)|([\p{XID_Start}])"#, |lex| lex.slice().to_string())]
     Ident(String),
+    // MEW ADDITION: strings are only allowed as arguments of attributes, like `@export("name")`
+    #[regex(r#""[^"\n]*""#, |lex| { let s = lex.slice(); s[1..s.len() - 1].to_string() })]
+    StringLiteral(String),
     #[regex(r#"0|[1-9][0-9]*"#, parse_dec_abstract_int)]
     #[regex(r#"0[xX][0-9a-fA-F]+"#, parse_hex_abstract_int)]
     AbstractInt(i64),
@@ -457,6 +462,7 @@ impl Token {
                 | Token::KwWhile
                 | Token::KwImport
                 | Token::KwModule
+                | Token::KwInterface
                 | Token::KwExtend
                 | Token::KwSig
                 | Token::KwAs
@@ -557,10 +563,12 @@ impl Display for Token {
             Token::KwImport => f.write_str("import"),
             Token::KwAs => f.write_str("as"),
             Token::KwModule => f.write_str("module"),
+            Token::KwInterface => f.write_str("interface"),
             Token::KwExtend => f.write_str("extend"),
             Token::KwSig => f.write_str("sig"),
             Token::KwWith => f.write_str("with"),
             Token::Ident(s) => write!(f, "identifier `{s}`"),
+            Token::StringLiteral(s) => write!(f, "\"{s}\""),
             Token::AbstractInt(n) => write!(f, "{n}"),
             Token::AbstractFloat(n) => write!(f, "{n}"),
             Token::I32(n) => write!(f, "{n}i"),
@@ -584,10 +592,17 @@ pub struct Lexer<'s> {
     next_token: Option<(Result<Token, ParseError>, Span)>,
     parsing_template: bool,
     opened_templates: u32,
+    offset: usize,
 }
 
 impl<'s> Lexer<'s> {
     pub fn new(source: &'s str) -> Self {
+        Self::with_offset(source, 0)
+    }
+
+    /// Creates a lexer whose spans are shifted by `offset`, e.g. the start of a file in a
+    /// [`crate::span::SourceMap`].
+    pub fn with_offset(source: &'s str, offset: usize) -> Self {
         let mut token_stream = Token::lexer_with_extras(source, LexerState::default()).spanned();
         let next_token = token_stream.next();
         Self {
@@ -596,12 +611,17 @@ impl<'s> Lexer<'s> {
             next_token,
             parsing_template: false,
             opened_templates: 0,
+            offset,
         }
     }
 
     pub fn source(&self) -> &str {
         self.source
     }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
 }
 
 /// Returns `true` if the source starts with a valid template list.
@@ -627,6 +647,7 @@ impl<'s> Lexer<'s> {
 /// assert_eq!(recognize_template_list("<SumBinaryOp<F32>>"), true);
 /// assert_eq!(recognize_template_list("<SumBinaryOp with { module F32 { alias T = f32; } }>"), true);
 /// assert_eq!(recognize_template_list("<SumBinaryOp<8,F32<16>>::v>>>"), true);
+/// assert_eq!(recognize_template_list("<S: Hashable, size: u32 = 8u>"), true);
 /// // false cases
 /// assert_eq!(recognize_template_list("<d]>"), false);
 /// assert_eq!(recognize_template_list(""), false);
@@ -643,7 +664,17 @@ impl<'s> Lexer<'s> {
 ///
 /// [template list discovery algorigthm]: https://www.w3.org/TR/WGSL/#template-list-discovery
 /// [*template_list*]: https://www.w3.org/TR/WGSL/#syntax-template_list
+///
+/// MEW ADDITION: template parameters with bounds, like `<S: Hashable>`, are recognized too.
 pub fn recognize_template_list(source: &str) -> bool {
+    recognize_template(source, |lexer| {
+        Parser::recognize_template_list(lexer).is_ok()
+    }) || recognize_template(source, |lexer| {
+        Parser::recognize_template_parameters(lexer).is_ok()
+    })
+}
+
+fn recognize_template(source: &str, recognize: impl FnOnce(&mut Lexer) -> bool) -> bool {
     let mut lexer = Lexer::new(source);
     match lexer.next_token {
         Some((Ok(ref mut t), _)) if *t == Token::SymLessThan => *t = Token::TemplateArgsStart,
@@ -652,8 +683,7 @@ pub fn recognize_template_list(source: &str) -> bool {
     lexer.parsing_template = true;
     lexer.opened_templates = 1;
     lexer.token_stream.extras.template_depths.push(0);
-    let parse = Parser::recognize_template_list(&mut lexer);
-    parse.is_ok()
+    recognize(&mut lexer)
 }
 
 impl<'s> Iterator for Lexer<'s> {
@@ -694,9 +724,10 @@ impl<'s> Iterator for Lexer<'s> {
 
         std::mem::swap(&mut self.next_token, &mut next_token);
 
+        let offset = self.offset;
         next_token.map(|(token, span)| match token {
-            Ok(tok) => Ok((span.start, tok, span.end)),
-            Err(err) => Err((span.start, err, span.end)),
+            Ok(tok) => Ok((span.start + offset, tok, span.end + offset)),
+            Err(err) => Err((span.start + offset, err, span.end + offset)),
         })
     }
 }
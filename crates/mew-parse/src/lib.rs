@@ -1,3 +1,5 @@
+pub mod cst;
+pub mod diagnostic;
 pub mod error;
 pub mod lexer;
 pub mod parser;
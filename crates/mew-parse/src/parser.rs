@@ -3,52 +3,156 @@
 //! [syntax tree]: syntax
 
 lalrpop_mod!(
-    #[allow(clippy::type_complexity)]
+    #[allow(clippy::type_complexity, clippy::large_enum_variant, clippy::ptr_arg)]
     wgsl
 );
 use lalrpop_util::lalrpop_mod;
 
 use crate::{
-    error::SpannedError,
+    error::{LalrErrorRecovery, SpannedError},
     lexer::Lexer,
+    span::SourceFile,
     syntax::{self, IdentifierExpression},
 };
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Parser;
 
+/// Returns the first error the parser recovered from, if any, for a source at `offset`.
+fn first_recovered_error<'s>(
+    errors: Vec<LalrErrorRecovery>,
+    source: &'s str,
+    offset: usize,
+) -> Option<SpannedError<'s>> {
+    errors
+        .into_iter()
+        .next()
+        .map(|e| SpannedError::with_offset(e.error, source, offset))
+}
+
 impl Parser {
     pub fn parse_path<'source>(
         path: &'source str,
     ) -> Result<IdentifierExpression, SpannedError<'source>> {
+        let mut errors = Vec::new();
         let lexer = Lexer::new(path);
         let parser = wgsl::EntryPointPathParser::new();
-        let res = parser.parse(lexer);
-        res.map_err(|e| SpannedError::new(e, path))
+        let res = parser
+            .parse(&mut errors, lexer)
+            .map_err(|e| SpannedError::new(e, path))?;
+        match first_recovered_error(errors, path, 0) {
+            Some(err) => Err(err),
+            None => Ok(res),
+        }
     }
 
     pub fn parse_str<'source>(
         source: &'source str,
     ) -> Result<syntax::TranslationUnit, SpannedError<'source>> {
+        let mut errors = Vec::new();
         let lexer = Lexer::new(source);
         let parser = wgsl::TranslationUnitParser::new();
-        let res = parser.parse(lexer);
-        res.map_err(|e| SpannedError::new(e, source))
+        let res = parser
+            .parse(&mut errors, lexer)
+            .map_err(|e| SpannedError::new(e, source))?;
+        match first_recovered_error(errors, source, 0) {
+            Some(err) => Err(err),
+            None => Ok(res),
+        }
     }
+
+    /// Parses a file of a source map. Spans in the result and in the error are offsets
+    /// in the source map, so they identify the file they come from.
+    pub fn parse_file(
+        source_file: &SourceFile,
+    ) -> Result<syntax::TranslationUnit, SpannedError<'_>> {
+        let source = source_file.source.as_str();
+        let offset = source_file.start;
+        let mut errors = Vec::new();
+        let lexer = Lexer::with_offset(source, offset);
+        let parser = wgsl::TranslationUnitParser::new();
+        let res = parser
+            .parse(&mut errors, lexer)
+            .map_err(|e| SpannedError::with_offset(e, source, offset))?;
+        match first_recovered_error(errors, source, offset) {
+            Some(err) => Err(err),
+            None => Ok(res),
+        }
+    }
+
+    /// Parses the source, recovering from syntax errors where possible.
+    ///
+    /// Declarations, module members and statements that fail to parse are replaced
+    /// by `Error` nodes in the returned syntax tree, and every error encountered is
+    /// returned alongside it. If the parser cannot recover, as from a token the lexer
+    /// rejects, the tree holds what precedes the error that stopped the parse, which is
+    /// the last error.
+    pub fn parse_str_with_recovery(
+        source: &str,
+    ) -> (syntax::TranslationUnit, Vec<SpannedError<'_>>) {
+        let mut errors = Vec::new();
+        let lexer = Lexer::new(source);
+        let parser = wgsl::TranslationUnitParser::new();
+        let res = parser.parse(&mut errors, lexer);
+        let mut spanned_errors: Vec<SpannedError> = errors
+            .into_iter()
+            .map(|e| SpannedError::new(e.error, source))
+            .collect();
+        match res {
+            Ok(translation_unit) => (translation_unit, spanned_errors),
+            Err(e) => {
+                let error = SpannedError::new(e, source);
+                // the source before the error is parsed again, and its end is recovered from
+                let partial = &source[..error.span().start.min(source.len())];
+                let translation_unit = parser
+                    .parse(&mut Vec::new(), Lexer::new(partial))
+                    .unwrap_or_default();
+                spanned_errors.push(error);
+                (translation_unit, spanned_errors)
+            }
+        }
+    }
+
     pub fn parse<'s>(
         mut lexer: &'s mut Lexer,
     ) -> Result<syntax::TranslationUnit, SpannedError<'s>> {
+        let mut errors = Vec::new();
         let parser = wgsl::TranslationUnitParser::new();
-        let res = parser.parse(&mut lexer);
-        res.map_err(|e| SpannedError::new(e, lexer.source()))
+        let res = parser.parse(&mut errors, &mut lexer);
+        let source = lexer.source();
+        let offset = lexer.offset();
+        let res = res.map_err(|e| SpannedError::with_offset(e, source, offset))?;
+        match first_recovered_error(errors, source, offset) {
+            Some(err) => Err(err),
+            None => Ok(res),
+        }
     }
 }
 
 impl Parser {
     pub fn recognize_template_list<'s>(mut lexer: &'s mut Lexer) -> Result<(), SpannedError<'s>> {
+        let mut errors = Vec::new();
         let parser = wgsl::TryTemplateListParser::new();
-        let res = parser.parse(&mut lexer);
-        res.map_err(|e| SpannedError::new(e, lexer.source()))
-            .and(Ok(()))
+        let res = parser.parse(&mut errors, &mut lexer);
+        let source = lexer.source();
+        res.map_err(|e| SpannedError::new(e, source))?;
+        match first_recovered_error(errors, source, 0) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    pub fn recognize_template_parameters<'s>(
+        mut lexer: &'s mut Lexer,
+    ) -> Result<(), SpannedError<'s>> {
+        let mut errors = Vec::new();
+        let parser = wgsl::TryTemplateParametersParser::new();
+        let res = parser.parse(&mut errors, &mut lexer);
+        let source = lexer.source();
+        res.map_err(|e| SpannedError::new(e, source))?;
+        match first_recovered_error(errors, source, 0) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
     }
 }
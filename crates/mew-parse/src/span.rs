@@ -33,6 +33,11 @@ impl<T> Spanned<T> {
     pub fn into_inner(self) -> T {
         self.value
     }
+
+    /// Returns the file this node was parsed from.
+    pub fn file(&self, source_map: &SourceMap) -> Option<FileId> {
+        source_map.file_id(&self.span)
+    }
 }
 
 impl<T> Deref for Spanned<T> {
@@ -76,3 +81,122 @@ impl<T: IntoIterator> IntoIterator for Spanned<T> {
         self.value.into_iter()
     }
 }
+
+/// Identifies a file registered in a [`SourceMap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId(usize);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceFile {
+    pub name: String,
+    pub source: String,
+    /// The offset of the first byte of the file in the [`SourceMap`].
+    pub start: usize,
+}
+
+impl SourceFile {
+    /// The span covered by the file, in [`SourceMap`] offsets.
+    pub fn span(&self) -> Span {
+        self.start..self.start + self.source.len()
+    }
+
+    /// Returns the 1-based line and column of a [`SourceMap`] offset inside this file.
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let local = offset.saturating_sub(self.start).min(self.source.len());
+        let before = &self.source[..local];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|x| x + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+        (line, column)
+    }
+}
+
+/// A position in a [`SourceFile`], displayed as `file:line:col`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub file: FileId,
+    pub file_name: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file_name, self.line, self.column)
+    }
+}
+
+/// Lays out several source files in a single offset space.
+///
+/// Every file gets a distinct range of offsets, so a [`Span`] produced by parsing a file
+/// with [`crate::Parser::parse_file`] identifies both the file and the position in it.
+/// Offset `0` belongs to no file and is left for synthesized nodes, which use `0..0`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    /// The files by their id. A removed file leaves a `None`, so that the ids and offsets
+    /// of the other files stay valid.
+    files: Vec<Option<SourceFile>>,
+    /// The offset after the last file added, even if it was removed.
+    end: usize,
+}
+
+impl SourceMap {
+    pub fn add_file(&mut self, name: impl Into<String>, source: impl Into<String>) -> FileId {
+        // files are separated by one byte so that an end-of-file offset stays in its file.
+        let start = self.end + 1;
+        let source = source.into();
+        self.end = start + source.len();
+        self.files.push(Some(SourceFile {
+            name: name.into(),
+            source,
+            start,
+        }));
+        FileId(self.files.len() - 1)
+    }
+
+    /// Removes a file and its source. Its offsets are not reused by files added later.
+    pub fn remove_file(&mut self, file: FileId) -> Option<SourceFile> {
+        self.files.get_mut(file.0)?.take()
+    }
+
+    /// Returns the file with the id, or `None` if it was removed.
+    pub fn file(&self, file: FileId) -> Option<&SourceFile> {
+        self.files.get(file.0)?.as_ref()
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = (FileId, &SourceFile)> {
+        self.files
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, file)| Some((FileId(idx), file.as_ref()?)))
+    }
+
+    /// Returns the file containing the start of the span.
+    pub fn file_id(&self, span: &Span) -> Option<FileId> {
+        let (file, source_file) = self
+            .files()
+            .take_while(|(_, file)| file.start <= span.start)
+            .last()?;
+        (span.start <= source_file.span().end).then_some(file)
+    }
+
+    pub fn location(&self, span: &Span) -> Option<Location> {
+        let file = self.file_id(span)?;
+        let source_file = self.file(file)?;
+        let (line, column) = source_file.line_column(span.start);
+        Some(Location {
+            file,
+            file_name: source_file.name.clone(),
+            line,
+            column,
+        })
+    }
+
+    /// Returns the source text covered by the span, if it lies within a single file.
+    pub fn text(&self, span: &Span) -> Option<&str> {
+        let file = self.file(self.file_id(span)?)?;
+        let start = span.start - file.start;
+        let end = span.end.checked_sub(file.start)?;
+        file.source.get(start..end)
+    }
+}
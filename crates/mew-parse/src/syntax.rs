@@ -25,7 +25,7 @@
 
 use std::{hash::Hash, ops::Deref};
 
-use crate::span::S;
+use crate::span::{S, Span};
 
 pub struct WithSource<'s, T> {
     syntax: T,
@@ -152,12 +152,16 @@ pub struct RequiresDirective {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum GlobalDeclaration {
     Void,
+    /// A declaration that failed to parse, produced by [`crate::Parser::parse_str_with_recovery`],
+    /// with the range of the source the parser skipped.
+    Error(Span),
     Declaration(Declaration),
     Alias(Alias),
     Struct(Struct),
     Function(Function),
     ConstAssert(ConstAssert),
     Module(Module),
+    Interface(ModuleTypeSignature),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Default, Hash)]
@@ -169,6 +173,8 @@ pub struct Module {
     pub template_parameters: Vec<S<FormalTemplateParameter>>,
 }
 
+/// MEW ADDITION: an `interface`, the members a module must have to be the argument of a
+/// template parameter bounded by it.
 #[derive(Clone, Debug, PartialEq, Eq, Default, Hash)]
 pub struct ModuleTypeSignature {
     pub attributes: Vec<S<Attribute>>,
@@ -182,6 +188,9 @@ pub struct ModuleTypeSignature {
 pub struct FormalTemplateParameter {
     pub name: S<String>,
     pub default_value: Option<S<Expression>>,
+    /// MEW ADDITION: the interface the argument must implement, like `Hashable` in
+    /// `<S: Hashable>`.
+    pub bound: Option<S<TypeExpression>>,
 }
 
 pub struct TemplateElaboratedIdent {
@@ -197,23 +206,44 @@ pub struct TemplateElaboratedIdentPart {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum ModuleMemberDeclaration {
     Void,
+    /// A module member that failed to parse, produced by [`crate::Parser::parse_str_with_recovery`],
+    /// with the range of the source the parser skipped.
+    Error(Span),
     Declaration(Declaration),
     Alias(Alias),
     Struct(Struct),
     Function(Function),
     ConstAssert(ConstAssert),
     Module(Module),
+    Interface(ModuleTypeSignature),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum ModuleTypeSignatureMemberDeclaration {
     Void,
+    /// `const n: u32;`, a declaration of the same kind and type. With an initializer, like
+    /// `const n: u32 = 4u;`, modules that extend the interface inherit it unless they declare
+    /// their own.
     Declaration(Declaration),
-    Alias(Alias),
+    /// `alias t;`, a member of any kind, usually a type.
+    Alias(S<String>),
+    /// `struct Entry { key: t }`, a struct with at least these members.
     Struct(Struct),
-    Function(Function),
-    ConstAssert(ConstAssert),
-    Module(Module),
+    /// `fn hash(a: t) -> u32;`, a function with the same signature.
+    Function(FunctionSignature),
+    /// `fn twice(a: t) -> t { return add(a, a); }`, a function with the same signature, which
+    /// modules that extend the interface inherit unless they declare their own.
+    DefaultFunction(Function),
+}
+
+/// MEW ADDITION: a function required by an interface, which has no body.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FunctionSignature {
+    pub attributes: Vec<S<Attribute>>,
+    pub name: S<String>,
+    pub parameters: Vec<S<FormalParameter>>,
+    pub return_attributes: Vec<S<Attribute>>,
+    pub return_type: Option<S<TypeExpression>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -237,6 +267,8 @@ pub enum DeclarationKind {
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Alias {
+    /// MEW ADDITION: WGSL aliases take no attributes, but MEW aliases can be conditional.
+    pub attributes: Vec<S<Attribute>>,
     pub name: S<String>,
     pub typ: S<TypeExpression>,
     pub template_parameters: Vec<S<FormalTemplateParameter>>,
@@ -244,6 +276,8 @@ pub struct Alias {
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Struct {
+    /// MEW ADDITION: WGSL structs take no attributes, but MEW structs can be conditional.
+    pub attributes: Vec<S<Attribute>>,
     pub name: S<String>,
     pub members: Vec<S<StructMember>>,
     pub template_parameters: Vec<S<FormalTemplateParameter>>,
@@ -281,6 +315,9 @@ pub struct FormalParameter {
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ConstAssert {
+    /// MEW ADDITION: WGSL const assertions take no attributes, but MEW const assertions can
+    /// be conditional.
+    pub attributes: Vec<S<Attribute>>,
     pub expression: S<Expression>,
     pub template_parameters: Vec<S<FormalTemplateParameter>>,
 }
@@ -314,6 +351,8 @@ pub enum LiteralExpression {
     U32(u32),
     F32(String),
     F16(String),
+    /// MEW ADDITION: only parsed as an argument of an attribute.
+    String(String),
 }
 
 pub type ParenthesizedExpression = Box<S<Expression>>;
@@ -412,6 +451,9 @@ pub struct TemplateArg {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Statement {
     Void,
+    /// A statement that failed to parse, produced by [`crate::Parser::parse_str_with_recovery`],
+    /// with the range of the source the parser skipped.
+    Error(Span),
     Compound(CompoundStatement),
     Assignment(AssignmentStatement),
     Increment(IncrementStatement),
@@ -12,10 +12,12 @@ impl<T: Display> Display for Indent<T> {
         let inner_display = self.0.to_string();
         let fmt = inner_display
             .lines()
-            .map(|l| if l.is_empty() {
-                "".to_owned()
-            } else {
-                format!("{}{}", indent, l)
+            .map(|l| {
+                if l.is_empty() {
+                    "".to_owned()
+                } else {
+                    format!("{}{}", indent, l)
+                }
             })
             .format("\n");
         write!(f, "{}", fmt)?;
@@ -44,9 +46,13 @@ impl Display for GlobalDirective {
             GlobalDirective::Import(print)
                 if matches!(print.content.value, ImportContent::Item(_)) =>
             {
-                write!(f, "import {};", print)
+                let attrs = fmt_attrs(&print.attributes, false);
+                write!(f, "{attrs}import {};", print)
+            }
+            GlobalDirective::Import(print) => {
+                let attrs = fmt_attrs(&print.attributes, false);
+                write!(f, "{attrs}import {}", print)
             }
-            GlobalDirective::Import(print) => write!(f, "import {}", print),
             GlobalDirective::Extend(print) => write!(f, "{}", print),
         }
     }
@@ -89,12 +95,14 @@ impl Display for GlobalDeclaration {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             GlobalDeclaration::Void => write!(f, ";"),
+            GlobalDeclaration::Error(_) => write!(f, "/* syntax error */;"),
             GlobalDeclaration::Declaration(print) => write!(f, "{}", print),
             GlobalDeclaration::Alias(print) => write!(f, "{}", print),
             GlobalDeclaration::Struct(print) => write!(f, "{}", print),
             GlobalDeclaration::Function(print) => write!(f, "{}", print),
             GlobalDeclaration::ConstAssert(print) => write!(f, "{}", print),
             GlobalDeclaration::Module(print) => write!(f, "{}", print),
+            GlobalDeclaration::Interface(print) => write!(f, "{}", print),
         }
     }
 }
@@ -137,10 +145,11 @@ impl Display for DeclarationKind {
 
 impl Display for Alias {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let attrs = fmt_attrs(&self.attributes, false);
         let name = &self.name;
         let typ = &self.typ;
         let template_params = fmt_template_params(&self.template_parameters);
-        write!(f, "alias {name}{template_params} = {typ};")
+        write!(f, "{attrs}alias {name}{template_params} = {typ};")
     }
 }
 
@@ -156,10 +165,11 @@ fn fmt_template_params(params: &[S<FormalTemplateParameter>]) -> String {
 
 impl Display for Struct {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let attrs = fmt_attrs(&self.attributes, false);
         let name = &self.name;
         let members = Indent(self.members.iter().format(",\n"));
         let template_params = fmt_template_params(&self.template_parameters);
-        write!(f, "struct {name}{template_params} {{\n{members}\n}}")
+        write!(f, "{attrs}struct {name}{template_params} {{\n{members}\n}}")
     }
 }
 
@@ -203,9 +213,10 @@ impl Display for FormalParameter {
 
 impl Display for ConstAssert {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let attrs = fmt_attrs(&self.attributes, false);
         let expr = &self.expression;
         let template_params = fmt_template_params(&self.template_parameters);
-        write!(f, "const_assert{template_params} {expr};",)
+        write!(f, "{attrs}const_assert{template_params} {expr};",)
     }
 }
 
@@ -268,6 +279,7 @@ impl Display for LiteralExpression {
             LiteralExpression::U32(num) => write!(f, "{num}u"),
             LiteralExpression::F32(num) => write!(f, "{}f", num.parse::<f32>().unwrap()),
             LiteralExpression::F16(num) => write!(f, "{}h", num.parse::<f32>().unwrap()),
+            LiteralExpression::String(string) => write!(f, "\"{string}\""),
         }
     }
 }
@@ -403,6 +415,7 @@ impl Display for Statement {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Statement::Void => write!(f, ";"),
+            Statement::Error(_) => write!(f, "/* syntax error */;"),
             Statement::Compound(print) => write!(f, "{}", print),
             Statement::Assignment(print) => write!(f, "{}", print),
             Statement::Increment(expr) => write!(f, "{}++;", expr),
@@ -435,10 +448,12 @@ impl Display for CompoundDirective {
             CompoundDirective::Import(usage)
                 if matches!(usage.content.value, ImportContent::Item(_)) =>
             {
-                writeln!(f, "import {usage};")?;
+                let attrs = fmt_attrs(&usage.attributes, false);
+                writeln!(f, "{attrs}import {usage};")?;
             }
             CompoundDirective::Import(usage) => {
-                writeln!(f, "import {usage}")?;
+                let attrs = fmt_attrs(&usage.attributes, false);
+                writeln!(f, "{attrs}import {usage}")?;
             }
         }
         Ok(())
@@ -451,10 +466,12 @@ impl Display for ModuleDirective {
             ModuleDirective::Import(usage)
                 if matches!(usage.content.value, ImportContent::Item(_)) =>
             {
-                writeln!(f, "import {usage};\n")?;
+                let attrs = fmt_attrs(&usage.attributes, false);
+                writeln!(f, "{attrs}import {usage};\n")?;
             }
             ModuleDirective::Import(usage) => {
-                writeln!(f, "import {usage}\n")?;
+                let attrs = fmt_attrs(&usage.attributes, false);
+                writeln!(f, "{attrs}import {usage}\n")?;
             }
             ModuleDirective::Extend(extend) => {
                 writeln!(f, "{extend}")?;
@@ -659,12 +676,14 @@ impl Display for ModuleMemberDeclaration {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             &ModuleMemberDeclaration::Void => write!(f, ";"),
+            &ModuleMemberDeclaration::Error(_) => write!(f, "/* syntax error */;"),
             ModuleMemberDeclaration::Declaration(print) => write!(f, "{}", print),
             ModuleMemberDeclaration::Alias(print) => write!(f, "{}", print),
             ModuleMemberDeclaration::Struct(print) => write!(f, "{}", print),
             ModuleMemberDeclaration::Function(print) => write!(f, "{}", print),
             ModuleMemberDeclaration::ConstAssert(print) => write!(f, "{}", print),
             ModuleMemberDeclaration::Module(print) => write!(f, "{}", print),
+            ModuleMemberDeclaration::Interface(print) => write!(f, "{}", print),
         }
     }
 }
@@ -692,6 +711,49 @@ impl Display for Module {
     }
 }
 
+impl Display for ModuleTypeSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let attrs = fmt_attrs(&self.attributes, false);
+        let members = self.members.iter().format("\n\n");
+        let directives = self.directives.iter().format("\n");
+        let template_params = fmt_template_params(&self.template_parameters);
+        write!(
+            f,
+            "{attrs}interface {}{template_params} {{\n{}\n}}",
+            self.name,
+            Indent(format!("{}{}", directives, members))
+        )
+    }
+}
+
+impl Display for ModuleTypeSignatureMemberDeclaration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ModuleTypeSignatureMemberDeclaration::Void => write!(f, ";"),
+            ModuleTypeSignatureMemberDeclaration::Declaration(print) => write!(f, "{}", print),
+            ModuleTypeSignatureMemberDeclaration::Alias(name) => write!(f, "alias {name};"),
+            ModuleTypeSignatureMemberDeclaration::Struct(print) => write!(f, "{}", print),
+            ModuleTypeSignatureMemberDeclaration::Function(print) => write!(f, "{}", print),
+            ModuleTypeSignatureMemberDeclaration::DefaultFunction(print) => write!(f, "{}", print),
+        }
+    }
+}
+
+impl Display for FunctionSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let attrs = fmt_attrs(&self.attributes, false);
+        let name = &self.name;
+        let params = self.parameters.iter().format(", ");
+        let ret_attrs = fmt_attrs(&self.return_attributes, true);
+        let ret_typ = self
+            .return_type
+            .as_ref()
+            .map(|typ| format!(" -> {ret_attrs}{}", typ))
+            .unwrap_or_default();
+        write!(f, "{attrs}fn {name}({params}){ret_typ};")
+    }
+}
+
 impl Display for ImportContent {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -737,14 +799,14 @@ impl Display for InlineTemplateArgs {
     }
 }
 
+// the attributes of an import are printed before the `import` keyword, by the directive
 impl Display for Import {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let attrs = fmt_attrs(&self.attributes, false);
         let path = self.path.iter().format("::").to_string();
         if !path.is_empty() {
-            write!(f, "{attrs}{path}::{}", self.content.value)?;
+            write!(f, "{path}::{}", self.content.value)?;
         } else {
-            write!(f, "{attrs}{}", self.content.value)?;
+            write!(f, "{}", self.content.value)?;
         };
         Ok(())
     }
@@ -762,10 +824,13 @@ impl Display for TemplateArg {
 
 impl Display for FormalTemplateParameter {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(bound) = self.bound.as_ref() {
+            write!(f, ": {bound}")?;
+        }
         if let Some(default_value) = self.default_value.as_ref() {
-            write!(f, "{} = {default_value}", self.name)
-        } else {
-            write!(f, "{}", self.name)
+            write!(f, " = {default_value}")?;
         }
+        Ok(())
     }
 }
@@ -20,12 +20,16 @@ impl From<GlobalDeclaration> for ModuleMemberDeclaration {
     fn from(value: GlobalDeclaration) -> Self {
         match value {
             GlobalDeclaration::Void => ModuleMemberDeclaration::Void,
+            GlobalDeclaration::Error(span) => ModuleMemberDeclaration::Error(span),
             GlobalDeclaration::Declaration(decl) => ModuleMemberDeclaration::Declaration(decl),
             GlobalDeclaration::Alias(alias) => ModuleMemberDeclaration::Alias(alias),
             GlobalDeclaration::Struct(strct) => ModuleMemberDeclaration::Struct(strct),
             GlobalDeclaration::Function(func) => ModuleMemberDeclaration::Function(func),
             GlobalDeclaration::ConstAssert(ass) => ModuleMemberDeclaration::ConstAssert(ass),
             GlobalDeclaration::Module(module) => ModuleMemberDeclaration::Module(module),
+            GlobalDeclaration::Interface(interface) => {
+                ModuleMemberDeclaration::Interface(interface)
+            }
         }
     }
 }
@@ -34,12 +38,16 @@ impl From<ModuleMemberDeclaration> for GlobalDeclaration {
     fn from(value: ModuleMemberDeclaration) -> Self {
         match value {
             ModuleMemberDeclaration::Void => GlobalDeclaration::Void,
+            ModuleMemberDeclaration::Error(span) => GlobalDeclaration::Error(span),
             ModuleMemberDeclaration::Declaration(decl) => GlobalDeclaration::Declaration(decl),
             ModuleMemberDeclaration::Alias(alias) => GlobalDeclaration::Alias(alias),
             ModuleMemberDeclaration::Struct(strct) => GlobalDeclaration::Struct(strct),
             ModuleMemberDeclaration::Function(func) => GlobalDeclaration::Function(func),
             ModuleMemberDeclaration::ConstAssert(ass) => GlobalDeclaration::ConstAssert(ass),
             ModuleMemberDeclaration::Module(module) => GlobalDeclaration::Module(module),
+            ModuleMemberDeclaration::Interface(interface) => {
+                GlobalDeclaration::Interface(interface)
+            }
         }
     }
 }
@@ -85,6 +93,7 @@ impl ModuleMemberDeclaration {
             ModuleMemberDeclaration::Struct(s) => Some(s.name.clone()),
             ModuleMemberDeclaration::Function(f) => Some(f.name.clone()),
             ModuleMemberDeclaration::Module(m) => Some(m.name.clone()),
+            ModuleMemberDeclaration::Interface(i) => Some(i.name.clone()),
             _ => None,
         }
     }
@@ -95,7 +104,9 @@ impl ModuleMemberDeclaration {
             ModuleMemberDeclaration::Struct(s) => Some(&mut s.name),
             ModuleMemberDeclaration::Function(f) => Some(&mut f.name),
             ModuleMemberDeclaration::Module(m) => Some(&mut m.name),
+            ModuleMemberDeclaration::Interface(i) => Some(&mut i.name),
             ModuleMemberDeclaration::Void => None,
+            ModuleMemberDeclaration::Error(_) => None,
             ModuleMemberDeclaration::ConstAssert(_) => None,
         }
     }
@@ -108,7 +119,9 @@ impl ModuleMemberDeclaration {
             ModuleMemberDeclaration::Declaration(decl) => Some(&mut decl.template_parameters),
             ModuleMemberDeclaration::Alias(decl) => Some(&mut decl.template_parameters),
             ModuleMemberDeclaration::ConstAssert(decl) => Some(&mut decl.template_parameters),
+            ModuleMemberDeclaration::Interface(decl) => Some(&mut decl.template_parameters),
             ModuleMemberDeclaration::Void => None,
+            ModuleMemberDeclaration::Error(_) => None,
         }
     }
 
@@ -120,12 +133,27 @@ impl ModuleMemberDeclaration {
             ModuleMemberDeclaration::Declaration(decl) => Some(&decl.template_parameters),
             ModuleMemberDeclaration::Alias(decl) => Some(&decl.template_parameters),
             ModuleMemberDeclaration::Void => None,
+            ModuleMemberDeclaration::Error(_) => None,
             ModuleMemberDeclaration::ConstAssert(decl) => Some(&decl.template_parameters),
+            ModuleMemberDeclaration::Interface(decl) => Some(&decl.template_parameters),
         }
         .and_then(|x| if x.is_empty() { None } else { Some(x) })
     }
 }
 
+impl ModuleTypeSignatureMemberDeclaration {
+    pub fn name(&self) -> Option<S<String>> {
+        match self {
+            ModuleTypeSignatureMemberDeclaration::Declaration(d) => Some(d.name.clone()),
+            ModuleTypeSignatureMemberDeclaration::Alias(name) => Some(name.clone()),
+            ModuleTypeSignatureMemberDeclaration::Struct(s) => Some(s.name.clone()),
+            ModuleTypeSignatureMemberDeclaration::Function(f) => Some(f.name.clone()),
+            ModuleTypeSignatureMemberDeclaration::DefaultFunction(f) => Some(f.name.clone()),
+            ModuleTypeSignatureMemberDeclaration::Void => None,
+        }
+    }
+}
+
 impl GlobalDeclaration {
     pub fn name(&self) -> Option<S<String>> {
         match self {
@@ -134,7 +162,9 @@ impl GlobalDeclaration {
             GlobalDeclaration::Struct(s) => Some(s.name.clone()),
             GlobalDeclaration::Function(f) => Some(f.name.clone()),
             GlobalDeclaration::Module(m) => Some(m.name.clone()),
+            GlobalDeclaration::Interface(i) => Some(i.name.clone()),
             GlobalDeclaration::Void => None,
+            GlobalDeclaration::Error(_) => None,
             GlobalDeclaration::ConstAssert(_) => None,
         }
     }
@@ -146,7 +176,9 @@ impl GlobalDeclaration {
             GlobalDeclaration::Struct(s) => Some(&mut s.name),
             GlobalDeclaration::Function(f) => Some(&mut f.name),
             GlobalDeclaration::Module(m) => Some(&mut m.name),
+            GlobalDeclaration::Interface(i) => Some(&mut i.name),
             GlobalDeclaration::Void => None,
+            GlobalDeclaration::Error(_) => None,
             GlobalDeclaration::ConstAssert(_) => None,
         }
     }
@@ -159,7 +191,9 @@ impl GlobalDeclaration {
             GlobalDeclaration::Declaration(decl) => Some(&mut decl.template_parameters),
             GlobalDeclaration::Alias(alias) => Some(&mut alias.template_parameters),
             GlobalDeclaration::Void => None,
+            GlobalDeclaration::Error(_) => None,
             GlobalDeclaration::ConstAssert(assrt) => Some(&mut assrt.template_parameters),
+            GlobalDeclaration::Interface(i) => Some(&mut i.template_parameters),
         }
     }
 
@@ -171,7 +205,9 @@ impl GlobalDeclaration {
             GlobalDeclaration::Declaration(decl) => Some(&decl.template_parameters),
             GlobalDeclaration::Alias(alias) => Some(&alias.template_parameters),
             GlobalDeclaration::Void => None,
+            GlobalDeclaration::Error(_) => None,
             GlobalDeclaration::ConstAssert(assrt) => Some(&assrt.template_parameters),
+            GlobalDeclaration::Interface(i) => Some(&i.template_parameters),
         }
         .and_then(|x| if x.is_empty() { None } else { Some(x) })
     }
@@ -1,2 +1,4 @@
 pub mod resolver;
+mod suggestions;
+
 pub use resolver::*;
@@ -1,24 +1,35 @@
-use std::fmt::Debug;
+use std::{collections::HashSet, fmt::Debug};
 
 use mew_parse::{
     span::Spanned,
     syntax::{
         Alias, CompoundDirective, CompoundStatement, ConstAssert, Declaration,
-        DeclarationStatement, Expression, ExtendDirective, Function, GlobalDeclaration,
-        GlobalDirective, IdentifierExpression, Import, Module, ModuleDirective,
-        ModuleMemberDeclaration, PathPart, Statement, Struct, TemplateArg, TranslationUnit,
-        TypeExpression,
+        DeclarationStatement, Expression, ExtendDirective, Function, FunctionSignature,
+        GlobalDeclaration, GlobalDirective, IdentifierExpression, Import, Module, ModuleDirective,
+        ModuleMemberDeclaration, ModuleTypeSignature, ModuleTypeSignatureMemberDeclaration,
+        PathPart, Statement, Struct, TemplateArg, TranslationUnit, TypeExpression,
     },
 };
 use mew_types::{
-    CompilerPass, CompilerPassError, CompilerPassResult,
+    CompilerPass, CompilerPassError, CompilerPassResult, Diagnostics, Suggestion,
     builtins::{get_builtin_functions, get_builtin_tokens},
     mangling::mangle_inline_arg_name,
 };
 
+use crate::suggestions::{MAX_SUGGESTIONS, similar_names};
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Resolver;
 
+/// Provides the root modules that a translation unit references but does not declare, so
+/// that they can be loaded while resolving it.
+pub trait ModuleProvider {
+    /// Returns the root module `path[0]`, or `None` if there is no such module. `path` is
+    /// the path that could not be resolved, so the modules nested in the root module along
+    /// the rest of it can be provided too.
+    fn provide(&mut self, path: &[String]) -> Option<Spanned<Module>>;
+}
+
 #[derive(Debug, PartialEq, Clone, Hash)]
 struct ModulePath(im::Vector<PathPart>);
 
@@ -29,6 +40,8 @@ enum ScopeMember {
     ModuleMemberDeclaration(ModulePath, ModuleMemberDeclaration),
     ImportDeclaration(ModulePath, Option<Vec<Spanned<TemplateArg>>>),
     GlobalDeclaration(GlobalDeclaration),
+    /// A member of an interface, at the path of the interface.
+    InterfaceMember(ModulePath),
     FormalFunctionParameter,
     TemplateParam(String),
     Inline(ModulePath),
@@ -39,6 +52,7 @@ impl Resolver {
         statement: &mut CompoundStatement,
         module_path: ModulePath,
         mut scope: im::HashMap<String, ScopeMember>,
+        diagnostics: &mut Diagnostics,
     ) -> Result<(), Box<CompilerPassError>> {
         for CompoundDirective::Import(usage) in
             statement.directives.iter_mut().map(|x| &mut x.value)
@@ -46,7 +60,13 @@ impl Resolver {
             Self::add_usage_to_scope(usage, module_path.clone(), &mut scope)?;
         }
         for c in statement.statements.iter_mut() {
-            Self::statement_to_absolute_paths(c, module_path.clone(), scope.clone())?;
+            let result = Self::statement_to_absolute_paths(
+                c,
+                module_path.clone(),
+                scope.clone(),
+                diagnostics,
+            );
+            diagnostics.report(result);
         }
         Ok(())
     }
@@ -55,13 +75,14 @@ impl Resolver {
         statement: &mut Statement,
         module_path: ModulePath,
         mut scope: im::HashMap<String, ScopeMember>,
+        diagnostics: &mut Diagnostics,
     ) -> Result<(), Box<CompilerPassError>> {
         match statement {
-            Statement::Void => {
+            Statement::Void | Statement::Error(_) => {
                 // No action required
             }
             Statement::Compound(c) => {
-                Self::compound_statement_to_absolute_paths(c, module_path, scope)?;
+                Self::compound_statement_to_absolute_paths(c, module_path, scope, diagnostics)?;
             }
             Statement::Assignment(a) => {
                 Self::expression_to_absolute_paths(&mut a.lhs, module_path.clone(), scope.clone())?;
@@ -83,6 +104,7 @@ impl Resolver {
                     &mut iff.if_clause.1,
                     module_path.clone(),
                     scope.clone(),
+                    diagnostics,
                 )?;
                 for (else_if_expr, else_if_statements) in iff.else_if_clauses.iter_mut() {
                     Self::expression_to_absolute_paths(
@@ -94,10 +116,16 @@ impl Resolver {
                         else_if_statements,
                         module_path.clone(),
                         scope.clone(),
+                        diagnostics,
                     )?;
                 }
                 if let Some(else_clause) = iff.else_clause.as_mut() {
-                    Self::compound_statement_to_absolute_paths(else_clause, module_path, scope)?;
+                    Self::compound_statement_to_absolute_paths(
+                        else_clause,
+                        module_path,
+                        scope,
+                        diagnostics,
+                    )?;
                 }
             }
             Statement::Switch(s) => {
@@ -125,6 +153,7 @@ impl Resolver {
                         &mut clause.body,
                         module_path.clone(),
                         scope.clone(),
+                        diagnostics,
                     )?;
                 }
             }
@@ -137,6 +166,7 @@ impl Resolver {
                     &mut l.body,
                     module_path.clone(),
                     scope.clone(),
+                    diagnostics,
                 )?;
                 // Unfortunate asymmetry (and redundant work) here as the continuing statement is within the same scope
                 for c in l.body.statements.iter_mut() {
@@ -158,6 +188,7 @@ impl Resolver {
                         &mut l.body,
                         module_path.clone(),
                         scope.clone(),
+                        diagnostics,
                     )?;
                     for c in cont.body.statements.iter_mut() {
                         if let Statement::Declaration(decl) = c.as_ref() {
@@ -175,6 +206,7 @@ impl Resolver {
                         init.as_mut(),
                         module_path.clone(),
                         scope.clone(),
+                        diagnostics,
                     )?;
                     if let Statement::Declaration(d) = init.as_mut().as_mut() {
                         scope.insert(
@@ -191,9 +223,15 @@ impl Resolver {
                         update.as_mut(),
                         module_path.clone(),
                         scope.clone(),
+                        diagnostics,
                     )?;
                 }
-                Self::compound_statement_to_absolute_paths(&mut f.body, module_path, scope)?;
+                Self::compound_statement_to_absolute_paths(
+                    &mut f.body,
+                    module_path,
+                    scope,
+                    diagnostics,
+                )?;
             }
             Statement::While(w) => {
                 Self::expression_to_absolute_paths(
@@ -201,7 +239,12 @@ impl Resolver {
                     module_path.clone(),
                     scope.clone(),
                 )?;
-                Self::compound_statement_to_absolute_paths(&mut w.body, module_path, scope)?;
+                Self::compound_statement_to_absolute_paths(
+                    &mut w.body,
+                    module_path,
+                    scope,
+                    diagnostics,
+                )?;
             }
             Statement::Break => {
                 // No action required
@@ -235,16 +278,31 @@ impl Resolver {
                 )?;
             }
             Statement::Declaration(d) => {
+                // the statements following the declaration are nested in it, so they are
+                // resolved even if the declaration itself fails to
                 if let Some(init) = d.declaration.initializer.as_mut() {
-                    Self::expression_to_absolute_paths(init, module_path.clone(), scope.clone())?;
+                    let result = Self::expression_to_absolute_paths(
+                        init,
+                        module_path.clone(),
+                        scope.clone(),
+                    );
+                    diagnostics.report(result);
                 }
                 if let Some(typ) = d.declaration.typ.as_mut() {
-                    Self::type_to_absolute_path(typ, module_path.clone(), scope.clone())?;
+                    let result =
+                        Self::type_to_absolute_path(typ, module_path.clone(), scope.clone());
+                    diagnostics.report(result);
                 };
                 let name = d.declaration.name.value.clone();
                 scope.insert(name, ScopeMember::LocalDeclaration);
                 for s in d.statements.iter_mut() {
-                    Self::statement_to_absolute_paths(s, module_path.clone(), scope.clone())?;
+                    let result = Self::statement_to_absolute_paths(
+                        s,
+                        module_path.clone(),
+                        scope.clone(),
+                        diagnostics,
+                    );
+                    diagnostics.report(result);
                 }
             }
         };
@@ -302,6 +360,7 @@ impl Resolver {
         module: &mut Module,
         mut module_path: ModulePath,
         mut scope: im::HashMap<String, ScopeMember>,
+        diagnostics: &mut Diagnostics,
     ) -> Result<(), Box<CompilerPassError>> {
         Self::update_module_scope(&mut module_path, module, &mut scope)?;
         Self::add_extensions_and_usages_to_scope(
@@ -312,33 +371,56 @@ impl Resolver {
         )?;
 
         for decl in module.members.iter_mut() {
-            match decl.as_mut() {
-                ModuleMemberDeclaration::Void => {
-                    // NO ACTION REQUIRED REQUIRED
+            let result = match decl.as_mut() {
+                ModuleMemberDeclaration::Void | ModuleMemberDeclaration::Error(_) => {
+                    // NO ACTION REQUIRED
+                    Ok(())
                 }
                 ModuleMemberDeclaration::Declaration(decl) => {
-                    Self::decl_to_absolute_path(decl, module_path.clone(), scope.clone())?;
+                    Self::decl_to_absolute_path(decl, module_path.clone(), scope.clone())
                 }
                 ModuleMemberDeclaration::Alias(a) => {
-                    Self::alias_to_absolute_path(a, module_path.clone(), scope.clone())?;
+                    Self::alias_to_absolute_path(a, module_path.clone(), scope.clone())
                 }
                 ModuleMemberDeclaration::Struct(s) => {
-                    Self::struct_to_absolute_path(s, module_path.clone(), scope.clone())?;
+                    Self::struct_to_absolute_path(s, module_path.clone(), scope.clone())
                 }
                 ModuleMemberDeclaration::Function(f) => {
-                    Self::func_to_absolute_path(f, module_path.clone(), scope.clone())?;
+                    Self::func_to_absolute_path(f, module_path.clone(), scope.clone(), diagnostics)
                 }
                 ModuleMemberDeclaration::ConstAssert(assrt) => {
-                    Self::const_assert_to_absolute_path(assrt, module_path.clone(), scope.clone())?;
-                }
-                ModuleMemberDeclaration::Module(m) => {
-                    Self::module_to_absolute_path(m, module_path.clone(), scope.clone())?;
+                    Self::const_assert_to_absolute_path(assrt, module_path.clone(), scope.clone())
                 }
-            }
+                ModuleMemberDeclaration::Module(m) => Self::module_to_absolute_path(
+                    m,
+                    module_path.clone(),
+                    scope.clone(),
+                    diagnostics,
+                ),
+                ModuleMemberDeclaration::Interface(i) => Self::interface_to_absolute_path(
+                    i,
+                    module_path.clone(),
+                    scope.clone(),
+                    diagnostics,
+                ),
+            };
+            diagnostics.report(result);
         }
         Ok(())
     }
 
+    /// Resolves a copy of a module, failing on the first error. Errors in the original
+    /// module are reported when resolving it.
+    fn derived_module_to_absolute_path(
+        module: &mut Module,
+        module_path: ModulePath,
+        scope: im::HashMap<String, ScopeMember>,
+    ) -> Result<(), Box<CompilerPassError>> {
+        let mut diagnostics = Diagnostics::default();
+        Self::module_to_absolute_path(module, module_path, scope, &mut diagnostics)?;
+        diagnostics.first_error()
+    }
+
     fn append_from_scope(
         mut scope: im::HashMap<String, ScopeMember>,
         path: &mut Spanned<Vec<PathPart>>,
@@ -352,11 +434,15 @@ impl Resolver {
                     ScopeMember::LocalDeclaration => {
                         // No action required
                     }
-                    ScopeMember::ModuleMemberDeclaration(module_path, _) => {
+                    ScopeMember::ModuleMemberDeclaration(module_path, _)
+                    | ScopeMember::InterfaceMember(module_path) => {
                         let mut new_path = module_path.0.iter().cloned().collect::<Vec<PathPart>>();
                         new_path.extend(path.iter().cloned());
                         path.value = new_path;
                     }
+                    ScopeMember::GlobalDeclaration(GlobalDeclaration::Module(module)) => {
+                        Self::check_nested_modules(&module, path, &scope)?;
+                    }
                     ScopeMember::GlobalDeclaration(_) => {
                         // No action required
                     }
@@ -392,6 +478,7 @@ impl Resolver {
                 return Err(CompilerPassError::SymbolNotFound(
                     path.value.clone().to_owned(),
                     path.span(),
+                    Self::suggestions(&scope, &path.value),
                 )
                 .into());
             }
@@ -399,6 +486,130 @@ impl Resolver {
         Ok(())
     }
 
+    /// Checks that the modules along a path into a global module declare the next part of
+    /// the path, so that paths into nested modules which are not loaded are reported here.
+    /// Modules with `extend` directives are not checked, as they gain members when resolved.
+    fn check_nested_modules(
+        mut module: &Module,
+        path: &Spanned<Vec<PathPart>>,
+        scope: &im::HashMap<String, ScopeMember>,
+    ) -> Result<(), Box<CompilerPassError>> {
+        for part in path.iter().skip(1) {
+            // the modules of inline template arguments are added when resolving the module
+            if matches!(scope.get(part.name.as_str()), Some(ScopeMember::Inline(_))) {
+                return Ok(());
+            }
+            if module
+                .directives
+                .iter()
+                .any(|directive| matches!(directive.as_ref(), ModuleDirective::Extend(_)))
+            {
+                return Ok(());
+            }
+            let nested = module
+                .members
+                .iter()
+                .find_map(|member| match member.as_ref() {
+                    ModuleMemberDeclaration::Module(m) if m.name.value == part.name.value => {
+                        Some(m)
+                    }
+                    _ => None,
+                });
+            if let Some(nested) = nested {
+                module = nested;
+                continue;
+            }
+            let member_names = module
+                .members
+                .iter()
+                .filter_map(|member| member.name())
+                .map(|name| name.value)
+                .collect::<Vec<_>>();
+            if member_names.contains(&part.name.value) {
+                return Ok(());
+            }
+            let suggestions = similar_names(&part.name, member_names.iter().map(String::as_str))
+                .into_iter()
+                .map(Suggestion::SimilarName)
+                .collect();
+            return Err(CompilerPassError::SymbolNotFound(
+                path.value.clone(),
+                path.span(),
+                suggestions,
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Suggests the names in scope that are similar to the first part of the path, and
+    /// the members of other modules that have the same name.
+    fn suggestions(scope: &im::HashMap<String, ScopeMember>, path: &[PathPart]) -> Vec<Suggestion> {
+        let Some(name) = path.first().map(|part| part.name.as_str()) else {
+            return vec![];
+        };
+        let mut suggestions = similar_names(name, scope.keys().map(String::as_str))
+            .into_iter()
+            .map(Suggestion::SimilarName)
+            .collect::<Vec<_>>();
+
+        let mut imports = vec![];
+        for (key, member) in scope.iter() {
+            match member {
+                ScopeMember::GlobalDeclaration(GlobalDeclaration::Module(m)) => {
+                    Self::find_members_named(vec![key.clone()], m, name, &mut imports);
+                }
+                ScopeMember::ModuleMemberDeclaration(
+                    module_path,
+                    ModuleMemberDeclaration::Module(m),
+                ) => {
+                    let mut module_path = module_path
+                        .0
+                        .iter()
+                        .map(|part| part.name.value.clone())
+                        .collect::<Vec<_>>();
+                    module_path.push(key.clone());
+                    Self::find_members_named(module_path, m, name, &mut imports);
+                }
+                _ => {}
+            }
+        }
+        imports.sort();
+        imports.dedup();
+        suggestions.extend(
+            imports
+                .into_iter()
+                .take(MAX_SUGGESTIONS)
+                .map(Suggestion::Import),
+        );
+        suggestions
+    }
+
+    /// Collects the paths of the members of the module, and of its nested modules, that
+    /// are called `name`.
+    fn find_members_named(
+        module_path: Vec<String>,
+        module: &Module,
+        name: &str,
+        found: &mut Vec<Vec<String>>,
+    ) {
+        for member in module.members.iter() {
+            if let ModuleMemberDeclaration::Module(m) = member.as_ref() {
+                let mut path = module_path.clone();
+                path.push(m.name.value.clone());
+                Self::find_members_named(path, m, name, found);
+            }
+            if member
+                .name()
+                .is_some_and(|member_name| member_name.value == name)
+            {
+                let mut path = module_path.clone();
+                path.push(name.to_string());
+                found.push(path);
+            }
+        }
+    }
+
     fn relative_path_to_absolute_path(
         mut scope: im::HashMap<String, ScopeMember>,
         module_path: ModulePath,
@@ -453,6 +664,7 @@ impl Resolver {
         func: &mut Function,
         module_path: ModulePath,
         mut scope: im::HashMap<String, ScopeMember>,
+        diagnostics: &mut Diagnostics,
     ) -> Result<(), Box<CompilerPassError>> {
         Self::function_template_parameters_to_absolute_path(module_path.clone(), func, &mut scope)?;
         if let Some(r) = func.return_type.as_mut() {
@@ -464,7 +676,12 @@ impl Resolver {
             scope.insert(p.name.value.clone(), ScopeMember::FormalFunctionParameter);
         }
 
-        Self::compound_statement_to_absolute_paths(&mut func.body, module_path, scope)?;
+        Self::compound_statement_to_absolute_paths(
+            &mut func.body,
+            module_path,
+            scope,
+            diagnostics,
+        )?;
 
         Ok(())
     }
@@ -497,6 +714,9 @@ impl Resolver {
     ) -> Result<(), Box<CompilerPassError>> {
         let mut template_args = vec![];
         for param in module.template_parameters.iter_mut() {
+            if let Some(bound) = param.bound.as_mut() {
+                Self::type_to_absolute_path(bound, module_path.clone(), scope.clone())?;
+            }
             let old_name = param.name.value.clone();
             let new_name =
                 Self::mangle_template_parameter_name(module_path, &module.name, &old_name);
@@ -569,6 +789,9 @@ impl Resolver {
                     scope.clone(),
                 )?;
             }
+            if let Some(bound) = param.bound.as_mut() {
+                Self::type_to_absolute_path(bound, module_path.clone(), scope.clone())?;
+            }
             let old_name = param.name.value.clone();
             let new_name =
                 Self::mangle_template_parameter_name(&module_path, &function.name, &param.name);
@@ -595,6 +818,9 @@ impl Resolver {
                     scope.clone(),
                 )?;
             }
+            if let Some(bound) = param.bound.as_mut() {
+                Self::type_to_absolute_path(bound, module_path.clone(), scope.clone())?;
+            }
             let old_name = param.name.value.clone();
             let new_name =
                 Self::mangle_template_parameter_name(&module_path, &alias.name, &param.name);
@@ -621,6 +847,9 @@ impl Resolver {
                     scope.clone(),
                 )?;
             }
+            if let Some(bound) = param.bound.as_mut() {
+                Self::type_to_absolute_path(bound, module_path.clone(), scope.clone())?;
+            }
             let name = param.name.value.clone();
             scope.insert(name.clone(), ScopeMember::TemplateParam(name));
         }
@@ -640,6 +869,9 @@ impl Resolver {
                     scope.clone(),
                 )?;
             }
+            if let Some(bound) = param.bound.as_mut() {
+                Self::type_to_absolute_path(bound, module_path.clone(), scope.clone())?;
+            }
             let old_name = param.name.value.clone();
             let new_name =
                 Self::mangle_template_parameter_name(&module_path, &declaration.name, &param.name);
@@ -666,6 +898,9 @@ impl Resolver {
                     scope.clone(),
                 )?;
             }
+            if let Some(bound) = param.bound.as_mut() {
+                Self::type_to_absolute_path(bound, module_path.clone(), scope.clone())?;
+            }
             let old_name = param.name.value.clone();
             let new_name =
                 Self::mangle_template_parameter_name(&module_path, &strct.name, &param.name);
@@ -679,6 +914,70 @@ impl Resolver {
         Ok(())
     }
 
+    /// Resolves the members of an interface, in which the other members are in scope at the
+    /// path of the interface. Modules that extend the interface resolve their copies of the
+    /// defaults in their own scope, and the defaults resolved here are rebased onto modules
+    /// that conform to the interface without extending it.
+    fn interface_to_absolute_path(
+        interface: &mut ModuleTypeSignature,
+        mut module_path: ModulePath,
+        mut scope: im::HashMap<String, ScopeMember>,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<(), Box<CompilerPassError>> {
+        module_path.0.push_back(PathPart {
+            name: interface.name.clone(),
+            template_args: None,
+            inline_template_args: None,
+        });
+        for member in interface.members.iter() {
+            if let Some(name) = member.name() {
+                scope.insert(
+                    name.value,
+                    ScopeMember::InterfaceMember(module_path.clone()),
+                );
+            }
+        }
+        for member in interface.members.iter_mut() {
+            match member.as_mut() {
+                ModuleTypeSignatureMemberDeclaration::Void
+                | ModuleTypeSignatureMemberDeclaration::Alias(_) => {
+                    // NO ACTION REQUIRED
+                }
+                ModuleTypeSignatureMemberDeclaration::Declaration(decl) => {
+                    Self::decl_to_absolute_path(decl, module_path.clone(), scope.clone())?;
+                }
+                ModuleTypeSignatureMemberDeclaration::Struct(s) => {
+                    Self::struct_to_absolute_path(s, module_path.clone(), scope.clone())?;
+                }
+                ModuleTypeSignatureMemberDeclaration::DefaultFunction(f) => {
+                    Self::func_to_absolute_path(
+                        f,
+                        module_path.clone(),
+                        scope.clone(),
+                        diagnostics,
+                    )?;
+                }
+                ModuleTypeSignatureMemberDeclaration::Function(FunctionSignature {
+                    parameters,
+                    return_type,
+                    ..
+                }) => {
+                    if let Some(r) = return_type.as_mut() {
+                        Self::type_to_absolute_path(r, module_path.clone(), scope.clone())?;
+                    }
+                    for p in parameters.iter_mut() {
+                        Self::type_to_absolute_path(
+                            &mut p.typ,
+                            module_path.clone(),
+                            scope.clone(),
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn const_assert_to_absolute_path(
         assrt: &mut ConstAssert,
         module_path: ModulePath,
@@ -715,7 +1014,13 @@ impl Resolver {
         let module_path = module_path.clone();
 
         let mut full_path = Spanned::new(path.iter().take(1).cloned().collect(), path.span());
-        Self::append_from_scope(scope.clone(), &mut full_path)?;
+        // the error names the whole path, as its span covers it
+        Self::append_from_scope(scope.clone(), &mut full_path).map_err(|err| match *err {
+            CompilerPassError::SymbolNotFound(_, span, suggestions) => Box::new(
+                CompilerPassError::SymbolNotFound(path.value.clone(), span, suggestions),
+            ),
+            err => Box::new(err),
+        })?;
         if !full_path.is_empty() {
             let to_remove = full_path.len() - 1;
             full_path.remove(to_remove);
@@ -785,7 +1090,7 @@ impl Resolver {
 
                     derived_module.members.push(arg);
                 }
-                Self::module_to_absolute_path(
+                Self::derived_module_to_absolute_path(
                     &mut derived_module,
                     module_path.clone(),
                     inner_scope,
@@ -928,13 +1233,33 @@ impl Resolver {
                             continue 'outer;
                         }
                     }
-                    return Err(
-                        CompilerPassError::SymbolNotFound(path.value.clone(), path.span()).into(),
-                    );
+                    let missing = &remaining_path.head().unwrap().name.value;
+                    let member_names = module
+                        .members
+                        .iter()
+                        .filter_map(|member| member.name())
+                        .map(|name| name.value)
+                        .collect::<Vec<_>>();
+                    let suggestions =
+                        similar_names(missing, member_names.iter().map(String::as_str))
+                            .into_iter()
+                            .map(Suggestion::SimilarName)
+                            .collect();
+                    return Err(CompilerPassError::SymbolNotFound(
+                        path.value.clone(),
+                        path.span(),
+                        suggestions,
+                    )
+                    .into());
                 }
                 Ok((module.clone(), scope))
             }
-            _ => Err(CompilerPassError::SymbolNotFound(path.value.clone(), path.span()).into()),
+            _ => Err(CompilerPassError::SymbolNotFound(
+                path.value.clone(),
+                path.span(),
+                Self::suggestions(&scope, &path.value),
+            )
+            .into()),
         }
     }
 
@@ -980,6 +1305,20 @@ impl Resolver {
 
         // let prior_scope = scope.clone();
         for mut extension in extend_dirs {
+            if let Some(interface) = Self::find_interface(scope, &extension.path) {
+                let declared = members
+                    .iter()
+                    .filter_map(|member| member.name())
+                    .map(|name| name.value)
+                    .collect::<Vec<_>>();
+                members.append(&mut Self::add_interface_defaults_to_scope(
+                    &interface,
+                    &declared,
+                    module_path,
+                    scope,
+                ));
+                continue;
+            }
             let aliases = Self::add_extension_to_scope(&mut extension, module_path, scope)?;
 
             for alias in aliases {
@@ -1003,6 +1342,71 @@ impl Resolver {
         Ok(())
     }
 
+    /// Finds the interface at `path`, if it is one.
+    fn find_interface(
+        scope: &im::HashMap<String, ScopeMember>,
+        path: &Spanned<Vec<PathPart>>,
+    ) -> Option<ModuleTypeSignature> {
+        let (last, prefix) = path.split_last()?;
+        if prefix.is_empty() {
+            return match scope.get(last.name.as_str())? {
+                ScopeMember::GlobalDeclaration(GlobalDeclaration::Interface(interface))
+                | ScopeMember::ModuleMemberDeclaration(
+                    _,
+                    ModuleMemberDeclaration::Interface(interface),
+                ) => Some(interface.clone()),
+                _ => None,
+            };
+        }
+        let prefix = Spanned::new(prefix.to_vec(), path.span());
+        let (module, _) = Self::find_module_and_scope(scope.clone(), &prefix).ok()?;
+        module
+            .members
+            .iter()
+            .find_map(|member| match member.as_ref() {
+                ModuleMemberDeclaration::Interface(interface) if interface.name == last.name => {
+                    Some(interface.clone())
+                }
+                _ => None,
+            })
+    }
+
+    /// Copies the default implementations of an extended interface that are not `declared`
+    /// into the scope, to be resolved as members of the extending module.
+    fn add_interface_defaults_to_scope(
+        interface: &ModuleTypeSignature,
+        declared: &[String],
+        module_path: &ModulePath,
+        scope: &mut im::HashMap<String, ScopeMember>,
+    ) -> Vec<Spanned<ModuleMemberDeclaration>> {
+        let mut defaults = vec![];
+        for member in interface.members.iter() {
+            let default = match member.as_ref() {
+                ModuleTypeSignatureMemberDeclaration::Declaration(decl)
+                    if decl.initializer.is_some() =>
+                {
+                    ModuleMemberDeclaration::Declaration(decl.clone())
+                }
+                ModuleTypeSignatureMemberDeclaration::DefaultFunction(func) => {
+                    ModuleMemberDeclaration::Function(func.clone())
+                }
+                _ => continue,
+            };
+            let Some(name) = default.name() else {
+                continue;
+            };
+            if declared.contains(&name.value) {
+                continue;
+            }
+            scope.insert(
+                name.value,
+                ScopeMember::ModuleMemberDeclaration(module_path.clone(), default.clone()),
+            );
+            defaults.push(Spanned::new(default, member.span()));
+        }
+        defaults
+    }
+
     fn add_extension_to_scope(
         extend: &mut Spanned<ExtendDirective>,
         module_path: &ModulePath,
@@ -1012,7 +1416,7 @@ impl Resolver {
 
         let mut extend_path = extend.path.clone();
         Self::relative_path_to_absolute_path(scope.clone(), module_path.clone(), &mut extend_path)?;
-        Self::module_to_absolute_path(
+        Self::derived_module_to_absolute_path(
             &mut module,
             ModulePath(extend_path.value.into()),
             module_scope,
@@ -1036,6 +1440,7 @@ impl Resolver {
                 });
 
                 let alias = Alias {
+                    attributes: vec![],
                     name: name.clone(),
                     typ: Spanned::new(TypeExpression { path }, extend.span()),
                     template_parameters: member
@@ -1068,6 +1473,8 @@ impl Resolver {
 
     fn translation_unit_to_absolute_path(
         translation_unit: &mut TranslationUnit,
+        diagnostics: &mut Diagnostics,
+        mut provider: Option<&mut dyn ModuleProvider>,
     ) -> CompilerPassResult {
         let module_path = ModulePath(im::Vector::new());
         let mut scope = im::HashMap::new();
@@ -1084,6 +1491,7 @@ impl Resolver {
                 .chain(builtin_tokens.type_aliases.keys())
                 .chain(builtin_functions.functions.keys())
                 .chain(builtin_tokens.primitive_types.iter())
+                .chain(builtin_tokens.type_generators.iter())
                 .map(|x| (x.clone(), ScopeMember::BuiltIn))
                 .collect(),
         );
@@ -1115,6 +1523,26 @@ impl Resolver {
             .append(&mut other_directives);
 
         for mut extend in extend_directives {
+            if let Some(interface) = Self::find_interface(&scope, &extend.path) {
+                let declared = translation_unit
+                    .global_declarations
+                    .iter()
+                    .filter_map(|decl| decl.name())
+                    .map(|name| name.value)
+                    .collect::<Vec<_>>();
+                for default in Self::add_interface_defaults_to_scope(
+                    &interface,
+                    &declared,
+                    &module_path,
+                    &mut scope,
+                ) {
+                    let span = default.span();
+                    translation_unit
+                        .global_declarations
+                        .push(Spanned::new(default.into_inner().into(), span));
+                }
+                continue;
+            }
             let aliases = Self::add_extension_to_scope(&mut extend, &module_path, &mut scope)?;
 
             for alias in aliases {
@@ -1133,38 +1561,165 @@ impl Resolver {
                 .push(Spanned::new(GlobalDirective::Extend(extend.value), span));
         }
 
-        for decl in translation_unit.global_declarations.iter_mut() {
-            match decl.as_mut() {
-                GlobalDeclaration::Void => {
-                    // NO ACTION REQUIRED
-                }
-                GlobalDeclaration::Declaration(decl) => {
-                    Self::decl_to_absolute_path(decl, module_path.clone(), scope.clone())?;
-                }
-                GlobalDeclaration::Alias(a) => {
-                    Self::alias_to_absolute_path(a, module_path.clone(), scope.clone())?;
-                }
-                GlobalDeclaration::Struct(s) => {
-                    Self::struct_to_absolute_path(s, module_path.clone(), scope.clone())?;
-                }
-                GlobalDeclaration::Function(f) => {
-                    Self::func_to_absolute_path(f, module_path.clone(), scope.clone())?;
-                }
-                GlobalDeclaration::ConstAssert(assrt) => {
-                    Self::const_assert_to_absolute_path(assrt, module_path.clone(), scope.clone())?;
-                }
-                GlobalDeclaration::Module(m) => {
-                    Self::module_to_absolute_path(m, module_path.clone(), scope.clone())?;
+        // declarations are resolved by index, as the modules loaded from the provider are
+        // appended to the translation unit while resolving it
+        let mut requested = HashSet::new();
+        let mut provided = HashSet::new();
+        let mut idx = 0;
+        while idx < translation_unit.global_declarations.len() {
+            let decl = &mut translation_unit.global_declarations[idx];
+            let original = provider.is_some().then(|| decl.clone());
+            let mut decl_diagnostics = Diagnostics::default();
+            let result = Self::global_decl_to_absolute_path(
+                decl,
+                module_path.clone(),
+                scope.clone(),
+                &mut decl_diagnostics,
+            );
+            decl_diagnostics.report(result);
+            if let (Some(provider), Some(original)) = (provider.as_deref_mut(), original) {
+                let modules = Self::provide_missing_modules(
+                    provider,
+                    &decl_diagnostics,
+                    &scope,
+                    &provided,
+                    &mut requested,
+                );
+                if !modules.is_empty() {
+                    for module in modules {
+                        let span = module.span();
+                        let name = module.name.value.clone();
+                        let decl = GlobalDeclaration::Module(module.value);
+                        scope.insert(name.clone(), ScopeMember::GlobalDeclaration(decl.clone()));
+                        // a root module provided again, with more nested modules, replaces the
+                        // earlier copy and is resolved after the declarations in the queue
+                        if let Some(old) =
+                            translation_unit.global_declarations.iter().position(|decl| {
+                                matches!(decl.as_ref(), GlobalDeclaration::Module(m) if m.name.value == name)
+                            })
+                        {
+                            translation_unit.global_declarations.remove(old);
+                            if old < idx {
+                                idx -= 1;
+                            }
+                        }
+                        translation_unit
+                            .global_declarations
+                            .push(Spanned::new(decl, span));
+                        provided.insert(name);
+                    }
+                    // resolve the declaration again, now that the modules are in scope
+                    translation_unit.global_declarations[idx] = original;
+                    continue;
                 }
             }
+            diagnostics.errors.append(&mut decl_diagnostics.errors);
+            diagnostics.warnings.append(&mut decl_diagnostics.warnings);
+            idx += 1;
         }
 
         Ok(())
     }
 
+    fn global_decl_to_absolute_path(
+        decl: &mut GlobalDeclaration,
+        module_path: ModulePath,
+        scope: im::HashMap<String, ScopeMember>,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<(), Box<CompilerPassError>> {
+        match decl {
+            GlobalDeclaration::Void | GlobalDeclaration::Error(_) => {
+                // NO ACTION REQUIRED
+                Ok(())
+            }
+            GlobalDeclaration::Declaration(decl) => {
+                Self::decl_to_absolute_path(decl, module_path, scope)
+            }
+            GlobalDeclaration::Alias(a) => Self::alias_to_absolute_path(a, module_path, scope),
+            GlobalDeclaration::Struct(s) => Self::struct_to_absolute_path(s, module_path, scope),
+            GlobalDeclaration::Function(f) => {
+                Self::func_to_absolute_path(f, module_path, scope, diagnostics)
+            }
+            GlobalDeclaration::ConstAssert(assrt) => {
+                Self::const_assert_to_absolute_path(assrt, module_path, scope)
+            }
+            GlobalDeclaration::Module(m) => {
+                Self::module_to_absolute_path(m, module_path, scope, diagnostics)
+            }
+            GlobalDeclaration::Interface(i) => {
+                Self::interface_to_absolute_path(i, module_path, scope, diagnostics)
+            }
+        }
+    }
+
+    /// Asks the provider for the root modules of the paths that were not found, which are
+    /// not in scope and were not asked for before. Paths into a root module the provider
+    /// returned are asked for again, as the provider may load modules nested in it.
+    fn provide_missing_modules(
+        provider: &mut dyn ModuleProvider,
+        diagnostics: &Diagnostics,
+        scope: &im::HashMap<String, ScopeMember>,
+        provided: &HashSet<String>,
+        requested: &mut HashSet<Vec<String>>,
+    ) -> Vec<Spanned<Module>> {
+        let mut modules: Vec<Spanned<Module>> = vec![];
+        for error in diagnostics.errors.iter() {
+            let CompilerPassError::SymbolNotFound(path, _, _) = error else {
+                continue;
+            };
+            let Some(root) = path.first().map(|part| &part.name.value) else {
+                continue;
+            };
+            if (scope.contains_key(root) && !provided.contains(root))
+                || modules.iter().any(|module| &module.name.value == root)
+            {
+                continue;
+            }
+            let path = path
+                .iter()
+                .map(|part| part.name.value.clone())
+                .collect::<Vec<_>>();
+            // a root module is asked for once, and the paths into it once each
+            let key = if provided.contains(root) {
+                path.clone()
+            } else {
+                vec![root.clone()]
+            };
+            if !requested.insert(key) {
+                continue;
+            }
+            let Some(module) = provider.provide(&path) else {
+                continue;
+            };
+            // the provider returns the same module when it has nothing nested to add
+            let unchanged = matches!(
+                scope.get(root),
+                Some(ScopeMember::GlobalDeclaration(GlobalDeclaration::Module(m))) if *m == module.value
+            );
+            if !unchanged {
+                modules.push(module);
+            }
+        }
+        modules
+    }
+
     pub fn resolve_mut(&self, translation_unit: &mut TranslationUnit) -> CompilerPassResult {
-        Self::translation_unit_to_absolute_path(translation_unit)?;
-        Ok(())
+        let mut diagnostics = Diagnostics::default();
+        Self::translation_unit_to_absolute_path(translation_unit, &mut diagnostics, None)?;
+        diagnostics.first_error()
+    }
+
+    /// Resolves the translation unit like [`CompilerPass::apply_mut_with_diagnostics`],
+    /// asking the provider for the root modules it references but does not declare. The
+    /// modules the provider returns are added to the translation unit, and the declaration
+    /// that referenced them is resolved again.
+    pub fn resolve_with_provider(
+        &self,
+        translation_unit: &mut TranslationUnit,
+        diagnostics: &mut Diagnostics,
+        provider: &mut dyn ModuleProvider,
+    ) -> CompilerPassResult {
+        Self::translation_unit_to_absolute_path(translation_unit, diagnostics, Some(provider))
     }
 }
 
@@ -1172,4 +1727,12 @@ impl CompilerPass for Resolver {
     fn apply_mut(&mut self, translation_unit: &mut TranslationUnit) -> CompilerPassResult {
         self.resolve_mut(translation_unit)
     }
+
+    fn apply_mut_with_diagnostics(
+        &mut self,
+        translation_unit: &mut TranslationUnit,
+        diagnostics: &mut Diagnostics,
+    ) -> CompilerPassResult {
+        Self::translation_unit_to_absolute_path(translation_unit, diagnostics, None)
+    }
 }
@@ -10,9 +10,14 @@ use mew_types::{
     mangling::maybe_mangle_template_args_if_needed,
 };
 
+/// Specializes the templates used by the entry points.
+///
+/// Construct it with [`Specializer::new`] or [`Specializer::with_entrypoint`], so that the
+/// way it stores the entry points can change without breaking its users.
 #[derive(Debug, Clone)]
 pub struct Specializer {
-    pub entrypoint: Option<Vec<PathPart>>,
+    /// The paths to specialize, along with the declarations they use.
+    entrypoints: Vec<Vec<PathPart>>,
 }
 
 type ConcreteSymbolPath = im::Vector<String>;
@@ -125,7 +130,9 @@ impl OwnedMember {
         path_part: PathPart,
     ) -> Result<(), Box<CompilerPassError>> {
         match decl {
-            GlobalDeclaration::Void => Ok(()),
+            GlobalDeclaration::Void
+            | GlobalDeclaration::Error(_)
+            | GlobalDeclaration::Interface(_) => Ok(()),
             GlobalDeclaration::Declaration(declaration) => {
                 Self::specialize_declaration(declaration, path_part)
             }
@@ -146,7 +153,9 @@ impl OwnedMember {
         path_part: PathPart,
     ) -> Result<(), Box<CompilerPassError>> {
         match decl {
-            ModuleMemberDeclaration::Void => Ok(()),
+            ModuleMemberDeclaration::Void
+            | ModuleMemberDeclaration::Error(_)
+            | ModuleMemberDeclaration::Interface(_) => Ok(()),
             ModuleMemberDeclaration::Declaration(declaration) => {
                 Self::specialize_declaration(declaration, path_part)
             }
@@ -237,7 +246,7 @@ impl OwnedMember {
         value: &Spanned<TemplateArg>,
     ) -> Result<(), Box<CompilerPassError>> {
         match statement {
-            Statement::Void => Ok(()),
+            Statement::Void | Statement::Error(_) => Ok(()),
             Statement::Compound(compound_statement) => {
                 Self::substitute_compound_statement(compound_statement, name, value)
             }
@@ -671,7 +680,9 @@ impl<'a> BorrowedMember<'a> {
         usages: &mut Usages,
     ) -> Result<(), Box<CompilerPassError>> {
         match decl {
-            GlobalDeclaration::Void => {}
+            GlobalDeclaration::Void
+            | GlobalDeclaration::Error(_)
+            | GlobalDeclaration::Interface(_) => {}
             GlobalDeclaration::Declaration(declaration) => {
                 Self::collect_usages_from_declaration(declaration, usages)?
             }
@@ -702,7 +713,9 @@ impl<'a> BorrowedMember<'a> {
         usages: &mut Usages,
     ) -> Result<(), Box<CompilerPassError>> {
         match decl {
-            ModuleMemberDeclaration::Void => {}
+            ModuleMemberDeclaration::Void
+            | ModuleMemberDeclaration::Error(_)
+            | ModuleMemberDeclaration::Interface(_) => {}
             ModuleMemberDeclaration::Declaration(declaration) => {
                 Self::collect_usages_from_declaration(declaration, usages)?
             }
@@ -890,7 +903,7 @@ impl<'a> BorrowedMember<'a> {
         usages: &mut Usages,
     ) -> Result<(), Box<CompilerPassError>> {
         match statement {
-            Statement::Void => Ok(()),
+            Statement::Void | Statement::Error(_) => Ok(()),
             Statement::Compound(compound_statement) => {
                 Self::collect_usages_from_compound_statement(compound_statement, usages)
             }
@@ -1239,6 +1252,7 @@ impl<'a> Parent<'a> {
             })
             .collect();
         Alias {
+            attributes: vec![],
             name: Spanned::new(
                 maybe_mangle_template_args_if_needed(path_part),
                 path_part.name.span(),
@@ -1269,6 +1283,7 @@ impl<'a> Parent<'a> {
                             entrypoints.push(declaration);
                         }
                     } else {
+                        #[allow(clippy::unnecessary_unwrap)]
                         let name = name.unwrap();
                         let mut symbol_path = symbol_path.clone();
                         symbol_path.push_back(name.value);
@@ -1319,13 +1334,23 @@ impl<'a> Parent<'a> {
 type SymbolPath = im::Vector<String>;
 
 impl Specializer {
+    /// A specializer for several entry points, which share the specializations they use.
+    pub fn new(entrypoints: Vec<Vec<PathPart>>) -> Self {
+        Self { entrypoints }
+    }
+
+    /// A specializer for a single entry point, or for none if `entrypoint` is `None`.
+    pub fn with_entrypoint(entrypoint: Option<Vec<PathPart>>) -> Self {
+        Self::new(entrypoint.into_iter().collect())
+    }
+
     fn specialize_translation_unit<'a>(
         &self,
         translation_unit: &'a mut TranslationUnit,
     ) -> Result<(), Box<CompilerPassError>> {
         let mut symbol_map: SymbolMap = HashMap::new();
         let mut usages: Usages = Usages::new();
-        if let Some(entrypoint) = self.entrypoint.as_ref() {
+        for entrypoint in self.entrypoints.iter() {
             usages.insert(entrypoint.iter().cloned().collect());
         }
         let mut parent: Parent<'a> = Parent::TranslationUnit(translation_unit);
@@ -0,0 +1,722 @@
+use std::collections::HashMap;
+
+use mew_parse::{
+    span::{Span, Spanned},
+    syntax::*,
+};
+use mew_types::{CompilerPassError, CompilerPassResult, UnsatisfiedMember};
+
+/// Aliases are followed at most this many times, so that cyclic aliases terminate.
+const MAX_ALIAS_DEPTH: usize = 32;
+
+/// The values of the template parameters bound along a path, by parameter name.
+type Bindings = HashMap<String, Expression>;
+
+#[derive(Debug, Clone, Copy)]
+enum Found<'a> {
+    Module(&'a Module),
+    Interface(&'a ModuleTypeSignature),
+    Struct(&'a Struct),
+    Function(&'a Function),
+    Declaration(&'a Declaration),
+    Alias(&'a Alias),
+}
+
+impl Found<'_> {
+    fn template_params(&self) -> &[Spanned<FormalTemplateParameter>] {
+        match self {
+            Found::Module(m) => &m.template_parameters,
+            Found::Interface(i) => &i.template_parameters,
+            Found::Struct(s) => &s.template_parameters,
+            Found::Function(f) => &f.template_parameters,
+            Found::Declaration(d) => &d.template_parameters,
+            Found::Alias(a) => &a.template_parameters,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Found::Module(_) => "a module".to_string(),
+            Found::Interface(_) => "an interface".to_string(),
+            Found::Struct(_) => "a struct".to_string(),
+            Found::Function(_) => "a function".to_string(),
+            Found::Declaration(d) => format!("a `{}` declaration", d.kind.value),
+            Found::Alias(a) => format!("an alias of `{}`", a.typ.value),
+        }
+    }
+}
+
+fn found_global<'a>(decl: &'a Spanned<GlobalDeclaration>, name: &str) -> Option<(Found<'a>, Span)> {
+    let found = match decl.as_ref() {
+        GlobalDeclaration::Module(m) if m.name.value == name => Found::Module(m),
+        GlobalDeclaration::Interface(i) if i.name.value == name => Found::Interface(i),
+        GlobalDeclaration::Struct(s) if s.name.value == name => Found::Struct(s),
+        GlobalDeclaration::Function(f) if f.name.value == name => Found::Function(f),
+        GlobalDeclaration::Declaration(d) if d.name.value == name => Found::Declaration(d),
+        GlobalDeclaration::Alias(a) if a.name.value == name => Found::Alias(a),
+        _ => return None,
+    };
+    Some((found, decl.span()))
+}
+
+fn found_member<'a>(
+    decl: &'a Spanned<ModuleMemberDeclaration>,
+    name: &str,
+) -> Option<(Found<'a>, Span)> {
+    let found = match decl.as_ref() {
+        ModuleMemberDeclaration::Module(m) if m.name.value == name => Found::Module(m),
+        ModuleMemberDeclaration::Interface(i) if i.name.value == name => Found::Interface(i),
+        ModuleMemberDeclaration::Struct(s) if s.name.value == name => Found::Struct(s),
+        ModuleMemberDeclaration::Function(f) if f.name.value == name => Found::Function(f),
+        ModuleMemberDeclaration::Declaration(d) if d.name.value == name => Found::Declaration(d),
+        ModuleMemberDeclaration::Alias(a) if a.name.value == name => Found::Alias(a),
+        _ => return None,
+    };
+    Some((found, decl.span()))
+}
+
+/// Replaces the template parameters in `path` with the values bound to them.
+fn substitute_path(path: &[PathPart], bindings: &Bindings) -> Vec<PathPart> {
+    let mut result: Vec<PathPart> = vec![];
+    let mut parts = path.iter();
+    if let Some(fst) = path.first()
+        && fst.template_args.is_none()
+        && let Some(
+            Expression::Identifier(IdentifierExpression { path: bound })
+            | Expression::Type(TypeExpression { path: bound }),
+        ) = bindings.get(&fst.name.value)
+    {
+        result.extend(bound.iter().cloned());
+        parts.next();
+    }
+    for part in parts {
+        let mut part = part.clone();
+        for arg in part.template_args.iter_mut().flatten() {
+            arg.expression.value = substitute_expr(&arg.expression, bindings);
+        }
+        result.push(part);
+    }
+    result
+}
+
+fn substitute_expr(expr: &Expression, bindings: &Bindings) -> Expression {
+    match expr {
+        Expression::Identifier(ident) => Expression::Identifier(IdentifierExpression {
+            path: Spanned::new(substitute_path(&ident.path, bindings), ident.path.span()),
+        }),
+        Expression::Type(typ) => Expression::Type(TypeExpression {
+            path: Spanned::new(substitute_path(&typ.path, bindings), typ.path.span()),
+        }),
+        other => other.clone(),
+    }
+}
+
+/// Binds the template parameters of `found` to the arguments of `part`, the defaults filling
+/// in for arguments that are not passed.
+fn bind(found: &Found, part: &PathPart, bindings: &mut Bindings) {
+    let args = part.template_args.as_deref().unwrap_or_default();
+    for (idx, param) in found.template_params().iter().enumerate() {
+        let value = args
+            .iter()
+            .find(|arg| arg.arg_name.as_ref() == Some(&param.name))
+            .or_else(|| args.get(idx).filter(|arg| arg.arg_name.is_none()))
+            .map(|arg| arg.expression.as_ref())
+            .or(param.default_value.as_deref());
+        if let Some(value) = value {
+            let value = substitute_expr(value, bindings);
+            bindings.insert(param.name.value.clone(), value);
+        }
+    }
+}
+
+/// Finds the declaration at `path`, following aliases when their targets can be found, along
+/// with the path it was found at.
+fn lookup<'a>(
+    path: &[PathPart],
+    translation_unit: &'a TranslationUnit,
+    bindings: &mut Bindings,
+    depth: usize,
+) -> Option<(Found<'a>, Span, Vec<PathPart>)> {
+    let path = substitute_path(path, bindings);
+    let (fst, rest) = path.split_first()?;
+    let (mut found, mut span) = translation_unit
+        .global_declarations
+        .iter()
+        .find_map(|decl| found_global(decl, &fst.name.value))?;
+    bind(&found, fst, bindings);
+    let mut resolved = vec![fst.clone()];
+    for (idx, part) in rest.iter().enumerate() {
+        match found {
+            Found::Module(m) => {
+                (found, span) = m
+                    .members
+                    .iter()
+                    .find_map(|decl| found_member(decl, &part.name.value))?;
+                bind(&found, part, bindings);
+                resolved.push(part.clone());
+            }
+            Found::Alias(a) => {
+                return follow_alias(a, &rest[idx..], translation_unit, bindings, depth);
+            }
+            _ => return None,
+        }
+    }
+    if let Found::Alias(a) = found {
+        let mut alias_bindings = bindings.clone();
+        if let Some(target) = follow_alias(a, &[], translation_unit, &mut alias_bindings, depth) {
+            *bindings = alias_bindings;
+            return Some(target);
+        }
+    }
+    Some((found, span, resolved))
+}
+
+fn follow_alias<'a>(
+    alias: &Alias,
+    rest: &[PathPart],
+    translation_unit: &'a TranslationUnit,
+    bindings: &mut Bindings,
+    depth: usize,
+) -> Option<(Found<'a>, Span, Vec<PathPart>)> {
+    if depth >= MAX_ALIAS_DEPTH {
+        return None;
+    }
+    let mut path = alias.typ.path.value.clone();
+    path.extend(rest.iter().cloned());
+    lookup(&path, translation_unit, bindings, depth + 1)
+}
+
+/// The long form of the predeclared aliases of WGSL, like `vec3<f32>` for `vec3f`.
+fn predeclared_alias(name: &str) -> Option<String> {
+    let (base, suffix) = name.split_at(name.len().checked_sub(1)?);
+    let component = match suffix {
+        "i" => "i32",
+        "u" => "u32",
+        "f" => "f32",
+        "h" => "f16",
+        _ => return None,
+    };
+    let is_vec = matches!(base, "vec2" | "vec3" | "vec4");
+    let is_mat = matches!(
+        base,
+        "mat2x2"
+            | "mat2x3"
+            | "mat2x4"
+            | "mat3x2"
+            | "mat3x3"
+            | "mat3x4"
+            | "mat4x2"
+            | "mat4x3"
+            | "mat4x4"
+    ) && matches!(component, "f32" | "f16");
+    (is_vec || is_mat).then(|| format!("{base}<{component}>"))
+}
+
+/// Compares the types of the members of an argument with the types required by an interface.
+struct Context<'a> {
+    translation_unit: &'a TranslationUnit,
+    /// The path of the interface, which the types of its members are relative to.
+    interface: Vec<String>,
+    /// The path of the module the members of the interface are looked up in.
+    module: Vec<PathPart>,
+}
+
+impl Context<'_> {
+    /// Rewrites a path into the interface to a path into the module.
+    fn rebase(&self, path: &[PathPart]) -> Vec<PathPart> {
+        if path.len() > self.interface.len()
+            && path
+                .iter()
+                .zip(self.interface.iter())
+                .all(|(part, name)| &part.name.value == name)
+        {
+            let mut rebased = self.module.clone();
+            rebased.extend(path[self.interface.len()..].iter().cloned());
+            rebased
+        } else {
+            path.to_vec()
+        }
+    }
+
+    /// A text for the type at `path`, the same for any two paths to the same type.
+    fn canonical_path(&self, path: &[PathPart], bindings: &Bindings, depth: usize) -> String {
+        let path = substitute_path(&self.rebase(path), bindings);
+        let mut bindings = bindings.clone();
+        match lookup(&path, self.translation_unit, &mut bindings, 0) {
+            Some((Found::Alias(a), _, _)) if depth < MAX_ALIAS_DEPTH => {
+                self.canonical_path(&a.typ.path, &bindings, depth + 1)
+            }
+            Some((_, _, resolved)) => self.canonical_parts(&resolved, &bindings, depth),
+            None => match path.as_slice() {
+                [part] if part.template_args.is_none() => {
+                    predeclared_alias(&part.name.value).unwrap_or_else(|| part.name.value.clone())
+                }
+                _ => self.canonical_parts(&path, &bindings, depth),
+            },
+        }
+    }
+
+    fn canonical_parts(&self, path: &[PathPart], bindings: &Bindings, depth: usize) -> String {
+        path.iter()
+            .map(|part| match part.template_args.as_ref() {
+                Some(args) if !args.is_empty() => format!(
+                    "{}<{}>",
+                    part.name.value,
+                    args.iter()
+                        .map(|arg| {
+                            let value = self.canonical_expr(&arg.expression, bindings, depth);
+                            match arg.arg_name.as_ref() {
+                                Some(name) => format!("{} = {value}", name.value),
+                                None => value,
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                _ => part.name.value.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+
+    fn canonical_expr(&self, expr: &Expression, bindings: &Bindings, depth: usize) -> String {
+        match expr {
+            Expression::Identifier(IdentifierExpression { path })
+            | Expression::Type(TypeExpression { path }) => {
+                self.canonical_path(path, bindings, depth)
+            }
+            other => other.to_string(),
+        }
+    }
+
+    fn canonical_type(&self, typ: &TypeExpression, bindings: &Bindings) -> String {
+        self.canonical_path(&typ.path, bindings, 0)
+    }
+
+    /// Why `found` does not satisfy `requirement`, if it does not.
+    fn mismatch(
+        &self,
+        requirement: &ModuleTypeSignatureMemberDeclaration,
+        found: &Found,
+        bindings: &Bindings,
+    ) -> Option<String> {
+        match (requirement, found) {
+            (ModuleTypeSignatureMemberDeclaration::Void, _)
+            | (ModuleTypeSignatureMemberDeclaration::Alias(_), _) => None,
+            (
+                ModuleTypeSignatureMemberDeclaration::Declaration(required),
+                Found::Declaration(d),
+            ) => {
+                if required.kind.value != d.kind.value {
+                    return Some(format!(
+                        "this is {}, expected a `{}` declaration",
+                        found.describe(),
+                        required.kind.value
+                    ));
+                }
+                let (Some(required_typ), Some(typ)) = (required.typ.as_ref(), d.typ.as_ref())
+                else {
+                    return None;
+                };
+                let expected = self.canonical_type(required_typ, bindings);
+                let actual = self.canonical_type(typ, bindings);
+                (expected != actual)
+                    .then(|| format!("this has type `{actual}`, expected `{expected}`"))
+            }
+            (ModuleTypeSignatureMemberDeclaration::Struct(required), Found::Struct(s)) => {
+                required.members.iter().find_map(|required_member| {
+                    let Some(member) = s
+                        .members
+                        .iter()
+                        .find(|member| member.name == required_member.name)
+                    else {
+                        return Some(format!(
+                            "this struct has no member `{}`",
+                            required_member.name.value
+                        ));
+                    };
+                    let expected = self.canonical_type(&required_member.typ, bindings);
+                    let actual = self.canonical_type(&member.typ, bindings);
+                    (expected != actual).then(|| {
+                        format!(
+                            "member `{}` has type `{actual}`, expected `{expected}`",
+                            member.name.value
+                        )
+                    })
+                })
+            }
+            (
+                ModuleTypeSignatureMemberDeclaration::Function(FunctionSignature {
+                    parameters: required_parameters,
+                    return_type: required_return_type,
+                    ..
+                })
+                | ModuleTypeSignatureMemberDeclaration::DefaultFunction(Function {
+                    parameters: required_parameters,
+                    return_type: required_return_type,
+                    ..
+                }),
+                Found::Function(f),
+            ) => {
+                if required_parameters.len() != f.parameters.len() {
+                    return Some(format!(
+                        "this function takes {} parameters, expected {}",
+                        f.parameters.len(),
+                        required_parameters.len()
+                    ));
+                }
+                let parameters = required_parameters
+                    .iter()
+                    .zip(f.parameters.iter())
+                    .find_map(|(required_param, param)| {
+                        let expected = self.canonical_type(&required_param.typ, bindings);
+                        let actual = self.canonical_type(&param.typ, bindings);
+                        (expected != actual).then(|| {
+                            format!(
+                                "parameter `{}` has type `{actual}`, expected `{expected}`",
+                                param.name.value
+                            )
+                        })
+                    });
+                parameters.or_else(|| {
+                    match (required_return_type.as_ref(), f.return_type.as_ref()) {
+                        (None, None) => None,
+                        (Some(required_typ), None) => Some(format!(
+                            "this function returns nothing, expected `{}`",
+                            self.canonical_type(required_typ, bindings)
+                        )),
+                        (None, Some(typ)) => Some(format!(
+                            "this function returns `{}`, expected nothing",
+                            self.canonical_type(typ, bindings)
+                        )),
+                        (Some(required_typ), Some(typ)) => {
+                            let expected = self.canonical_type(required_typ, bindings);
+                            let actual = self.canonical_type(typ, bindings);
+                            (expected != actual).then(|| {
+                                format!("this function returns `{actual}`, expected `{expected}`")
+                            })
+                        }
+                    }
+                })
+            }
+            (ModuleTypeSignatureMemberDeclaration::Declaration(required), _) => Some(format!(
+                "this is {}, expected a `{}` declaration",
+                found.describe(),
+                required.kind.value
+            )),
+            (ModuleTypeSignatureMemberDeclaration::Struct(_), _) => {
+                Some(format!("this is {}, expected a struct", found.describe()))
+            }
+            (
+                ModuleTypeSignatureMemberDeclaration::Function(_)
+                | ModuleTypeSignatureMemberDeclaration::DefaultFunction(_),
+                _,
+            ) => Some(format!("this is {}, expected a function", found.describe())),
+        }
+    }
+}
+
+/// A default of an interface, copied into a module that conforms to the interface without
+/// extending it, with its paths into the interface rebased onto the module.
+#[derive(Debug, Clone)]
+pub(crate) struct InheritedDefault {
+    pub(crate) module: Vec<String>,
+    pub(crate) member: Spanned<ModuleMemberDeclaration>,
+}
+
+/// Rewrites the paths of a default from the interface to the module that inherits it.
+impl Context<'_> {
+    fn rebase_path_mut(&self, path: &mut Spanned<Vec<PathPart>>) {
+        path.value = self.rebase(path);
+        for arg in path
+            .iter_mut()
+            .flat_map(|part| part.template_args.iter_mut().flatten())
+        {
+            self.rebase_expression(&mut arg.expression);
+        }
+    }
+
+    fn rebase_attributes(&self, attributes: &mut [Spanned<Attribute>]) {
+        for arg in attributes
+            .iter_mut()
+            .flat_map(|attr| attr.arguments.iter_mut().flatten())
+        {
+            self.rebase_expression(arg);
+        }
+    }
+
+    fn rebase_expression(&self, expression: &mut Expression) {
+        match expression {
+            Expression::Literal(_) => {}
+            Expression::Parenthesized(inner) => self.rebase_expression(inner),
+            Expression::NamedComponent(named) => self.rebase_expression(&mut named.base),
+            Expression::Indexing(indexing) => {
+                self.rebase_expression(&mut indexing.base);
+                self.rebase_expression(&mut indexing.index);
+            }
+            Expression::Unary(unary) => self.rebase_expression(&mut unary.operand),
+            Expression::Binary(binary) => {
+                self.rebase_expression(&mut binary.left);
+                self.rebase_expression(&mut binary.right);
+            }
+            Expression::FunctionCall(call) => self.rebase_call(call),
+            Expression::Identifier(IdentifierExpression { path })
+            | Expression::Type(TypeExpression { path }) => self.rebase_path_mut(path),
+        }
+    }
+
+    fn rebase_call(&self, call: &mut FunctionCallExpression) {
+        self.rebase_path_mut(&mut call.path);
+        for arg in call.arguments.iter_mut() {
+            self.rebase_expression(arg);
+        }
+    }
+
+    fn rebase_compound_statement(&self, compound: &mut CompoundStatement) {
+        self.rebase_attributes(&mut compound.attributes);
+        for statement in compound.statements.iter_mut() {
+            self.rebase_statement(statement);
+        }
+    }
+
+    fn rebase_statement(&self, statement: &mut Statement) {
+        match statement {
+            Statement::Void
+            | Statement::Error(_)
+            | Statement::Break
+            | Statement::Continue
+            | Statement::Discard => {}
+            Statement::Compound(compound) => self.rebase_compound_statement(compound),
+            Statement::Assignment(assignment) => {
+                self.rebase_expression(&mut assignment.lhs);
+                self.rebase_expression(&mut assignment.rhs);
+            }
+            Statement::Increment(expr) | Statement::Decrement(expr) => self.rebase_expression(expr),
+            Statement::If(if_statement) => {
+                self.rebase_attributes(&mut if_statement.attributes);
+                for (condition, body) in std::iter::once(&mut if_statement.if_clause)
+                    .chain(if_statement.else_if_clauses.iter_mut())
+                {
+                    self.rebase_expression(condition);
+                    self.rebase_compound_statement(body);
+                }
+                if let Some(body) = if_statement.else_clause.as_mut() {
+                    self.rebase_compound_statement(body);
+                }
+            }
+            Statement::Switch(switch) => {
+                self.rebase_attributes(&mut switch.attributes);
+                self.rebase_attributes(&mut switch.body_attributes);
+                self.rebase_expression(&mut switch.expression);
+                for clause in switch.clauses.iter_mut() {
+                    for selector in clause.case_selectors.iter_mut() {
+                        if let CaseSelector::Expression(expr) = selector.as_mut() {
+                            self.rebase_expression(expr);
+                        }
+                    }
+                    self.rebase_compound_statement(&mut clause.body);
+                }
+            }
+            Statement::Loop(loop_statement) => {
+                self.rebase_attributes(&mut loop_statement.attributes);
+                self.rebase_compound_statement(&mut loop_statement.body);
+                if let Some(continuing) = loop_statement.continuing.as_mut() {
+                    self.rebase_compound_statement(&mut continuing.body);
+                    if let Some(break_if) = continuing.break_if.as_mut() {
+                        self.rebase_expression(break_if);
+                    }
+                }
+            }
+            Statement::For(for_statement) => {
+                self.rebase_attributes(&mut for_statement.attributes);
+                if let Some(initializer) = for_statement.initializer.as_mut() {
+                    self.rebase_statement(initializer);
+                }
+                if let Some(condition) = for_statement.condition.as_mut() {
+                    self.rebase_expression(condition);
+                }
+                if let Some(update) = for_statement.update.as_mut() {
+                    self.rebase_statement(update);
+                }
+                self.rebase_compound_statement(&mut for_statement.body);
+            }
+            Statement::While(while_statement) => {
+                self.rebase_attributes(&mut while_statement.attributes);
+                self.rebase_expression(&mut while_statement.condition);
+                self.rebase_compound_statement(&mut while_statement.body);
+            }
+            Statement::Return(expr) => {
+                if let Some(expr) = expr.as_mut() {
+                    self.rebase_expression(expr);
+                }
+            }
+            Statement::FunctionCall(call) => self.rebase_call(call),
+            Statement::ConstAssert(assrt) => {
+                self.rebase_attributes(&mut assrt.attributes);
+                self.rebase_expression(&mut assrt.expression);
+            }
+            Statement::Declaration(decl) => {
+                self.rebase_declaration(&mut decl.declaration);
+                for statement in decl.statements.iter_mut() {
+                    self.rebase_statement(statement);
+                }
+            }
+        }
+    }
+
+    fn rebase_declaration(&self, decl: &mut Declaration) {
+        self.rebase_attributes(&mut decl.attributes);
+        if let Some(typ) = decl.typ.as_mut() {
+            self.rebase_path_mut(&mut typ.path);
+        }
+        if let Some(init) = decl.initializer.as_mut() {
+            self.rebase_expression(init);
+        }
+    }
+
+    fn rebase_function(&self, func: &mut Function) {
+        self.rebase_attributes(&mut func.attributes);
+        self.rebase_attributes(&mut func.return_attributes);
+        for param in func.parameters.iter_mut() {
+            self.rebase_attributes(&mut param.attributes);
+            self.rebase_path_mut(&mut param.typ.path);
+        }
+        if let Some(typ) = func.return_type.as_mut() {
+            self.rebase_path_mut(&mut typ.path);
+        }
+        self.rebase_compound_statement(&mut func.body);
+    }
+
+    /// The default of `requirement` as a member of the module, if it has one.
+    fn inherit(
+        &self,
+        requirement: &Spanned<ModuleTypeSignatureMemberDeclaration>,
+    ) -> Option<Spanned<ModuleMemberDeclaration>> {
+        let member = match requirement.as_ref() {
+            ModuleTypeSignatureMemberDeclaration::Declaration(decl)
+                if decl.initializer.is_some() =>
+            {
+                let mut decl = decl.clone();
+                self.rebase_declaration(&mut decl);
+                ModuleMemberDeclaration::Declaration(decl)
+            }
+            ModuleTypeSignatureMemberDeclaration::DefaultFunction(func) => {
+                let mut func = func.clone();
+                self.rebase_function(&mut func);
+                ModuleMemberDeclaration::Function(func)
+            }
+            _ => return None,
+        };
+        Some(Spanned::new(member, requirement.span()))
+    }
+}
+
+/// Checks that the argument of a template parameter implements the interface that bounds it.
+/// Arguments that are not found, like the parameters of an enclosing template, are checked
+/// where that template is used instead. The defaults of the members the argument does not
+/// declare are added to `inherited`.
+pub(crate) fn check_bound(
+    bound: &Spanned<TypeExpression>,
+    arg: &Spanned<TemplateArg>,
+    translation_unit: &TranslationUnit,
+    inherited: &mut Vec<InheritedDefault>,
+) -> CompilerPassResult {
+    let Some((Found::Interface(interface), _, interface_path)) =
+        lookup(&bound.path, translation_unit, &mut Bindings::new(), 0)
+    else {
+        return Err(
+            CompilerPassError::NotAnInterface(bound.path.value.clone(), bound.span()).into(),
+        );
+    };
+    let unsatisfied = |members| {
+        CompilerPassError::UnsatisfiedInterface(
+            arg.expression.to_string(),
+            arg.span(),
+            interface.name.value.clone(),
+            bound.span(),
+            members,
+        )
+        .into()
+    };
+
+    let (Expression::Identifier(IdentifierExpression { path })
+    | Expression::Type(TypeExpression { path })) = arg.expression.as_ref()
+    else {
+        return Err(unsatisfied(vec![]));
+    };
+    let mut bindings = Bindings::new();
+    let Some((found, _, module_path)) = lookup(path, translation_unit, &mut bindings, 0) else {
+        return Ok(());
+    };
+    let Found::Module(module) = found else {
+        return Err(unsatisfied(vec![]));
+    };
+
+    let context = Context {
+        translation_unit,
+        interface: interface_path
+            .iter()
+            .map(|part| part.name.value.clone())
+            .collect(),
+        module: module_path,
+    };
+    // the defaults refer to the members of the module itself, rather than an instance of it
+    let inheriting = Context {
+        module: context
+            .module
+            .iter()
+            .map(|part| PathPart {
+                template_args: None,
+                inline_template_args: None,
+                ..part.clone()
+            })
+            .collect(),
+        interface: context.interface.clone(),
+        ..context
+    };
+    let mut members = vec![];
+    for requirement in interface.members.iter() {
+        let Some(name) = requirement.name() else {
+            continue;
+        };
+        let Some((member, span)) = module
+            .members
+            .iter()
+            .find_map(|decl| found_member(decl, &name.value))
+        else {
+            match inheriting.inherit(requirement) {
+                Some(member) => inherited.push(InheritedDefault {
+                    module: inheriting
+                        .module
+                        .iter()
+                        .map(|part| part.name.value.clone())
+                        .collect(),
+                    member,
+                }),
+                None => members.push(UnsatisfiedMember::Missing(name.value, requirement.span())),
+            }
+            continue;
+        };
+        let mut member_bindings = bindings.clone();
+        let member = match member {
+            Found::Alias(a) => follow_alias(a, &[], translation_unit, &mut member_bindings, 0)
+                .map(|(found, _, _)| found)
+                .unwrap_or(member),
+            _ => member,
+        };
+        if let Some(reason) = context.mismatch(requirement, &member, &member_bindings) {
+            members.push(UnsatisfiedMember::Mismatched(
+                name.value,
+                requirement.span(),
+                span,
+                reason,
+            ));
+        }
+    }
+
+    if members.is_empty() {
+        Ok(())
+    } else {
+        Err(unsatisfied(members))
+    }
+}
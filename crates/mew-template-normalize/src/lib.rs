@@ -1,2 +1,3 @@
+mod conformance;
 pub mod normalize;
 pub use normalize::*;
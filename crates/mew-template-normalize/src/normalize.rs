@@ -3,6 +3,8 @@ use std::collections::VecDeque;
 use mew_parse::{span::Spanned, syntax::*};
 use mew_types::{CompilerPass, CompilerPassError, CompilerPassResult};
 
+use crate::conformance::{InheritedDefault, check_bound};
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct TemplateNormalizer;
 
@@ -42,6 +44,7 @@ impl TemplateNormalizer {
         generic_member: &GenericMember,
         path_part: &mut PathPart,
         translation_unit: &TranslationUnit,
+        inherited: &mut Vec<InheritedDefault>,
     ) -> CompilerPassResult {
         let mut template_args = path_part.template_args.take().unwrap_or_default();
         let template_params = generic_member.template_params();
@@ -66,6 +69,7 @@ impl TemplateNormalizer {
                 Self::normalize_template_arguments_from_expr(
                     &mut value.expression,
                     translation_unit,
+                    inherited,
                 )?;
                 result.push(value);
             } else if let Some(template_arg) = template_args.get_mut(idx) {
@@ -76,6 +80,7 @@ impl TemplateNormalizer {
                     Self::normalize_template_arguments_from_expr(
                         &mut template_arg.expression,
                         translation_unit,
+                        inherited,
                     )?;
                     result.push(template_arg.clone());
                 }
@@ -87,6 +92,15 @@ impl TemplateNormalizer {
                 .into());
             }
         }
+        for param in template_params.iter() {
+            if let Some(bound) = param.bound.as_ref()
+                && let Some(arg) = result
+                    .iter()
+                    .find(|arg| arg.arg_name.as_ref() == Some(&param.name))
+            {
+                check_bound(bound, arg, translation_unit, inherited)?;
+            }
+        }
         if let Some(mut args) = path_part.template_args.take() {
             args.append(&mut result);
             result = args;
@@ -101,6 +115,7 @@ impl TemplateNormalizer {
     fn normalize_path(
         path: &mut Spanned<Vec<PathPart>>,
         translation_unit: &TranslationUnit,
+        inherited: &mut Vec<InheritedDefault>,
     ) -> CompilerPassResult {
         assert!(!path.is_empty());
         Self::template_args_to_none_if_empty(path);
@@ -152,27 +167,29 @@ impl TemplateNormalizer {
                 })
         {
             let mut generic_member = generic_member;
-            Self::normalize_path_part(&generic_member, fst, translation_unit)?;
+            Self::normalize_path_part(&generic_member, fst, translation_unit, inherited)?;
 
-            let process_alias =
-                |a: &Alias, mut remaining_path: VecDeque<&mut PathPart>| -> CompilerPassResult {
-                    let mut remaining_path_with_alias: Spanned<Vec<PathPart>> =
-                        Spanned::new(vec![], a.typ.path.span());
-                    remaining_path_with_alias.append(&mut a.typ.path.clone());
+            let process_alias = |a: &Alias,
+                                 mut remaining_path: VecDeque<&mut PathPart>,
+                                 inherited: &mut Vec<InheritedDefault>|
+             -> CompilerPassResult {
+                let mut remaining_path_with_alias: Spanned<Vec<PathPart>> =
+                    Spanned::new(vec![], a.typ.path.span());
+                remaining_path_with_alias.append(&mut a.typ.path.clone());
 
-                    for p in remaining_path.iter() {
-                        remaining_path_with_alias.push((**p).clone());
-                    }
-                    Self::normalize_path(&mut remaining_path_with_alias, translation_unit)?;
-                    for (part, resultant_part) in remaining_path
-                        .iter_mut()
-                        .zip(remaining_path_with_alias.into_iter().skip(a.typ.path.len()))
-                    {
-                        part.template_args = resultant_part.template_args;
-                    }
+                for p in remaining_path.iter() {
+                    remaining_path_with_alias.push((**p).clone());
+                }
+                Self::normalize_path(&mut remaining_path_with_alias, translation_unit, inherited)?;
+                for (part, resultant_part) in remaining_path
+                    .iter_mut()
+                    .zip(remaining_path_with_alias.into_iter().skip(a.typ.path.len()))
+                {
+                    part.template_args = resultant_part.template_args;
+                }
 
-                    Ok(())
-                };
+                Ok(())
+            };
 
             'outer: while !remaining_path.is_empty() {
                 match &generic_member {
@@ -180,6 +197,7 @@ impl TemplateNormalizer {
                         return Err(CompilerPassError::SymbolNotFound(
                             path.value.clone(),
                             path.span(),
+                            vec![],
                         )
                         .into());
                     }
@@ -187,16 +205,18 @@ impl TemplateNormalizer {
                         return Err(CompilerPassError::SymbolNotFound(
                             path.value.clone(),
                             path.span(),
+                            vec![],
                         )
                         .into());
                     }
                     GenericMember::Alias(a) => {
-                        return process_alias(a, remaining_path);
+                        return process_alias(a, remaining_path, inherited);
                     }
                     GenericMember::Struct(_) => {
                         return Err(CompilerPassError::SymbolNotFound(
                             path.value.clone(),
                             path.span(),
+                            vec![],
                         )
                         .into());
                     }
@@ -214,6 +234,7 @@ impl TemplateNormalizer {
                                             &generic_member,
                                             path_part,
                                             translation_unit,
+                                            inherited,
                                         )?;
                                         continue 'outer;
                                     }
@@ -228,6 +249,7 @@ impl TemplateNormalizer {
                                             &generic_member,
                                             path_part,
                                             translation_unit,
+                                            inherited,
                                         )?;
                                         continue 'outer;
                                     }
@@ -242,6 +264,7 @@ impl TemplateNormalizer {
                                             &generic_member,
                                             path_part,
                                             translation_unit,
+                                            inherited,
                                         )?;
                                         continue 'outer;
                                     }
@@ -256,11 +279,14 @@ impl TemplateNormalizer {
                                             &generic_member,
                                             path_part,
                                             translation_unit,
+                                            inherited,
                                         )?;
-                                        return process_alias(a, remaining_path);
+                                        return process_alias(a, remaining_path, inherited);
                                     }
                                 }
-                                ModuleMemberDeclaration::Void => {}
+                                ModuleMemberDeclaration::Void
+                                | ModuleMemberDeclaration::Error(_)
+                                | ModuleMemberDeclaration::Interface(_) => {}
                                 ModuleMemberDeclaration::ConstAssert(_) => {}
                                 ModuleMemberDeclaration::Declaration(d) => {
                                     if d.name.value
@@ -272,6 +298,7 @@ impl TemplateNormalizer {
                                             &generic_member,
                                             path_part,
                                             translation_unit,
+                                            inherited,
                                         )?;
                                         continue 'outer;
                                     }
@@ -282,6 +309,7 @@ impl TemplateNormalizer {
                         return Err(CompilerPassError::SymbolNotFound(
                             path.value.clone(),
                             path.span(),
+                            vec![],
                         )
                         .into());
                     }
@@ -294,6 +322,7 @@ impl TemplateNormalizer {
                     Self::normalize_template_arguments_from_expr(
                         &mut arg.expression,
                         translation_unit,
+                        inherited,
                     )?;
                 }
             }
@@ -304,6 +333,7 @@ impl TemplateNormalizer {
     fn normalize_template_arguments_from_module(
         module: &mut Module,
         translation_unit: &TranslationUnit,
+        inherited: &mut Vec<InheritedDefault>,
     ) -> Result<(), Box<CompilerPassError>> {
         for decl in module.directives.iter_mut() {
             match &mut decl.value {
@@ -311,88 +341,124 @@ impl TemplateNormalizer {
                     panic!("IMPORT SHOULD HAVE ALREADY BEEN REMOVED");
                 }
                 ModuleDirective::Extend(extend_directive) => {
-                    Self::normalize_path(&mut extend_directive.path, translation_unit)?;
+                    Self::normalize_path(&mut extend_directive.path, translation_unit, inherited)?;
                 }
             }
         }
         for decl in module.members.iter_mut() {
             match decl.as_mut() {
-                ModuleMemberDeclaration::Void => {
+                ModuleMemberDeclaration::Void
+                | ModuleMemberDeclaration::Error(_)
+                | ModuleMemberDeclaration::Interface(_) => {
                     // NO ACTION REQUIRED
                 }
                 ModuleMemberDeclaration::Declaration(decl) => {
-                    Self::normalize_template_arguments_from_decl(decl, translation_unit)?;
+                    Self::normalize_template_arguments_from_decl(
+                        decl,
+                        translation_unit,
+                        inherited,
+                    )?;
                 }
                 ModuleMemberDeclaration::Alias(alias) => {
-                    Self::normalize_template_arguments_from_type(&mut alias.typ, translation_unit)?;
+                    Self::normalize_template_arguments_from_type(
+                        &mut alias.typ,
+                        translation_unit,
+                        inherited,
+                    )?;
                 }
                 ModuleMemberDeclaration::Struct(s) => {
-                    Self::normalize_template_arguments_from_struct(s, translation_unit)?;
+                    Self::normalize_template_arguments_from_struct(s, translation_unit, inherited)?;
                 }
                 ModuleMemberDeclaration::Function(f) => {
-                    Self::normalize_template_arguments_from_function(f, translation_unit)?;
+                    Self::normalize_template_arguments_from_function(
+                        f,
+                        translation_unit,
+                        inherited,
+                    )?;
                 }
                 ModuleMemberDeclaration::ConstAssert(assrt) => {
-                    Self::normalize_template_arguments_from_const_assert(assrt, translation_unit)?;
+                    Self::normalize_template_arguments_from_const_assert(
+                        assrt,
+                        translation_unit,
+                        inherited,
+                    )?;
                 }
                 ModuleMemberDeclaration::Module(m) => {
-                    Self::normalize_template_arguments_from_module(m, translation_unit)?;
+                    Self::normalize_template_arguments_from_module(m, translation_unit, inherited)?;
                 }
             }
         }
+        // Interfaces only constrain template arguments, which have all been checked
+        module
+            .members
+            .retain(|decl| !matches!(decl.as_ref(), ModuleMemberDeclaration::Interface(_)));
         Ok(())
     }
 
     fn normalize_template_arguments_from_expr(
         expr: &mut Expression,
         translation_unit: &TranslationUnit,
+        inherited: &mut Vec<InheritedDefault>,
     ) -> Result<(), Box<CompilerPassError>> {
         match expr {
             Expression::Literal(_) => {
                 // No action required
             }
             Expression::Parenthesized(spanned) => {
-                Self::normalize_template_arguments_from_expr(spanned, translation_unit)?;
+                Self::normalize_template_arguments_from_expr(spanned, translation_unit, inherited)?;
             }
             Expression::NamedComponent(named_component_expression) => {
                 Self::normalize_template_arguments_from_expr(
                     &mut named_component_expression.base,
                     translation_unit,
+                    inherited,
                 )?;
             }
             Expression::Indexing(indexing_expression) => {
                 Self::normalize_template_arguments_from_expr(
                     &mut indexing_expression.base,
                     translation_unit,
+                    inherited,
                 )?;
             }
             Expression::Unary(unary_expression) => {
                 Self::normalize_template_arguments_from_expr(
                     &mut unary_expression.operand,
                     translation_unit,
+                    inherited,
                 )?;
             }
             Expression::Binary(binary_expression) => {
                 Self::normalize_template_arguments_from_expr(
                     &mut binary_expression.left,
                     translation_unit,
+                    inherited,
                 )?;
                 Self::normalize_template_arguments_from_expr(
                     &mut binary_expression.right,
                     translation_unit,
+                    inherited,
                 )?;
             }
             Expression::FunctionCall(function_call_expression) => {
-                Self::normalize_path(&mut function_call_expression.path, translation_unit)?;
+                Self::normalize_path(
+                    &mut function_call_expression.path,
+                    translation_unit,
+                    inherited,
+                )?;
                 for arg in function_call_expression.arguments.iter_mut() {
-                    Self::normalize_template_arguments_from_expr(arg, translation_unit)?;
+                    Self::normalize_template_arguments_from_expr(arg, translation_unit, inherited)?;
                 }
             }
             Expression::Identifier(identifier_expression) => {
-                Self::normalize_path(&mut identifier_expression.path, translation_unit)?;
+                Self::normalize_path(&mut identifier_expression.path, translation_unit, inherited)?;
             }
             Expression::Type(type_expression) => {
-                Self::normalize_template_arguments_from_type(type_expression, translation_unit)?;
+                Self::normalize_template_arguments_from_type(
+                    type_expression,
+                    translation_unit,
+                    inherited,
+                )?;
             }
         }
         Ok(())
@@ -401,58 +467,82 @@ impl TemplateNormalizer {
     fn normalize_template_arguments_from_statement(
         statement: &mut Statement,
         translation_unit: &TranslationUnit,
+        inherited: &mut Vec<InheritedDefault>,
     ) -> Result<(), Box<CompilerPassError>> {
         match statement {
-            Statement::Void => {
+            Statement::Void | Statement::Error(_) => {
                 // No action required
             }
             Statement::Compound(compound_statement) => {
                 Self::normalize_template_arguments_from_compound_statement(
                     compound_statement,
                     translation_unit,
+                    inherited,
                 )?;
             }
             Statement::Assignment(assignment_statement) => {
                 Self::normalize_template_arguments_from_expr(
                     &mut assignment_statement.lhs,
                     translation_unit,
+                    inherited,
                 )?;
                 Self::normalize_template_arguments_from_expr(
                     &mut assignment_statement.rhs,
                     translation_unit,
+                    inherited,
                 )?;
             }
             Statement::Increment(expression) => {
-                Self::normalize_template_arguments_from_expr(expression, translation_unit)?;
+                Self::normalize_template_arguments_from_expr(
+                    expression,
+                    translation_unit,
+                    inherited,
+                )?;
             }
             Statement::Decrement(expression) => {
-                Self::normalize_template_arguments_from_expr(expression, translation_unit)?;
+                Self::normalize_template_arguments_from_expr(
+                    expression,
+                    translation_unit,
+                    inherited,
+                )?;
             }
             Statement::If(iff) => {
                 Self::normalize_template_arguments_from_expr(
                     &mut iff.if_clause.0,
                     translation_unit,
+                    inherited,
                 )?;
                 Self::normalize_template_arguments_from_compound_statement(
                     &mut iff.if_clause.1,
                     translation_unit,
+                    inherited,
                 )?;
                 for (else_if_expr, else_if_statements) in iff.else_if_clauses.iter_mut() {
-                    Self::normalize_template_arguments_from_expr(else_if_expr, translation_unit)?;
+                    Self::normalize_template_arguments_from_expr(
+                        else_if_expr,
+                        translation_unit,
+                        inherited,
+                    )?;
                     Self::normalize_template_arguments_from_compound_statement(
                         else_if_statements,
                         translation_unit,
+                        inherited,
                     )?;
                 }
                 if let Some(else_clause) = iff.else_clause.as_mut() {
                     Self::normalize_template_arguments_from_compound_statement(
                         else_clause,
                         translation_unit,
+                        inherited,
                     )?;
                 }
             }
             Statement::Switch(s) => {
-                Self::normalize_template_arguments_from_expr(&mut s.expression, translation_unit)?;
+                Self::normalize_template_arguments_from_expr(
+                    &mut s.expression,
+                    translation_unit,
+                    inherited,
+                )?;
                 for clause in s.clauses.iter_mut() {
                     for c in clause.case_selectors.iter_mut() {
                         match &mut c.value {
@@ -460,13 +550,18 @@ impl TemplateNormalizer {
                                 // NO ACTION NEEDED
                             }
                             mew_parse::syntax::CaseSelector::Expression(e) => {
-                                Self::normalize_template_arguments_from_expr(e, translation_unit)?;
+                                Self::normalize_template_arguments_from_expr(
+                                    e,
+                                    translation_unit,
+                                    inherited,
+                                )?;
                             }
                         }
                     }
                     Self::normalize_template_arguments_from_compound_statement(
                         &mut clause.body,
                         translation_unit,
+                        inherited,
                     )?;
                 }
             }
@@ -474,14 +569,20 @@ impl TemplateNormalizer {
                 Self::normalize_template_arguments_from_compound_statement(
                     &mut l.body,
                     translation_unit,
+                    inherited,
                 )?;
                 if let Some(cont) = l.continuing.as_mut() {
                     Self::normalize_template_arguments_from_compound_statement(
                         &mut l.body,
                         translation_unit,
+                        inherited,
                     )?;
                     if let Some(expr) = cont.break_if.as_mut() {
-                        Self::normalize_template_arguments_from_expr(expr, translation_unit)?;
+                        Self::normalize_template_arguments_from_expr(
+                            expr,
+                            translation_unit,
+                            inherited,
+                        )?;
                     }
                 }
             }
@@ -490,27 +591,39 @@ impl TemplateNormalizer {
                     Self::normalize_template_arguments_from_statement(
                         init.as_mut(),
                         translation_unit,
+                        inherited,
                     )?;
                 }
                 if let Some(cond) = f.condition.as_mut() {
-                    Self::normalize_template_arguments_from_expr(cond, translation_unit)?;
+                    Self::normalize_template_arguments_from_expr(
+                        cond,
+                        translation_unit,
+                        inherited,
+                    )?;
                 }
                 if let Some(update) = f.update.as_mut() {
                     Self::normalize_template_arguments_from_statement(
                         update.as_mut(),
                         translation_unit,
+                        inherited,
                     )?;
                 }
                 Self::normalize_template_arguments_from_compound_statement(
                     &mut f.body,
                     translation_unit,
+                    inherited,
                 )?;
             }
             Statement::While(w) => {
-                Self::normalize_template_arguments_from_expr(&mut w.condition, translation_unit)?;
+                Self::normalize_template_arguments_from_expr(
+                    &mut w.condition,
+                    translation_unit,
+                    inherited,
+                )?;
                 Self::normalize_template_arguments_from_compound_statement(
                     &mut w.body,
                     translation_unit,
+                    inherited,
                 )?;
             }
             Statement::Break => {
@@ -521,31 +634,45 @@ impl TemplateNormalizer {
             }
             Statement::Return(spanned) => {
                 if let Some(expr) = spanned.as_mut() {
-                    Self::normalize_template_arguments_from_expr(expr, translation_unit)?;
+                    Self::normalize_template_arguments_from_expr(
+                        expr,
+                        translation_unit,
+                        inherited,
+                    )?;
                 }
             }
             Statement::Discard => {
                 // No action required
             }
             Statement::FunctionCall(function_call_expression) => {
-                Self::normalize_path(&mut function_call_expression.path, translation_unit)?;
+                Self::normalize_path(
+                    &mut function_call_expression.path,
+                    translation_unit,
+                    inherited,
+                )?;
                 for arg in function_call_expression.arguments.iter_mut() {
-                    Self::normalize_template_arguments_from_expr(arg, translation_unit)?;
+                    Self::normalize_template_arguments_from_expr(arg, translation_unit, inherited)?;
                 }
             }
             Statement::ConstAssert(const_assert) => {
                 Self::normalize_template_arguments_from_const_assert(
                     const_assert,
                     translation_unit,
+                    inherited,
                 )?;
             }
             Statement::Declaration(declaration_statement) => {
                 Self::normalize_template_arguments_from_decl(
                     &mut declaration_statement.declaration,
                     translation_unit,
+                    inherited,
                 )?;
                 for statement in declaration_statement.statements.iter_mut() {
-                    Self::normalize_template_arguments_from_statement(statement, translation_unit)?;
+                    Self::normalize_template_arguments_from_statement(
+                        statement,
+                        translation_unit,
+                        inherited,
+                    )?;
                 }
             }
         }
@@ -555,21 +682,27 @@ impl TemplateNormalizer {
     fn normalize_template_arguments_from_type(
         expr: &mut TypeExpression,
         translation_unit: &TranslationUnit,
+        inherited: &mut Vec<InheritedDefault>,
     ) -> Result<(), Box<CompilerPassError>> {
-        Self::normalize_path(&mut expr.path, translation_unit)?;
+        Self::normalize_path(&mut expr.path, translation_unit, inherited)?;
         Ok(())
     }
 
     fn normalize_template_arguments_from_decl(
         decl: &mut Declaration,
         translation_unit: &TranslationUnit,
+        inherited: &mut Vec<InheritedDefault>,
     ) -> Result<(), Box<CompilerPassError>> {
         if let Some(init) = decl.initializer.as_mut() {
-            Self::normalize_template_arguments_from_expr(init.as_mut(), translation_unit)?;
+            Self::normalize_template_arguments_from_expr(
+                init.as_mut(),
+                translation_unit,
+                inherited,
+            )?;
         }
 
         if let Some(typ) = decl.typ.as_mut() {
-            Self::normalize_template_arguments_from_type(typ, translation_unit)?;
+            Self::normalize_template_arguments_from_type(typ, translation_unit, inherited)?;
         }
 
         Ok(())
@@ -578,9 +711,10 @@ impl TemplateNormalizer {
     fn normalize_template_arguments_from_struct(
         strct: &mut Struct,
         translation_unit: &TranslationUnit,
+        inherited: &mut Vec<InheritedDefault>,
     ) -> Result<(), Box<CompilerPassError>> {
         for m in strct.members.iter_mut() {
-            Self::normalize_template_arguments_from_type(&mut m.typ, translation_unit)?;
+            Self::normalize_template_arguments_from_type(&mut m.typ, translation_unit, inherited)?;
         }
         Ok(())
     }
@@ -588,10 +722,11 @@ impl TemplateNormalizer {
     fn normalize_template_arguments_from_template_params(
         params: &mut Vec<Spanned<FormalTemplateParameter>>,
         translation_unit: &TranslationUnit,
+        inherited: &mut Vec<InheritedDefault>,
     ) -> Result<(), Box<CompilerPassError>> {
         for p in params {
             if let Some(def) = p.default_value.as_mut() {
-                Self::normalize_template_arguments_from_expr(def, translation_unit)?;
+                Self::normalize_template_arguments_from_expr(def, translation_unit, inherited)?;
             }
         }
         Ok(())
@@ -600,11 +735,13 @@ impl TemplateNormalizer {
     fn normalize_template_arguments_from_compound_statement(
         statement: &mut CompoundStatement,
         translation_unit: &TranslationUnit,
+        inherited: &mut Vec<InheritedDefault>,
     ) -> Result<(), Box<CompilerPassError>> {
         for statement in statement.statements.iter_mut() {
             Self::normalize_template_arguments_from_statement(
                 statement.as_mut(),
                 translation_unit,
+                inherited,
             )?;
         }
         Ok(())
@@ -613,22 +750,25 @@ impl TemplateNormalizer {
     fn normalize_template_arguments_from_function(
         func: &mut Function,
         translation_unit: &TranslationUnit,
+        inherited: &mut Vec<InheritedDefault>,
     ) -> Result<(), Box<CompilerPassError>> {
         if let Some(r) = func.return_type.as_mut() {
-            Self::normalize_template_arguments_from_type(r, translation_unit)?;
+            Self::normalize_template_arguments_from_type(r, translation_unit, inherited)?;
         }
         Self::normalize_template_arguments_from_template_params(
             &mut func.template_parameters,
             translation_unit,
+            inherited,
         )?;
 
         for p in func.parameters.iter_mut() {
-            Self::normalize_template_arguments_from_type(&mut p.typ, translation_unit)?;
+            Self::normalize_template_arguments_from_type(&mut p.typ, translation_unit, inherited)?;
         }
 
         Self::normalize_template_arguments_from_compound_statement(
             &mut func.body,
             translation_unit,
+            inherited,
         )?;
         Ok(())
     }
@@ -636,8 +776,13 @@ impl TemplateNormalizer {
     fn normalize_template_arguments_from_const_assert(
         assrt: &mut ConstAssert,
         translation_unit: &TranslationUnit,
+        inherited: &mut Vec<InheritedDefault>,
     ) -> Result<(), Box<CompilerPassError>> {
-        Self::normalize_template_arguments_from_expr(&mut assrt.expression, translation_unit)?;
+        Self::normalize_template_arguments_from_expr(
+            &mut assrt.expression,
+            translation_unit,
+            inherited,
+        )?;
         Ok(())
     }
 
@@ -645,6 +790,7 @@ impl TemplateNormalizer {
         translation_unit: &mut TranslationUnit,
     ) -> Result<(), Box<CompilerPassError>> {
         let clone = translation_unit.clone();
+        let mut inherited = vec![];
         for decl in translation_unit.global_directives.iter_mut() {
             match &mut decl.value {
                 GlobalDirective::Diagnostic(_) => {}
@@ -654,37 +800,88 @@ impl TemplateNormalizer {
                     panic!("IMPORT SHOULD HAVE ALREADY BEEN REMOVED");
                 }
                 GlobalDirective::Extend(extend_directive) => {
-                    Self::normalize_path(&mut extend_directive.path, &clone)?;
+                    Self::normalize_path(&mut extend_directive.path, &clone, &mut inherited)?;
                 }
             }
         }
         for decl in translation_unit.global_declarations.iter_mut() {
             match decl.as_mut() {
-                GlobalDeclaration::Void => {
+                GlobalDeclaration::Void
+                | GlobalDeclaration::Error(_)
+                | GlobalDeclaration::Interface(_) => {
                     // NO ACTION REQUIRED REQUIRED
                 }
                 GlobalDeclaration::Declaration(decl) => {
-                    Self::normalize_template_arguments_from_decl(decl, &clone)?;
+                    Self::normalize_template_arguments_from_decl(decl, &clone, &mut inherited)?;
                 }
                 GlobalDeclaration::Alias(alias) => {
-                    Self::normalize_template_arguments_from_type(&mut alias.typ, &clone)?;
+                    Self::normalize_template_arguments_from_type(
+                        &mut alias.typ,
+                        &clone,
+                        &mut inherited,
+                    )?;
                 }
                 GlobalDeclaration::Struct(s) => {
-                    Self::normalize_template_arguments_from_struct(s, &clone)?;
+                    Self::normalize_template_arguments_from_struct(s, &clone, &mut inherited)?;
                 }
                 GlobalDeclaration::Function(f) => {
-                    Self::normalize_template_arguments_from_function(f, &clone)?;
+                    Self::normalize_template_arguments_from_function(f, &clone, &mut inherited)?;
                 }
                 GlobalDeclaration::ConstAssert(assrt) => {
-                    Self::normalize_template_arguments_from_const_assert(assrt, &clone)?;
+                    Self::normalize_template_arguments_from_const_assert(
+                        assrt,
+                        &clone,
+                        &mut inherited,
+                    )?;
                 }
                 GlobalDeclaration::Module(m) => {
-                    Self::normalize_template_arguments_from_module(m, &clone)?;
+                    Self::normalize_template_arguments_from_module(m, &clone, &mut inherited)?;
                 }
             }
         }
+        for default in inherited {
+            Self::inherit_default(translation_unit, default);
+        }
+        translation_unit
+            .global_declarations
+            .retain(|decl| !matches!(decl.as_ref(), GlobalDeclaration::Interface(_)));
         Ok(())
     }
+
+    /// Adds the default of an interface to a module that conforms to it without extending it,
+    /// unless an earlier use of the module already added it.
+    fn inherit_default(translation_unit: &mut TranslationUnit, default: InheritedDefault) {
+        let Some((fst, rest)) = default.module.split_first() else {
+            return;
+        };
+        let Some(mut module) = translation_unit
+            .global_declarations
+            .iter_mut()
+            .find_map(|decl| match decl.as_mut() {
+                GlobalDeclaration::Module(m) if &m.name.value == fst => Some(m),
+                _ => None,
+            })
+        else {
+            return;
+        };
+        for name in rest {
+            let Some(m) = module
+                .members
+                .iter_mut()
+                .find_map(|decl| match decl.as_mut() {
+                    ModuleMemberDeclaration::Module(m) if &m.name.value == name => Some(m),
+                    _ => None,
+                })
+            else {
+                return;
+            };
+            module = m;
+        }
+        let name = default.member.name();
+        if !module.members.iter().any(|member| member.name() == name) {
+            module.members.push(default.member);
+        }
+    }
 }
 
 impl CompilerPass for TemplateNormalizer {
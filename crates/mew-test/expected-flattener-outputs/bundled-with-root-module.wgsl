@@ -1,11 +1,11 @@
 
 
 @fragment
-fn MyLib_util__main() -> u32 {
-    return MyLib_util_my__fn();
+fn MyLib_util_0main() -> u32 {
+    return MyLib_util_my_0fn();
 }
 
-fn MyLib_util_my__fn() -> u32 {
+fn MyLib_util_my_0fn() -> u32 {
     return 42;
 }
 
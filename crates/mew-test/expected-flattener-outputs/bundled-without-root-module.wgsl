@@ -1,8 +1,8 @@
 import util::my_fn as x;
 
 @fragment
-fn util__main() -> u32 {
-    return util_my__fn();
+fn util_0main() -> u32 {
+    return util_my_0fn();
 }
 
 @binding(0) @group(0)
@@ -44,7 +44,7 @@ fn main(@builtin(instance_index) instanceIdx: u32, @location(0) position: vec4f,
     return output;
 }
 
-fn util_my__fn() -> u32 {
+fn util_my_0fn() -> u32 {
     return 42;
 }
 
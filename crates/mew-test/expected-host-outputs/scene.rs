@@ -0,0 +1,64 @@
+// Generated by MEW, do not edit.
+
+pub mod scene {
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+    pub struct Particle {
+        pub position: [f32; 3],
+        pub _pad0: [u8; 4],
+        pub velocity: [f32; 3],
+        pub _pad1: [u8; 4],
+    }
+
+    const _: () = assert!(std::mem::size_of::<Particle>() == 32);
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+    pub struct Emitter {
+        pub rate: f32,
+        pub _pad0: [u8; 12],
+        pub origin: [f32; 3],
+        pub _pad1: [u8; 4],
+        // `particles` is a runtime-sized array of `super::scene::Particle` that follows
+    }
+
+    const _: () = assert!(std::mem::size_of::<Emitter>() == 32);
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+    pub struct VertexInput {
+        pub position: [f32; 3],
+        pub _pad0: [u8; 4],
+        pub uv: [f32; 2],
+        pub _pad1: [u8; 8],
+    }
+
+    const _: () = assert!(std::mem::size_of::<VertexInput>() == 32);
+
+    pub const PARTICLES_GROUP: u32 = 1;
+    pub const PARTICLES_BINDING: u32 = 0;
+
+    pub const EMITTER_GROUP: u32 = 1;
+    pub const EMITTER_BINDING: u32 = 3;
+
+    pub const ALBEDO_GROUP: u32 = 1;
+    pub const ALBEDO_BINDING: u32 = 1;
+
+    pub const ALBEDO_SAMPLER_GROUP: u32 = 1;
+    pub const ALBEDO_SAMPLER_BINDING: u32 = 2;
+
+    pub mod camera {
+        #[repr(C)]
+        #[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+        pub struct Uniforms {
+            pub view_projection: [[f32; 4]; 4],
+            pub position: [f32; 3],
+            pub exposure: f32,
+        }
+
+        const _: () = assert!(std::mem::size_of::<Uniforms>() == 80);
+
+        pub const UNIFORMS_GROUP: u32 = 0;
+        pub const UNIFORMS_BINDING: u32 = 0;
+    }
+}
@@ -0,0 +1,19 @@
+
+
+fn App_main() -> f32 {
+    return Tonemap_clamped(Lighting_Util_saturate(Lighting_Phong_shade(0.5)));
+}
+
+fn Lighting_Phong_shade(cosine: f32) -> f32 {
+    return pow(cosine, Lighting_Phong_shininess);
+}
+
+const Lighting_Phong_shininess: f32 = 32.0;
+
+fn Lighting_Util_saturate(x: f32) -> f32 {
+    return clamp(x, 0.0, 1.0);
+}
+
+fn Tonemap_clamped(x: f32) -> f32 {
+    return min(x, 1.0);
+}
@@ -0,0 +1,13 @@
+
+
+fn Main_fragment(hdr: f32) -> f32 {
+    return Color_Grade_warm(Color_Tonemap_reinhard(hdr));
+}
+
+fn Color_Tonemap_reinhard(x: f32) -> f32 {
+    return x / (1.0 + x);
+}
+
+fn Color_Grade_warm(x: f32) -> f32 {
+    return x * 1.1;
+}
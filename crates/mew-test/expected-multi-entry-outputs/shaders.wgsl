@@ -0,0 +1,25 @@
+
+
+@fragment
+fn fragment(input: shaders_VertexOutput) -> @location(0) vec4f {
+    return shaders_gamma(input.color);
+}
+
+fn shaders_gamma(color: vec4f) -> vec4f {
+    return pow(color, vec4f(2.2));
+}
+
+struct shaders_VertexOutput {
+    @builtin(position)
+    position: vec4f,
+    @location(0)
+    color: vec4f
+}
+
+@vertex
+fn vertex(@location(0) position: vec4f, @location(1) color: vec4f) -> shaders_VertexOutput {
+    var output: shaders_VertexOutput;
+    output.position = position;
+    output.color = shaders_gamma(color);
+    return output;
+}
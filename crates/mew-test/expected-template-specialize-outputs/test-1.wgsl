@@ -1,25 +1,25 @@
 
 
 @vertex
-fn test__1_main(@builtin(instance_index) instanceIdx: u32, @location(0) position: vec4<f32>, @location(1) color: vec4f) -> test__1_My__Lib_VertexShader_Types_VertexOutput__test____95____1____58____58____Hey {
+fn main(@builtin(instance_index) instanceIdx: u32, @location(0) position: vec4<f32>, @location(1) color: vec4f) -> test_01_My_0Lib_VertexShader_Types_VertexOutput_1test_01_Hey_2 {
     import test_1::My_Lib<test_1::Hey, test_1::WhatsUp>::VertexShader<Hi> as V;
-    let uni = test__1_Camera__mat4x4f();
-    var output: test__1_My__Lib_VertexShader_Types_VertexOutput__test____95____1____58____58____Hey;
-    output.Position = test__1_My__Lib_VertexShader_camera__test____95____1____58____58____Hey__test____95____1____58____58____WhatsUp__test____95____1____58____58____Hi__test____95____1____58____58____Hi.viewProjectionMatrix * test__1_My__Lib_VertexShader_uniforms__test____95____1____58____58____Hey__test____95____1____58____58____WhatsUp__test____95____1____58____58____Hi__test____95____1____58____58____Hi.modelMatrix.viewProjectionMatrix[instanceIdx] * position;
-    output.fragColor = color / vec4<f32>(test__1_My__Lib_x__test____95____1____58____58____Hey__test____95____1____58____58____WhatsUp__test____95____1____58____58____Hi);
+    let uni = test_01_Camera_1mat4x4f_2();
+    var output: test_01_My_0Lib_VertexShader_Types_VertexOutput_1test_01_Hey_2;
+    output.Position = test_01_My_0Lib_VertexShader_camera_1test_01_Hey_3test_01_WhatsUp_3test_01_Hi_3test_01_Hi_2.viewProjectionMatrix * test_01_My_0Lib_VertexShader_uniforms_1test_01_Hey_3test_01_WhatsUp_3test_01_Hi_3test_01_Hi_2.modelMatrix.viewProjectionMatrix[instanceIdx] * position;
+    output.fragColor = color / vec4<f32>(test_01_My_0Lib_x_1test_01_Hey_3test_01_WhatsUp_3test_01_Hi_2);
     return output;
 }
 
-const test__1_My__Lib_x__test____95____1____58____58____Hey__test____95____1____58____58____WhatsUp__test____95____1____58____58____Hi: test__1_Camera__mat4x4f = test__1_Camera__mat4x4f();
+const test_01_My_0Lib_x_1test_01_Hey_3test_01_WhatsUp_3test_01_Hi_2: test_01_Camera_1mat4x4f_2 = test_01_Camera_1mat4x4f_2();
 
 @binding(0) @group(0)
-var<uniform> test__1_My__Lib_VertexShader_uniforms__test____95____1____58____58____Hey__test____95____1____58____58____WhatsUp__test____95____1____58____58____Hi__test____95____1____58____58____Hi: test__1_My__Lib_VertexShader_Types_Uniforms__test____95____1____58____58____Hi;
+var<uniform> test_01_My_0Lib_VertexShader_uniforms_1test_01_Hey_3test_01_WhatsUp_3test_01_Hi_3test_01_Hi_2: test_01_My_0Lib_VertexShader_Types_Uniforms_1test_01_Hi_2;
 
-struct test__1_My__Lib_VertexShader_Types_Uniforms__test____95____1____58____58____Hi {
-    modelMatrix: test__1_Camera__mat4x4f
+struct test_01_My_0Lib_VertexShader_Types_Uniforms_1test_01_Hi_2 {
+    modelMatrix: test_01_Camera_1mat4x4f_2
 }
 
-struct test__1_My__Lib_VertexShader_Types_VertexOutput__test____95____1____58____58____Hey {
+struct test_01_My_0Lib_VertexShader_Types_VertexOutput_1test_01_Hey_2 {
     @builtin(position)
     Position: vec4f,
     @location(0)
@@ -27,8 +27,8 @@ struct test__1_My__Lib_VertexShader_Types_VertexOutput__test____95____1____58___
 }
 
 @binding(1) @group(0)
-var<uniform> test__1_My__Lib_VertexShader_camera__test____95____1____58____58____Hey__test____95____1____58____58____WhatsUp__test____95____1____58____58____Hi__test____95____1____58____58____Hi: test__1_Camera__mat4x4f;
+var<uniform> test_01_My_0Lib_VertexShader_camera_1test_01_Hey_3test_01_WhatsUp_3test_01_Hi_3test_01_Hi_2: test_01_Camera_1mat4x4f_2;
 
-struct test__1_Camera__mat4x4f {
+struct test_01_Camera_1mat4x4f_2 {
     viewProjectionMatrix: mat4x4f
 }
@@ -0,0 +1,20 @@
+
+
+@compute @workgroup_size(1)
+fn main() {
+    let x = test_010_Sum_sum_1test_010_I32_2(1i, 2i);
+}
+
+fn test_010_Sum_sum_1test_010_I32_2(a: i32, b: i32) -> i32 {
+    return test_010_I32_add(test_010_I32_twice(a), test_010_I32_add(b, test_010_I32_one));
+}
+
+const test_010_I32_one: i32 = i32(1);
+
+fn test_010_I32_twice(a: i32) -> i32 {
+    return test_010_I32_add(a, a);
+}
+
+fn test_010_I32_add(a: i32, b: i32) -> i32 {
+    return a + b;
+}
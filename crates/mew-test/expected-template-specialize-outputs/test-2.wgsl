@@ -1,22 +1,22 @@
 
 
 @vertex
-fn test__2_My__Lib_VertexShader_main__test____95____2____58____58____Hey__test____95____2____58____58____WhatsUp__test____95____2____58____58____Hi__test____95____2____58____58____Hi(@builtin(instance_index) instanceIdx: u32, @location(0) position: vec4<f32>, @location(1) color: vec4f) -> test__2_My__Lib_VertexShader_Types_VertexOutput__test____95____2____58____58____Hey {
-    let uni = test__2_Camera__mat4x4f();
-    var output: test__2_My__Lib_VertexShader_Types_VertexOutput__test____95____2____58____58____Hey;
-    output.Position = test__2_My__Lib_VertexShader_camera__test____95____2____58____58____Hey__test____95____2____58____58____WhatsUp__test____95____2____58____58____Hi__test____95____2____58____58____Hi.viewProjectionMatrix * test__2_My__Lib_VertexShader_uniforms__test____95____2____58____58____Hey__test____95____2____58____58____WhatsUp__test____95____2____58____58____Hi__test____95____2____58____58____Hi.modelMatrix.viewProjectionMatrix[instanceIdx] * position;
-    output.fragColor = color / vec4<f32>(test__2_My__Lib_x__test____95____2____58____58____Hey__test____95____2____58____58____WhatsUp__test____95____2____58____58____Hi);
+fn main_1test_02_Hey_3test_02_WhatsUp_3test_02_Hi_3test_02_Hi_2(@builtin(instance_index) instanceIdx: u32, @location(0) position: vec4<f32>, @location(1) color: vec4f) -> test_02_My_0Lib_VertexShader_Types_VertexOutput_1test_02_Hey_2 {
+    let uni = test_02_Camera_1mat4x4f_2();
+    var output: test_02_My_0Lib_VertexShader_Types_VertexOutput_1test_02_Hey_2;
+    output.Position = test_02_My_0Lib_VertexShader_camera_1test_02_Hey_3test_02_WhatsUp_3test_02_Hi_3test_02_Hi_2.viewProjectionMatrix * test_02_My_0Lib_VertexShader_uniforms_1test_02_Hey_3test_02_WhatsUp_3test_02_Hi_3test_02_Hi_2.modelMatrix.viewProjectionMatrix[instanceIdx] * position;
+    output.fragColor = color / vec4<f32>(test_02_My_0Lib_x_1test_02_Hey_3test_02_WhatsUp_3test_02_Hi_2);
     return output;
 }
 
 @binding(0) @group(0)
-var<uniform> test__2_My__Lib_VertexShader_uniforms__test____95____2____58____58____Hey__test____95____2____58____58____WhatsUp__test____95____2____58____58____Hi__test____95____2____58____58____Hi: test__2_My__Lib_VertexShader_Types_Uniforms__test____95____2____58____58____Hi;
+var<uniform> test_02_My_0Lib_VertexShader_uniforms_1test_02_Hey_3test_02_WhatsUp_3test_02_Hi_3test_02_Hi_2: test_02_My_0Lib_VertexShader_Types_Uniforms_1test_02_Hi_2;
 
-struct test__2_My__Lib_VertexShader_Types_Uniforms__test____95____2____58____58____Hi {
-    modelMatrix: test__2_Camera__mat4x4f
+struct test_02_My_0Lib_VertexShader_Types_Uniforms_1test_02_Hi_2 {
+    modelMatrix: test_02_Camera_1mat4x4f_2
 }
 
-struct test__2_My__Lib_VertexShader_Types_VertexOutput__test____95____2____58____58____Hey {
+struct test_02_My_0Lib_VertexShader_Types_VertexOutput_1test_02_Hey_2 {
     @builtin(position)
     Position: vec4f,
     @location(0)
@@ -24,10 +24,10 @@ struct test__2_My__Lib_VertexShader_Types_VertexOutput__test____95____2____58___
 }
 
 @binding(1) @group(0)
-var<uniform> test__2_My__Lib_VertexShader_camera__test____95____2____58____58____Hey__test____95____2____58____58____WhatsUp__test____95____2____58____58____Hi__test____95____2____58____58____Hi: test__2_Camera__mat4x4f;
+var<uniform> test_02_My_0Lib_VertexShader_camera_1test_02_Hey_3test_02_WhatsUp_3test_02_Hi_3test_02_Hi_2: test_02_Camera_1mat4x4f_2;
 
-const test__2_My__Lib_x__test____95____2____58____58____Hey__test____95____2____58____58____WhatsUp__test____95____2____58____58____Hi: test__2_Camera__mat4x4f = test__2_Camera__mat4x4f();
+const test_02_My_0Lib_x_1test_02_Hey_3test_02_WhatsUp_3test_02_Hi_2: test_02_Camera_1mat4x4f_2 = test_02_Camera_1mat4x4f_2();
 
-struct test__2_Camera__mat4x4f {
+struct test_02_Camera_1mat4x4f_2 {
     viewProjectionMatrix: mat4x4f
 }
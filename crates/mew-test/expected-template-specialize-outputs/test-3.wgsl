@@ -1,93 +1,93 @@
 
 
 @compute @workgroup_size(workgroupThreads, 1, 1)
-fn test__3_ReduceBuffer_main__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads(@builtin(global_invocation_id) grid: vec3<u32>, @builtin(local_invocation_index) localIndex: u32, @builtin(num_workgroups) numWorkgroups: vec3<u32>, @builtin(workgroup_id) workgroupId: vec3<u32>) {
-    test__3_ReduceBuffer_reduceBufferToWork__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads(grid.xy, localIndex);
-    let outDex = workgroupId.x + test__3_ReduceBuffer_u__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads.resultOffset;
-    test__3_ReduceWorkgroup_reduceWorkgroup__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads(localIndex);
+fn main_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2(@builtin(global_invocation_id) grid: vec3<u32>, @builtin(local_invocation_index) localIndex: u32, @builtin(num_workgroups) numWorkgroups: vec3<u32>, @builtin(workgroup_id) workgroupId: vec3<u32>) {
+    test_03_ReduceBuffer_reduceBufferToWork_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2(grid.xy, localIndex);
+    let outDex = workgroupId.x + test_03_ReduceBuffer_u_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2.resultOffset;
+    test_03_ReduceWorkgroup_reduceWorkgroup_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_WorkSize_3test_03_Threads_2(localIndex);
     if localIndex == 0u {
-        test__3_ReduceBuffer_out__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads[outDex] = test__3_ReduceWorkgroup_work__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____WorkSize[0];
+        test_03_ReduceBuffer_out_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2[outDex] = test_03_ReduceWorkgroup_work_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_WorkSize_2[0];
     }
 }
 
 @group(0) @binding(2)
-var<storage, read_write> test__3_ReduceBuffer_out__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads: array<test__3_Sum_T__test____95____3____58____58____F32>;
+var<storage, read_write> test_03_ReduceBuffer_out_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2: array<test_03_Sum_T_1test_03_F32_2>;
 
 @group(0) @binding(0)
-var<uniform> test__3_ReduceBuffer_u__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads: test__3_ReduceBuffer_Uniforms;
+var<uniform> test_03_ReduceBuffer_u_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2: test_03_ReduceBuffer_Uniforms;
 
-struct test__3_ReduceBuffer_Uniforms {
+struct test_03_ReduceBuffer_Uniforms {
     sourceOffset: u32,
     resultOffset: u32
 }
 
-fn test__3_ReduceBuffer_reduceBufferToWork__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads(grid: vec2<u32>, localId: u32) {
-    var values = test__3_ReduceBuffer_fetchSrcBuffer__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads(grid.x);
-    var v = test__3_ReduceBuffer_reduceSrcBlock__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads(values);
-    test__3_ReduceWorkgroup_work__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____WorkSize[localId] = v;
+fn test_03_ReduceBuffer_reduceBufferToWork_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2(grid: vec2<u32>, localId: u32) {
+    var values = test_03_ReduceBuffer_fetchSrcBuffer_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2(grid.x);
+    var v = test_03_ReduceBuffer_reduceSrcBlock_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2(values);
+    test_03_ReduceWorkgroup_work_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_WorkSize_2[localId] = v;
 }
 
-fn test__3_ReduceBuffer_reduceSrcBlock__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads(a: array<test__3_Sum_T__test____95____3____58____58____F32, test__3_BlockArea_value>) -> test__3_Sum_T__test____95____3____58____58____F32 {
+fn test_03_ReduceBuffer_reduceSrcBlock_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2(a: array<test_03_Sum_T_1test_03_F32_2, test_03_BlockArea_value>) -> test_03_Sum_T_1test_03_F32_2 {
     var v = a[0];
-    for (var i = 1u; i < test__3_BlockArea_value; i = i + 1u) {
-        v = test__3_SumBinaryOp_binaryOp__test____95____3____58____58____F32(v, a[i]);
+    for (var i = 1u; i < test_03_BlockArea_value; i = i + 1u) {
+        v = test_03_SumBinaryOp_binaryOp_1test_03_F32_2(v, a[i]);
     }
     return v;
 }
 
-fn test__3_ReduceBuffer_fetchSrcBuffer__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads(gridX: u32) -> array<test__3_Sum_T__test____95____3____58____58____F32, test__3_BlockArea_value> {
-    let start = test__3_ReduceBuffer_u__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads.sourceOffset + (gridX * test__3_BlockArea_value);
-    let end = arrayLength(&test__3_ReduceBuffer_src__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads);
-    var a = array<test__3_Sum_T__test____95____3____58____58____F32, test__3_BlockArea_value>();
-    for (var i = 0u; i < test__3_BlockArea_value; i = i + 1u) {
+fn test_03_ReduceBuffer_fetchSrcBuffer_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2(gridX: u32) -> array<test_03_Sum_T_1test_03_F32_2, test_03_BlockArea_value> {
+    let start = test_03_ReduceBuffer_u_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2.sourceOffset + (gridX * test_03_BlockArea_value);
+    let end = arrayLength(&test_03_ReduceBuffer_src_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2);
+    var a = array<test_03_Sum_T_1test_03_F32_2, test_03_BlockArea_value>();
+    for (var i = 0u; i < test_03_BlockArea_value; i = i + 1u) {
         var idx = i + start;
         if idx < end {
-            a[i] = test__3_SumBinaryOp_loadOp__test____95____3____58____58____F32(test__3_ReduceBuffer_src__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads[idx]);
+            a[i] = test_03_SumBinaryOp_loadOp_1test_03_F32_2(test_03_ReduceBuffer_src_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2[idx]);
         }
         else {
-            a[i] = test__3_SumBinaryOp_identityOp__test____95____3____58____58____F32();
+            a[i] = test_03_SumBinaryOp_identityOp_1test_03_F32_2();
         }
     }
     return a;
 }
 
 @group(0) @binding(1)
-var<storage, read> test__3_ReduceBuffer_src__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____BlockArea__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads: array<test__3_Sum_T__test____95____3____58____58____F32>;
+var<storage, read> test_03_ReduceBuffer_src_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_BlockArea_3test_03_WorkSize_3test_03_Threads_2: array<test_03_Sum_T_1test_03_F32_2>;
 
-var<workgroup> test__3_ReduceWorkgroup_work__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____WorkSize: array<test__3_Sum_T__test____95____3____58____58____F32, test__3_WorkSize_value>;
+var<workgroup> test_03_ReduceWorkgroup_work_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_WorkSize_2: array<test_03_Sum_T_1test_03_F32_2, test_03_WorkSize_value>;
 
-fn test__3_ReduceWorkgroup_reduceWorkgroup__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____WorkSize__test____95____3____58____58____Threads(localId: u32) {
+fn test_03_ReduceWorkgroup_reduceWorkgroup_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_WorkSize_3test_03_Threads_2(localId: u32) {
     let workDex = localId << 1u;
-    for (var step = 1u; step < test__3_Threads_value; step <<= 1u) {
+    for (var step = 1u; step < test_03_Threads_value; step <<= 1u) {
         workgroupBarrier();
         if localId % step == 0u {
-            test__3_ReduceWorkgroup_work__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____WorkSize[workDex] = test__3_SumBinaryOp_binaryOp__test____95____3____58____58____F32(test__3_ReduceWorkgroup_work__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____WorkSize[workDex], test__3_ReduceWorkgroup_work__test____95____3____58____58____SumBinaryOp____60____test____95____95____3____95____SumBinaryOp____95____N____61____test____95____3____58____58____F32____62__test____95____3____58____58____WorkSize[workDex + step]);
+            test_03_ReduceWorkgroup_work_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_WorkSize_2[workDex] = test_03_SumBinaryOp_binaryOp_1test_03_F32_2(test_03_ReduceWorkgroup_work_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_WorkSize_2[workDex], test_03_ReduceWorkgroup_work_1test_03_SumBinaryOp_1test_0_03_0SumBinaryOp_0N_420__43d__420_test_03_F32_2_3test_03_WorkSize_2[workDex + step]);
         }
     }
 }
 
-const test__3_WorkSize_value: u32 = 18u;
+const test_03_WorkSize_value: u32 = 18u;
 
-fn test__3_SumBinaryOp_binaryOp__test____95____3____58____58____F32(a: test__3_Sum_T__test____95____3____58____58____F32, b: test__3_Sum_T__test____95____3____58____58____F32) -> test__3_Sum_T__test____95____3____58____58____F32 {
-    return test__3_Sum_T__test____95____3____58____58____F32(test__3_F32_add(a.sum, b.sum));
+fn test_03_SumBinaryOp_binaryOp_1test_03_F32_2(a: test_03_Sum_T_1test_03_F32_2, b: test_03_Sum_T_1test_03_F32_2) -> test_03_Sum_T_1test_03_F32_2 {
+    return test_03_Sum_T_1test_03_F32_2(test_03_F32_add(a.sum, b.sum));
 }
 
-fn test__3_SumBinaryOp_identityOp__test____95____3____58____58____F32() -> test__3_Sum_T__test____95____3____58____58____F32 {
-    return test__3_Sum_T__test____95____3____58____58____F32();
+fn test_03_SumBinaryOp_identityOp_1test_03_F32_2() -> test_03_Sum_T_1test_03_F32_2 {
+    return test_03_Sum_T_1test_03_F32_2();
 }
 
-fn test__3_SumBinaryOp_loadOp__test____95____3____58____58____F32(a: test__3_Sum_T__test____95____3____58____58____F32) -> test__3_Sum_T__test____95____3____58____58____F32 {
-    return test__3_Sum_T__test____95____3____58____58____F32(a.sum);
+fn test_03_SumBinaryOp_loadOp_1test_03_F32_2(a: test_03_Sum_T_1test_03_F32_2) -> test_03_Sum_T_1test_03_F32_2 {
+    return test_03_Sum_T_1test_03_F32_2(a.sum);
 }
 
-struct test__3_Sum_T__test____95____3____58____58____F32 {
+struct test_03_Sum_T_1test_03_F32_2 {
     sum: f32
 }
 
-fn test__3_F32_add(a: f32, b: f32) -> f32 {
+fn test_03_F32_add(a: f32, b: f32) -> f32 {
     return a + b;
 }
 
-const test__3_Threads_value: u32 = 10u;
+const test_03_Threads_value: u32 = 10u;
 
-const test__3_BlockArea_value: u32 = 4u;
+const test_03_BlockArea_value: u32 = 4u;
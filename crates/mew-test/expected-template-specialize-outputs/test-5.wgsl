@@ -1,10 +1,10 @@
 
 
 @compute
-fn test__5_My__Lib_Demo_main() {
-    return test__5_My__Lib_Demo_one;
+fn main() {
+    return test_05_My_0Lib_Demo_one;
 }
 
-const test__5_My__Lib_Demo_one = test__5_Foo_bar;
+const test_05_My_0Lib_Demo_one = test_05_Foo_bar;
 
-const test__5_Foo_bar = 1;
+const test_05_Foo_bar = 1;
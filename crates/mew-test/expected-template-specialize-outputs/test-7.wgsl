@@ -1,5 +1,5 @@
 
 
-fn test__7_A_generic____function__f32(a: f32, b: f32) -> f32 {
+fn test_07_A_generic_0function_1f32_2(a: f32, b: f32) -> f32 {
     return a + b;
 }
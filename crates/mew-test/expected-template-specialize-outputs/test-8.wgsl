@@ -0,0 +1,24 @@
+
+
+@compute @workgroup_size(1)
+fn main() {
+    test_08_SmallSet_insert_1test_08_IdHasher_38u_2(test_08_IdHasher_Key(3u, 0u));
+}
+
+struct test_08_IdHasher_Key {
+    value: u32,
+    generation: u32
+}
+
+fn test_08_IdHasher_hash_0func(key: test_08_IdHasher_Key) -> u32 {
+    return key.value ^ test_08_IdHasher_seed;
+}
+
+const test_08_IdHasher_seed: u32 = 7u;
+
+fn test_08_SmallSet_insert_1test_08_IdHasher_38u_2(key: test_08_IdHasher_Key) {
+    let slot = test_08_IdHasher_hash_0func(key) % 8u;
+    test_08_SmallSet_slots_18u_2[slot] = key.value;
+}
+
+var<private> test_08_SmallSet_slots_18u_2: array<u32, 8u>;
@@ -0,0 +1,29 @@
+
+
+@compute @workgroup_size(1)
+fn main() {
+    let x = test_09_Mix_mix_1test_09_F32_2(1.0, 2.0);
+    let y = test_09_F32_lerp(1.0, 2.0, 0.5);
+}
+
+fn test_09_F32_lerp(a: f32, b: f32, t: f32) -> f32 {
+    return test_09_F32_add(test_09_F32_mul(a, test_09_F32_sub(test_09_F32_one, t)), test_09_F32_mul(b, t));
+}
+
+const test_09_F32_one: f32 = f32(1);
+
+fn test_09_F32_sub(a: f32, b: f32) -> f32 {
+    return a - b;
+}
+
+fn test_09_F32_mul(a: f32, b: f32) -> f32 {
+    return a * b;
+}
+
+fn test_09_F32_add(a: f32, b: f32) -> f32 {
+    return a + b;
+}
+
+fn test_09_Mix_mix_1test_09_F32_2(a: f32, b: f32) -> f32 {
+    return test_09_F32_lerp(a, b, test_09_F32_one);
+}
@@ -1,6 +1,9 @@
 #![cfg_attr(not(test), allow(dead_code, unused_imports))]
 
-use mew_api::{MewError, ModuleDescriptor, Path};
+use mew_api::{
+    GlslOptions, HlslOptions, MewError, ModuleDescriptor, MslOptions, Path, Permutation,
+    SpirVOptions, Target, TargetOutput,
+};
 use mew_bundle::Bundler;
 use mew_parse::syntax::TranslationUnit;
 use mew_types::CompilerPass;
@@ -27,6 +30,28 @@ fn webgpu_samples() {
     }
 }
 
+#[test]
+fn typecheck_webgpu_samples() {
+    let dir = std::fs::read_dir("webgpu-samples").expect("missing webgpu-samples");
+    for entry in dir {
+        let entry = entry.expect("error reading entry");
+        let path = entry.path();
+        if path.extension().unwrap() == "wgsl" {
+            println!("testing sample `{}`", path.display());
+            let source = std::fs::read_to_string(path).expect("failed to read file");
+            let source_module = mew_parse::Parser::parse_str(&source)
+                .inspect_err(|err| eprintln!("{err}"))
+                .expect("parse error");
+            let mut diagnostics = mew_types::Diagnostics::default();
+            mew_typecheck::TypeChecker::default().check(&source_module, &mut diagnostics);
+            for err in diagnostics.errors.iter() {
+                eprintln!("{err:?}");
+            }
+            assert!(!diagnostics.has_errors());
+        }
+    }
+}
+
 #[test]
 fn mew_samples() {
     let dir = std::fs::read_dir("mew-samples").expect("missing mew-samples");
@@ -68,6 +93,7 @@ pub fn bundle_mew_samples() -> Result<(), Box<MewError>> {
     let mut bundler = Bundler {
         sources: entrypoints.iter().map(|x| x.as_str()).collect(),
         enclosing_module_name: Some("MyLib".to_owned()),
+        ..Default::default()
     };
 
     let translation_unit = TranslationUnit::default();
@@ -77,6 +103,7 @@ pub fn bundle_mew_samples() -> Result<(), Box<MewError>> {
     let mut bundler = Bundler {
         sources: entrypoints.iter().map(|x| x.as_str()).collect(),
         enclosing_module_name: None,
+        ..Default::default()
     };
 
     let result_without_root_module = bundler.apply(&translation_unit)?;
@@ -178,7 +205,7 @@ fn mangle_mew_samples() -> Result<(), Box<MewError>> {
         if path.extension().unwrap() == "wgsl" || path.extension().unwrap() == "mew" {
             println!("testing sample `{}`", path.display());
 
-            let mut mangler = mew_mangle::Mangler;
+            let mut mangler = mew_mangle::Mangler::default();
 
             let source = std::fs::read_to_string(path.clone()).expect("failed to read file");
             let source_module = mew_parse::Parser::parse_str(&source)
@@ -209,6 +236,211 @@ fn mangle_mew_samples() -> Result<(), Box<MewError>> {
     Ok(())
 }
 
+/// Collects the names of the declarations of a module and of its nested modules.
+fn module_declaration_names(module: &mew_parse::syntax::Module, names: &mut Vec<String>) {
+    for member in module.members.iter() {
+        match member.as_ref() {
+            mew_parse::syntax::ModuleMemberDeclaration::Module(module) => {
+                module_declaration_names(module, names)
+            }
+            member => names.extend(member.name().map(|name| name.value)),
+        }
+    }
+}
+
+#[test]
+fn demangle_mangled_names() {
+    let mut names = vec![];
+    for dir in [
+        "expected-mangler-outputs",
+        "expected-template-specialize-outputs",
+    ] {
+        for entry in std::fs::read_dir(dir).expect("missing expected outputs") {
+            let path = entry.expect("error reading entry").path();
+            let source = std::fs::read_to_string(&path).expect("failed to read file");
+            let translation_unit = mew_parse::Parser::parse_str(&source)
+                .inspect_err(|err| eprintln!("{err}"))
+                .expect("parse error");
+            for declaration in translation_unit.global_declarations.iter() {
+                match declaration.as_ref() {
+                    mew_parse::syntax::GlobalDeclaration::Module(module) => {
+                        module_declaration_names(module, &mut names)
+                    }
+                    declaration => names.extend(declaration.name().map(|name| name.value)),
+                }
+            }
+        }
+    }
+    assert!(!names.is_empty());
+
+    for name in names.iter() {
+        let demangled = mew_types::mangling::demangle(name).expect("malformed mangled name");
+        println!("{name} => {demangled}");
+        // mangling the demangled path, like the specializer and the mangler do, round-trips
+        let mut path = mew_parse::Parser::parse_path(&demangled)
+            .expect("demangled path does not parse")
+            .path
+            .value;
+        mew_types::mangling::mangle_path(&mut path);
+        assert_eq!(&path[0].name.value, name);
+    }
+
+    assert_eq!(
+        mew_types::mangling::demangle(
+            "test_02_My_0Lib_VertexShader_main_1test_02_Hey_3test_02_WhatsUp_2"
+        )
+        .as_deref(),
+        Some("test_2::My_Lib::VertexShader::main<test_2::Hey, test_2::WhatsUp>")
+    );
+    assert_eq!(
+        mew_types::mangling::demangle("My_0Lib_VertexShader_Types_VertexOutput").as_deref(),
+        Some("My_Lib::VertexShader::Types::VertexOutput")
+    );
+    assert_eq!(
+        mew_types::mangling::demangle("Camera_1vec3_1f32_2_34_2").as_deref(),
+        Some("Camera<vec3<f32>, 4>")
+    );
+    assert_eq!(
+        mew_types::mangling::demangle("main_light_0dir").as_deref(),
+        Some("main::light_dir")
+    );
+    assert_eq!(
+        mew_types::mangling::demangle("a_42b_b").as_deref(),
+        Some("a+b")
+    );
+    // `a_::b` and `a::_b` were both `a___b` in the previous scheme
+    assert_ne!(
+        mew_types::mangling::mangle_str("a_::b"),
+        mew_types::mangling::mangle_str("a::_b")
+    );
+    assert_ne!(
+        mew_types::mangling::mangle_str("a_b::c"),
+        mew_types::mangling::mangle_str("a::b_c")
+    );
+    assert_eq!(mew_types::mangling::demangle("Lighting_"), None);
+    assert_eq!(mew_types::mangling::demangle("Lighting_5"), None);
+}
+
+#[test]
+fn mangling_strategies_mew_samples() -> Result<(), Box<MewError>> {
+    use mew_types::mangling::{
+        HashedMangling, ManglingStrategy, PreserveEntryPoints, ReadableMangling,
+    };
+
+    let source =
+        std::fs::read_to_string("multi-entry-inputs/shaders.mew").expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.validate = true;
+    api.mangling_strategy = Some(std::sync::Arc::new(HashedMangling::default()));
+    api.add_module(ModuleDescriptor {
+        module_name: "shaders",
+        source: mew_api::Source::Text(&source),
+    })?;
+    let paths = [
+        Path::Text("shaders::vertex".to_string()),
+        Path::Text("shaders::fragment".to_string()),
+    ];
+
+    let compilation = api.compile_many_with_diagnostics(&paths);
+    for err in compilation.errors.iter() {
+        eprintln!("{}", err.render(&api.source_map));
+    }
+    let output = compilation.output.expect("failed to compile");
+    println!("{output}");
+    // hashed names are stable, and demangled with the table of the compilation
+    let gamma = HashedMangling::default().mangle("shaders::gamma");
+    assert_eq!(gamma.len(), 8);
+    assert!(gamma.starts_with("_m"));
+    assert!(output.contains(&format!("fn {gamma}(")));
+    assert!(!output.contains("shaders_gamma"));
+    assert_eq!(
+        compilation.mew_paths.get(&gamma).map(String::as_str),
+        Some("shaders::gamma")
+    );
+    assert_eq!(HashedMangling::default().demangle(&gamma), None);
+    let reflection = mew_api::Reflection::new(&compilation.naga.expect("missing naga module"));
+    assert!(reflection.entry_point("shaders::vertex").is_some());
+
+    api.mangling_strategy = Some(std::sync::Arc::new(PreserveEntryPoints(ReadableMangling)));
+    let output = api.compile_many(&paths)?;
+    assert!(output.contains("fn vertex("));
+    assert!(output.contains("fn fragment("));
+    assert!(output.contains("fn shaders_gamma("));
+    let reflection = mew_api::Reflection::new(&api.compile_many_to_naga(&paths)?);
+    assert_eq!(
+        reflection
+            .entry_point("vertex")
+            .and_then(|entry_point| entry_point.name.mew_path.as_deref()),
+        Some("shaders::vertex")
+    );
+    Ok(())
+}
+
+#[test]
+fn hashed_mangling_collisions() {
+    use mew_types::mangling::HashedMangling;
+
+    let source = std::fs::read_to_string("diagnostic-inputs/hashed-collisions.mew")
+        .expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "scales",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+    let path = Path::Text("scales::main".to_string());
+
+    // with one digit, the 18 specializations of `Scale::k` cannot all have distinct names
+    api.mangling_strategy = Some(std::sync::Arc::new(HashedMangling { digits: 1 }));
+    let compilation = api.compile_with_diagnostics(&path);
+    for err in compilation.errors.iter() {
+        eprintln!("{}", err.render(&api.source_map));
+    }
+    assert_eq!(compilation.output, None);
+    assert!(!compilation.errors.is_empty());
+    for err in compilation.errors.iter() {
+        assert_eq!(err.diagnostic.code.as_deref(), Some("E0104"));
+        assert_eq!(
+            err.span.as_ref().and_then(|span| api.source_map.text(span)),
+            Some("k")
+        );
+    }
+
+    api.mangling_strategy = Some(std::sync::Arc::new(HashedMangling::default()));
+    let compilation = api.compile_with_diagnostics(&path);
+    assert!(compilation.errors.is_empty());
+    let output = compilation.output.expect("failed to compile");
+    assert_eq!(output.matches("const _m").count(), 18);
+}
+
+#[test]
+fn exported_names_mew_samples() -> Result<(), Box<MewError>> {
+    let source = std::fs::read_to_string("export-inputs/shaders.mew").expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "shaders",
+        source: mew_api::Source::Text(&source),
+    })?;
+    let compilation = api.compile_with_diagnostics(&Path::Text("shaders::fs_main".to_string()));
+    let output = compilation.output.expect("failed to compile");
+    println!("{output}");
+    assert!(output.contains("fn fs_main("));
+    assert!(output.contains("fn shade("));
+    assert!(output.contains("var<uniform> ambient: f32"));
+    assert!(output.contains("shade(normal, surface) + ambient"));
+    assert!(output.contains("struct Material {"));
+    assert!(output.contains("let surface = Material("));
+    assert!(output.contains("let color = tone("));
+    assert!(output.contains("let exposure = 2.0;"));
+    assert!(output.contains("color * surface.albedo * exposure"));
+    assert!(!output.contains("@export"));
+    assert_eq!(
+        compilation.mew_paths.get("shade").map(String::as_str),
+        Some("shaders::Lighting::phong")
+    );
+    Ok(())
+}
+
 #[test]
 fn flatten_mew_samples() -> Result<(), Box<MewError>> {
     let dir =
@@ -338,6 +570,182 @@ fn dealias_mew_samples() -> Result<(), Box<MewError>> {
     Ok(())
 }
 
+#[test]
+fn dce_mew_samples() -> Result<(), Box<MewError>> {
+    let dir = std::fs::read_dir("dce-inputs").expect("missing dce-inputs");
+
+    for entry in dir {
+        let entry = entry.expect("error reading entry");
+        let path: std::path::PathBuf = entry.path();
+        if path.extension().unwrap() == "wgsl" || path.extension().unwrap() == "mew" {
+            println!("testing sample `{}`", path.display());
+
+            let mut resolver = mew_resolve::Resolver;
+
+            let source = std::fs::read_to_string(path.clone()).expect("failed to read file");
+            let source_module = mew_parse::Parser::parse_str(&source)
+                .inspect_err(|err| eprintln!("{err}"))
+                .expect("parse error");
+
+            let mut result = resolver.apply(&source_module)?;
+
+            let mut dealiaser = mew_dealias::Dealiaser;
+
+            dealiaser.apply_mut(&mut result)?;
+
+            let entrypoint = mew_parse::Parser::parse_path("main").expect("parse error");
+            let mut eliminator = mew_dce::DeadCodeEliminator {
+                entrypoints: vec![entrypoint.path.value],
+            };
+
+            eliminator.apply_mut(&mut result)?;
+
+            let expected_output_location: PathBuf = std::env::current_dir()
+                .unwrap()
+                .join("expected-dce-outputs")
+                .join(path.file_name().unwrap());
+
+            #[cfg(feature = "update_expected_output")]
+            {
+                let disp: String = format!("{result}");
+                let _ = std::fs::write(expected_output_location.clone(), disp).expect("Written");
+            }
+
+            let expected_output_module = mew_parse::Parser::parse_str(
+                &std::fs::read_to_string(expected_output_location.clone()).expect("READ"),
+            )
+            .inspect_err(|err| eprintln!("{err}"))
+            .expect("parse error");
+            assert_eq!(format!("{}", result), format!("{}", expected_output_module));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn conditional_mew_samples() -> Result<(), Box<MewError>> {
+    let dir = std::fs::read_dir("conditional-inputs").expect("missing conditional-inputs");
+
+    for entry in dir {
+        let entry = entry.expect("error reading entry");
+        let path: std::path::PathBuf = entry.path();
+        if path.extension().unwrap() == "wgsl" || path.extension().unwrap() == "mew" {
+            println!("testing sample `{}`", path.display());
+
+            let mut translator = mew_conditional::ConditionalTranslator {
+                features: HashMap::from([
+                    ("debug".to_string(), true),
+                    ("skinning".to_string(), true),
+                    ("high_quality".to_string(), false),
+                ]),
+            };
+
+            let source = std::fs::read_to_string(path.clone()).expect("failed to read file");
+            let source_module = mew_parse::Parser::parse_str(&source)
+                .inspect_err(|err| eprintln!("{err}"))
+                .expect("parse error");
+
+            let result = translator.apply(&source_module)?;
+
+            let expected_output_location: PathBuf = std::env::current_dir()
+                .unwrap()
+                .join("expected-conditional-outputs")
+                .join(path.file_name().unwrap());
+
+            #[cfg(feature = "update_expected_output")]
+            {
+                let disp: String = format!("{result}");
+                let _ = std::fs::write(expected_output_location.clone(), disp).expect("Written");
+            }
+
+            let expected_output_module = mew_parse::Parser::parse_str(
+                &std::fs::read_to_string(expected_output_location.clone()).expect("READ"),
+            )
+            .inspect_err(|err| eprintln!("{err}"))
+            .expect("parse error");
+            assert_eq!(format!("{}", result), format!("{}", expected_output_module));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn permutation_compilation() {
+    let source =
+        std::fs::read_to_string("permutation-inputs/material.mew").expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.validate = true;
+    api.features.insert("tinted".to_string(), true);
+    api.add_module(ModuleDescriptor {
+        module_name: "material",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+
+    let permutation = |features: &[(&str, bool)], overrides: &[(&str, f64)]| Permutation {
+        features: features
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect(),
+        overrides: overrides
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect(),
+    };
+    let permutations = api.compile_permutations(
+        &Path::Text("material::Material::main".to_string()),
+        &[
+            permutation(&[], &[]),
+            permutation(&[("skinning", true)], &[]),
+            permutation(&[("shadows", true)], &[]),
+            permutation(&[("tinted", false)], &[]),
+            permutation(&[("skinning", true), ("shadows", true)], &[]),
+            permutation(&[("broken", true)], &[]),
+            permutation(&[], &[("material::strength", 2.5)]),
+            permutation(&[("shadows", true)], &[("material::strength", 2.5)]),
+            permutation(&[], &[("material::missing", 1.0)]),
+            permutation(&[], &[("material::samples", -1.0)]),
+            permutation(&[], &[("material::samples", 1.5)]),
+            // `samples` is not used, so the output does not change
+            permutation(&[], &[("material::samples", 8.0)]),
+        ],
+    );
+    assert_eq!(
+        permutations.indices,
+        vec![0, 1, 0, 2, 1, 3, 4, 4, 5, 6, 7, 0]
+    );
+
+    let outputs = permutations
+        .compilations
+        .iter()
+        .map(|compilation| compilation.output.as_deref())
+        .collect::<Vec<_>>();
+    for (index, output) in outputs.iter().enumerate().take(3) {
+        let output = output.expect("expected an output");
+        eprintln!("{output}");
+        assert_eq!(output.contains("weights"), index == 1);
+        assert_eq!(output.contains("0.5"), index != 2);
+        assert!(!output.contains("shadow_factor"));
+    }
+    assert_eq!(outputs[3], None);
+    let errors = &permutations.compilation(5).unwrap().errors;
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].diagnostic.code.as_deref(), Some("E0100"));
+
+    let output = outputs[4].expect("expected an output");
+    assert!(output.contains("strength: f32 = 2.5f;"));
+    let errors = &permutations.compilation(8).unwrap().errors;
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].diagnostic.code.as_deref(), Some("E0111"));
+
+    // values of integer types are not truncated nor clamped
+    for index in [9, 10] {
+        let errors = &permutations.compilation(index).unwrap().errors;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].diagnostic.code.as_deref(), Some("E0112"));
+    }
+}
+
 #[test]
 fn template_specialize_mew_samples() -> Result<(), Box<MewError>> {
     let dir =
@@ -359,6 +767,9 @@ fn template_specialize_mew_samples() -> Result<(), Box<MewError>> {
 }::main"#,
         ),
         ("test_7", r#"test_7::A::generic_function<f32>"#),
+        ("test_8", "test_8::main"),
+        ("test_9", "test_9::main"),
+        ("test_10", "test_10::main"),
     ]);
 
     for entry in dir {
@@ -409,3 +820,1153 @@ fn template_specialize_mew_samples() -> Result<(), Box<MewError>> {
     }
     Ok(())
 }
+
+#[test]
+fn parse_recovery_mew_samples() {
+    // the number of errors in each sample, and the source skipped at the global error
+    let samples = [
+        ("three-errors.mew", 3, "const broken: f32 ="),
+        ("one-error.mew", 1, "alias = f32"),
+    ];
+    for (file_name, error_count, skipped) in samples {
+        println!("testing sample `{file_name}`");
+        let path = PathBuf::from("parse-recovery-inputs").join(file_name);
+        let source = std::fs::read_to_string(path).expect("failed to read file");
+        let (translation_unit, errors) = mew_parse::Parser::parse_str_with_recovery(&source);
+        for err in errors.iter() {
+            eprintln!("{err}");
+        }
+        assert_eq!(errors.len(), error_count);
+        let error_spans = translation_unit
+            .global_declarations
+            .iter()
+            .filter_map(|decl| match &decl.value {
+                mew_parse::syntax::GlobalDeclaration::Error(span) => Some(span.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(error_spans.len(), 1);
+        assert_eq!(source[error_spans[0].clone()].trim(), skipped);
+        assert_eq!(
+            translation_unit
+                .global_declarations
+                .last()
+                .and_then(|decl| decl.name())
+                .map(|name| name.value),
+            Some("fine".to_string())
+        );
+        let first_error = mew_parse::Parser::parse_str(&source).unwrap_err();
+        assert_eq!(first_error, errors[0]);
+    }
+
+    // the declarations before the error that stops the parse are kept
+    let source = std::fs::read_to_string("parse-recovery-inputs/unrecoverable.mew")
+        .expect("failed to read file");
+    let (translation_unit, errors) = mew_parse::Parser::parse_str_with_recovery(&source);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(&source[errors[0].span()], "$");
+    let names = translation_unit
+        .global_declarations
+        .iter()
+        .filter_map(|decl| decl.name().map(|name| name.value))
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["main", "fine"]);
+}
+
+#[test]
+fn lossless_samples() {
+    for dir in ["webgpu-samples", "mew-samples"] {
+        let dir = std::fs::read_dir(dir).expect("missing samples");
+        for entry in dir {
+            let entry = entry.expect("error reading entry");
+            let path = entry.path();
+            if path.extension().unwrap() == "wgsl" || path.extension().unwrap() == "mew" {
+                println!("testing sample `{}`", path.display());
+                let source = std::fs::read_to_string(path).expect("failed to read file");
+                let concrete = mew_parse::cst::ConcreteSyntax::parse(&source);
+                assert_eq!(format!("{concrete}"), source);
+                assert_eq!(
+                    concrete.comments().next().is_some(),
+                    source.contains("//") || source.contains("/*")
+                );
+            }
+        }
+    }
+
+    use mew_parse::cst::TriviaKind::*;
+    let source = std::fs::read_to_string("lossless-inputs/comments.mew").expect("READ");
+    let concrete = mew_parse::cst::ConcreteSyntax::parse(&source);
+    assert_eq!(format!("{concrete}"), source);
+    let text = |trivia: &[mew_parse::cst::Trivia]| {
+        trivia
+            .iter()
+            .map(|trivia| (trivia.kind, concrete.text(&trivia.span)))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        concrete
+            .comments()
+            .map(|comment| concrete.text(&comment.span))
+            .collect::<Vec<_>>(),
+        vec![
+            "/* Lighting helpers,\n   shared by every pass. */",
+            "// A point light.",
+            "/* world space */",
+            "// Scales the color.",
+            "/* unused */",
+            "// trailing",
+        ]
+    );
+    assert_eq!(
+        text(concrete.trailing_trivia()),
+        vec![
+            (Whitespace, " "),
+            (LineComment, "// trailing"),
+            (Whitespace, "\n")
+        ]
+    );
+    let source_module = mew_parse::Parser::parse_str(&source).expect("parse error");
+    let leading_trivia = source_module
+        .global_declarations
+        .iter()
+        .map(|declaration| text(concrete.leading_trivia(&declaration.span)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        leading_trivia,
+        vec![
+            vec![
+                (
+                    BlockComment,
+                    "/* Lighting helpers,\n   shared by every pass. */"
+                ),
+                (Whitespace, "\n"),
+                (LineComment, "// A point light."),
+                (Whitespace, "\n"),
+            ],
+            vec![
+                (Whitespace, "\n\n"),
+                (LineComment, "// Scales the color."),
+                (Whitespace, "\n"),
+                (BlockComment, "/* unused */"),
+                (Whitespace, " "),
+            ],
+        ]
+    );
+}
+
+#[test]
+fn source_map_error_locations() {
+    let mut api = mew_api::MewApi::default();
+    for module_name in ["first", "second"] {
+        let source = std::fs::read_to_string(format!("source-map-inputs/{module_name}.mew"))
+            .expect("failed to read file");
+        api.add_module(ModuleDescriptor {
+            module_name,
+            source: mew_api::Source::Text(&source),
+        })
+        .expect("failed to add module");
+    }
+
+    let err = api
+        .compile(&Path::Text("second::main".to_string()))
+        .expect_err("expected an unresolved symbol");
+    eprintln!("{err}");
+    let location = err.location.as_ref().expect("missing location");
+    assert_eq!(location.to_string(), "second:4:19");
+    assert_eq!(
+        err.span.as_ref().and_then(|span| api.source_map.text(span)),
+        Some("missing")
+    );
+
+    // removing a module removes its files, and the other files keep their locations
+    let (first, _) = api.source_map.files().next().expect("missing file");
+    api.remove_module(&"first".to_string())
+        .expect("failed to remove module");
+    assert_eq!(api.source_map.file(first), None);
+    assert_eq!(
+        api.source_map
+            .files()
+            .map(|(_, file)| file.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["second"]
+    );
+    assert_eq!(
+        err.span
+            .as_ref()
+            .and_then(|span| api.source_map.location(span)),
+        err.location
+    );
+    assert!(api.remove_module(&"first".to_string()).is_err());
+}
+
+#[test]
+fn missing_template_argument_diagnostic() {
+    let source = std::fs::read_to_string("diagnostic-inputs/missing-template-argument.mew")
+        .expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "missing_template_argument",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+
+    let err = api
+        .compile(&Path::Text("missing_template_argument::main".to_string()))
+        .expect_err("expected a missing template argument");
+    let rendered = err.render(&api.source_map);
+    eprintln!("{rendered}");
+    assert_eq!(err.diagnostic.code.as_deref(), Some("E0102"));
+    let labels = err
+        .diagnostic
+        .labels
+        .iter()
+        .map(|label| (label.primary, api.source_map.text(&label.span)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        labels,
+        vec![(true, Some("generic_function")), (false, Some("T"))]
+    );
+    assert!(rendered.contains("missing_template_argument:8:"));
+}
+
+#[test]
+fn name_collision_diagnostics() {
+    let source = std::fs::read_to_string("diagnostic-inputs/name-collisions.mew")
+        .expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "texture",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+
+    let compilation = api.compile_with_diagnostics(&Path::Text("texture::main".to_string()));
+    assert_eq!(compilation.output, None);
+    let collisions = compilation
+        .errors
+        .iter()
+        .inspect(|err| eprintln!("{}", err.render(&api.source_map)))
+        .map(|err| {
+            assert_eq!(err.diagnostic.code.as_deref(), Some("E0104"));
+            err.diagnostic
+                .labels
+                .iter()
+                .map(|label| (label.primary, api.source_map.text(&label.span)))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        collisions,
+        vec![
+            vec![(true, Some("scale")), (false, Some("boost"))],
+            vec![(true, Some("external"))],
+        ]
+    );
+    assert!(
+        compilation.errors[0]
+            .to_string()
+            .contains("`texture_Light_scale`")
+    );
+    assert!(
+        compilation.errors[1]
+            .to_string()
+            .contains("`texture_external`")
+    );
+}
+
+#[test]
+fn misplaced_condition_diagnostic() {
+    let source = std::fs::read_to_string("diagnostic-inputs/misplaced-condition.mew")
+        .expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.features.insert("debug".to_string(), true);
+    api.add_module(ModuleDescriptor {
+        module_name: "conditions",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+
+    let err = api
+        .compile(&Path::Text("conditions::main".to_string()))
+        .expect_err("expected a misplaced condition");
+    eprintln!("{}", err.render(&api.source_map));
+    assert_eq!(err.diagnostic.code.as_deref(), Some("E0106"));
+    assert!(err.to_string().contains("misplaced `@else`"));
+    assert!(err.render(&api.source_map).contains("conditions:4:"));
+}
+
+#[test]
+fn unsatisfied_interface_diagnostic() {
+    let source = std::fs::read_to_string("diagnostic-inputs/unsatisfied-interface.mew")
+        .expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "interfaces",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+
+    let err = api
+        .compile(&Path::Text("interfaces::main".to_string()))
+        .expect_err("expected an unsatisfied interface");
+    let rendered = err.render(&api.source_map);
+    eprintln!("{rendered}");
+    assert_eq!(err.diagnostic.code.as_deref(), Some("E0107"));
+    assert!(
+        err.to_string()
+            .contains("`interfaces::FloatHasher` does not implement `Hashable`")
+    );
+    let labels = err
+        .diagnostic
+        .labels
+        .iter()
+        .map(|label| (api.source_map.text(&label.span), label.message.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        labels,
+        vec![
+            (
+                Some("FloatHasher"),
+                "this argument does not implement `Hashable`"
+            ),
+            (Some("Hashable"), "required by this bound"),
+            (Some("const seed: u32;"), "`seed` is required to match this"),
+            (
+                Some("const seed: f32 = 7.0;"),
+                "this has type `f32`, expected `u32`"
+            ),
+            (
+                Some("fn hash_func(key: u32) -> u32;"),
+                "`hash_func` is missing from `interfaces::FloatHasher`"
+            ),
+            (
+                Some("alias Key;"),
+                "`Key` is missing from `interfaces::FloatHasher`"
+            ),
+        ]
+    );
+    assert!(rendered.contains("interfaces:23:"));
+}
+
+#[test]
+fn malformed_export_diagnostics() {
+    let source = std::fs::read_to_string("diagnostic-inputs/malformed-exports.mew")
+        .expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "malformed_exports",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+
+    let compilation =
+        api.compile_with_diagnostics(&Path::Text("malformed_exports::main".to_string()));
+    assert_eq!(compilation.output, None);
+    let mut exports = compilation
+        .errors
+        .iter()
+        .inspect(|err| eprintln!("{}", err.render(&api.source_map)))
+        .map(|err| {
+            assert_eq!(err.diagnostic.code.as_deref(), Some("E0110"));
+            err.span.as_ref().and_then(|span| api.source_map.text(span))
+        })
+        .collect::<Vec<_>>();
+    exports.sort();
+    assert_eq!(
+        exports,
+        vec![
+            Some("@export(\"b\", \"c\")"),
+            Some("@export(1)"),
+            Some("@export(c)")
+        ]
+    );
+}
+
+#[test]
+fn entry_point_clash_diagnostic() {
+    let source = std::fs::read_to_string("diagnostic-inputs/entry-point-clash.mew")
+        .expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "entry_point_clash",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+
+    let err = api
+        .compile_many(&[
+            Path::Text("entry_point_clash::Forward::main".to_string()),
+            Path::Text("entry_point_clash::Deferred::main".to_string()),
+        ])
+        .expect_err("expected a name clash");
+    eprintln!("{}", err.render(&api.source_map));
+    assert_eq!(err.diagnostic.code.as_deref(), Some("E0104"));
+    let labels = err
+        .diagnostic
+        .labels
+        .iter()
+        .map(|label| (label.primary, api.source_map.text(&label.span)))
+        .collect::<Vec<_>>();
+    assert_eq!(labels, vec![(true, Some("main")), (false, Some("main"))]);
+}
+
+#[test]
+fn unresolved_paths_diagnostics() {
+    let source = std::fs::read_to_string("diagnostic-inputs/unresolved-paths.mew")
+        .expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "unresolved_paths",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+
+    let compilation =
+        api.compile_with_diagnostics(&Path::Text("unresolved_paths::main".to_string()));
+    assert_eq!(compilation.output, None);
+    let mut unresolved = compilation
+        .errors
+        .iter()
+        .inspect(|err| eprintln!("{}", err.render(&api.source_map)))
+        .filter_map(|err| err.span.as_ref().and_then(|span| api.source_map.text(span)))
+        .collect::<Vec<_>>();
+    unresolved.sort();
+    assert_eq!(
+        unresolved,
+        vec!["missing_a", "missing_b", "missing_c", "missing_scale"]
+    );
+
+    let err = api
+        .compile(&Path::Text("unresolved_paths::main".to_string()))
+        .expect_err("expected an unresolved path");
+    assert_eq!(err.span, compilation.errors[0].span);
+}
+
+#[test]
+fn unresolved_path_suggestions() {
+    let source =
+        std::fs::read_to_string("diagnostic-inputs/suggestions.mew").expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "suggestions",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+
+    let compilation = api.compile_with_diagnostics(&Path::Text("suggestions::main".to_string()));
+    let mut help = compilation
+        .errors
+        .iter()
+        .inspect(|err| eprintln!("{}", err.render(&api.source_map)))
+        .flat_map(|err| err.diagnostic.help.iter().cloned())
+        .collect::<Vec<_>>();
+    help.sort();
+    assert_eq!(
+        help,
+        vec![
+            "`square` exists in module `suggestions::Math`, import it with `import suggestions::Math::square;`",
+            "a similar name exists: `offset`",
+        ]
+    );
+}
+
+#[test]
+fn builtin_type_generators_resolve() {
+    let source = std::fs::read_to_string("diagnostic-inputs/type-generators.mew")
+        .expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "type_generators",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+
+    let compilation =
+        api.compile_with_diagnostics(&Path::Text("type_generators::main".to_string()));
+    for err in compilation.errors.iter() {
+        eprintln!("{}", err.render(&api.source_map));
+    }
+    assert!(compilation.errors.is_empty());
+    let output = compilation.output.expect("expected an output");
+    assert!(output.contains("texture_2d<f32>"));
+    assert!(output.contains("array<f32>"));
+}
+
+#[test]
+fn directory_loader_mew_samples() -> Result<(), Box<MewError>> {
+    let loader = mew_bundle::DirectoryLoader::new("loader-inputs");
+    let mut api = mew_api::MewApi::default();
+    let entrypoint = Path::Text("App::main".to_string());
+    api.load_module(&loader, &entrypoint)?;
+    let result = api.compile(&entrypoint)?;
+
+    let expected_output_location: PathBuf = std::env::current_dir()
+        .unwrap()
+        .join("expected-loader-outputs")
+        .join("app.wgsl");
+
+    #[cfg(feature = "update_expected_output")]
+    {
+        let _ = std::fs::write(expected_output_location.clone(), &result).expect("Written");
+    }
+
+    let expected_output_module = mew_parse::Parser::parse_str(
+        &std::fs::read_to_string(expected_output_location.clone()).expect("READ"),
+    )
+    .inspect_err(|err| eprintln!("{err}"))
+    .expect("parse error");
+    assert_eq!(result, format!("{}", expected_output_module));
+    Ok(())
+}
+
+#[test]
+fn lazy_loading_mew_samples() -> Result<(), Box<MewError>> {
+    let requested = std::sync::Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+    let provider = {
+        let requested = requested.clone();
+        let loader = mew_bundle::DirectoryLoader::new("lazy-loader-inputs");
+        move |path: &[String]| {
+            requested.lock().unwrap().push(path.join("::"));
+            mew_bundle::ModuleLoader::load(&loader, path)
+        }
+    };
+    let mut api = mew_api::MewApi::default();
+    api.set_module_provider(provider);
+    let entrypoint = Path::Text("Main::fragment".to_string());
+    api.load_dependencies(std::slice::from_ref(&entrypoint))?;
+    let result = api.compile(&entrypoint)?;
+
+    // the members of the loaded modules are not requested
+    assert_eq!(
+        *requested.lock().unwrap(),
+        vec!["Main", "Color", "Color::Grade", "Color::Tonemap"]
+    );
+
+    // loading again requests nothing, as every module is loaded
+    api.load_dependencies(std::slice::from_ref(&entrypoint))?;
+    assert_eq!(requested.lock().unwrap().len(), 4);
+
+    let expected_output_location: PathBuf = std::env::current_dir()
+        .unwrap()
+        .join("expected-loader-outputs")
+        .join("lazy.wgsl");
+
+    #[cfg(feature = "update_expected_output")]
+    {
+        let _ = std::fs::write(expected_output_location.clone(), &result).expect("Written");
+    }
+
+    let expected_output_module = mew_parse::Parser::parse_str(
+        &std::fs::read_to_string(expected_output_location.clone()).expect("READ"),
+    )
+    .inspect_err(|err| eprintln!("{err}"))
+    .expect("parse error");
+    assert_eq!(result, format!("{}", expected_output_module));
+    Ok(())
+}
+
+#[test]
+fn compile_many_mew_samples() -> Result<(), Box<MewError>> {
+    let source =
+        std::fs::read_to_string("multi-entry-inputs/shaders.mew").expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "shaders",
+        source: mew_api::Source::Text(&source),
+    })?;
+    api.validate = true;
+    let result = api.compile_many(&[
+        Path::Text("shaders::vertex".to_string()),
+        Path::Text("shaders::fragment".to_string()),
+    ])?;
+
+    let expected_output_location: PathBuf = std::env::current_dir()
+        .unwrap()
+        .join("expected-multi-entry-outputs")
+        .join("shaders.wgsl");
+
+    #[cfg(feature = "update_expected_output")]
+    {
+        let _ = std::fs::write(expected_output_location.clone(), &result).expect("Written");
+    }
+
+    let expected_output_module = mew_parse::Parser::parse_str(
+        &std::fs::read_to_string(expected_output_location.clone()).expect("READ"),
+    )
+    .inspect_err(|err| eprintln!("{err}"))
+    .expect("parse error");
+    assert_eq!(result, format!("{}", expected_output_module));
+    assert_eq!(result.matches("fn shaders_gamma(").count(), 1);
+    assert_eq!(result.matches("struct shaders_VertexOutput").count(), 1);
+    assert!(!result.contains("unused"));
+    Ok(())
+}
+
+#[test]
+fn naga_validation_diagnostics() {
+    let source =
+        std::fs::read_to_string("diagnostic-inputs/invalid-wgsl.mew").expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "invalid_wgsl",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+
+    let path = Path::Text("invalid_wgsl::main".to_string());
+    assert!(api.compile(&path).is_ok(), "validation is opt-in");
+
+    api.validate = true;
+    let err = api.compile(&path).expect_err("expected a validation error");
+    eprintln!("{}", err.render(&api.source_map));
+    assert_eq!(err.diagnostic.code.as_deref(), Some("E0301"));
+    assert_eq!(
+        err.location.as_ref().map(|location| location.to_string()),
+        Some("invalid_wgsl:7:5".to_string())
+    );
+}
+
+#[test]
+fn type_mismatch_diagnostics() {
+    let source = std::fs::read_to_string("diagnostic-inputs/type-mismatch.mew")
+        .expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "shading",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+
+    let path = Path::Text("shading::main".to_string());
+    assert!(api.compile(&path).is_ok(), "type checking is opt-in");
+
+    api.type_check = true;
+    let compilation = api.compile_with_diagnostics(&path);
+    assert_eq!(compilation.output, None);
+    let errors = compilation
+        .errors
+        .iter()
+        .inspect(|err| eprintln!("{}", err.render(&api.source_map)))
+        .map(|err| {
+            assert_eq!(err.diagnostic.code.as_deref(), Some("E0109"));
+            err.diagnostic
+                .labels
+                .iter()
+                .map(|label| (api.source_map.text(&label.span), label.message.as_str()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        vec![
+            vec![
+                (
+                    Some("Lighting::direction(light)"),
+                    "expected `vec4<f32>`, found `vec3<f32>`"
+                ),
+                (Some("vec4f"), "expected due to this"),
+            ],
+            vec![
+                (
+                    Some("light"),
+                    "expected `f32`, found `shading::Lighting::Light`"
+                ),
+                (Some("f32"), "expected due to this"),
+            ],
+        ]
+    );
+    assert_eq!(
+        compilation.errors[0]
+            .location
+            .as_ref()
+            .map(|location| location.to_string()),
+        Some("shading:15:24".to_string())
+    );
+}
+
+#[test]
+fn type_error_diagnostics() {
+    let source =
+        std::fs::read_to_string("diagnostic-inputs/type-errors.mew").expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "errors",
+        source: mew_api::Source::Text(&source),
+    })
+    .expect("failed to add module");
+    api.type_check = true;
+
+    let compilation = api.compile_with_diagnostics(&Path::Text("errors::main".to_string()));
+    assert_eq!(compilation.output, None);
+    let errors = compilation
+        .errors
+        .iter()
+        .inspect(|err| eprintln!("{}", err.render(&api.source_map)))
+        .map(|err| {
+            assert_eq!(err.diagnostic.code.as_deref(), Some("E0109"));
+            (
+                err.diagnostic.message.as_str(),
+                err.diagnostic
+                    .labels
+                    .iter()
+                    .map(|label| api.source_map.text(&label.span))
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+    // `let whole: f32 = 1;` converts the abstract integer, and is not an error. The unused
+    // function is checked, and is reported first as it is declared first in the output;
+    // the function of the template that is never specialized is not checked.
+    assert_eq!(
+        errors,
+        vec![
+            ("mismatched types", vec![Some("0.5"), Some("u32")]),
+            ("mismatched types", vec![Some("1.5"), Some("u32")]),
+            (
+                "cannot apply `+` to `errors::Light` and `AbstractFloat`",
+                vec![Some("light + 1.0")]
+            ),
+            (
+                "no member `color` on type `errors::Light`",
+                vec![Some("color")]
+            ),
+            (
+                "no overload of `max` takes arguments of types (`AbstractFloat`, `bool`)",
+                vec![Some("max(1.0, true)")]
+            ),
+            (
+                "`errors::scale` takes 1 argument but 2 were supplied",
+                vec![Some("scale(1.0, 2.0)")]
+            ),
+            ("cannot assign to this expression", vec![Some("whole")]),
+            ("missing return value", vec![Some("return;")]),
+            ("unexpected return value", vec![Some("1.0")]),
+        ]
+    );
+}
+
+#[test]
+fn compile_to_naga_mew_samples() -> Result<(), Box<MewError>> {
+    let source =
+        std::fs::read_to_string("multi-entry-inputs/shaders.mew").expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "shaders",
+        source: mew_api::Source::Text(&source),
+    })?;
+    let compiled = api.compile_many_to_naga(&[
+        Path::Text("shaders::vertex".to_string()),
+        Path::Text("shaders::fragment".to_string()),
+    ])?;
+
+    let mut entry_points = compiled
+        .module
+        .entry_points
+        .iter()
+        .map(|entry_point| (entry_point.name.as_str(), entry_point.stage))
+        .collect::<Vec<_>>();
+    entry_points.sort_by_key(|(name, _)| *name);
+    assert_eq!(
+        entry_points,
+        vec![
+            ("fragment", mew_api::naga::ShaderStage::Fragment),
+            ("vertex", mew_api::naga::ShaderStage::Vertex),
+        ]
+    );
+
+    // the module and its info feed naga's writers directly
+    let wgsl = mew_api::naga::back::wgsl::write_string(
+        &compiled.module,
+        &compiled.info,
+        mew_api::naga::back::wgsl::WriterFlags::empty(),
+    )
+    .expect("failed to write WGSL");
+    assert!(wgsl.contains("fn shaders_gamma("));
+
+    let source =
+        std::fs::read_to_string("diagnostic-inputs/invalid-wgsl.mew").expect("failed to read file");
+    api.add_module(ModuleDescriptor {
+        module_name: "invalid_wgsl",
+        source: mew_api::Source::Text(&source),
+    })?;
+    let err = api
+        .compile_to_naga(&Path::Text("invalid_wgsl::main".to_string()))
+        .expect_err("expected a validation error");
+    assert_eq!(err.diagnostic.code.as_deref(), Some("E0301"));
+    Ok(())
+}
+
+#[test]
+fn target_mew_samples() -> Result<(), Box<MewError>> {
+    let source =
+        std::fs::read_to_string("multi-entry-inputs/shaders.mew").expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "shaders",
+        source: mew_api::Source::Text(&source),
+    })?;
+    let paths = [
+        Path::Text("shaders::vertex".to_string()),
+        Path::Text("shaders::fragment".to_string()),
+    ];
+
+    let wgsl = api.compile_many_to_target(&paths, &Target::Wgsl)?;
+    assert_eq!(wgsl, TargetOutput::Text(api.compile_many(&paths)?));
+
+    let TargetOutput::SpirV(words) =
+        api.compile_many_to_target(&paths, &Target::SpirV(SpirVOptions::default()))?
+    else {
+        panic!("expected a SPIR-V binary");
+    };
+    assert_eq!(words[0], 0x07230203);
+
+    let err = api
+        .compile_many_to_target(&paths, &Target::Glsl(GlslOptions::default()))
+        .expect_err("a GLSL shader has a single entry point");
+    assert_eq!(err.diagnostic.code.as_deref(), Some("E0302"));
+
+    let glsl = api.compile_many_to_target(
+        &paths,
+        &Target::Glsl(GlslOptions {
+            version: mew_api::naga::back::glsl::Version::new_gles(300),
+            entry_point: Some("fragment".to_string()),
+        }),
+    )?;
+    let TargetOutput::Text(glsl) = glsl else {
+        panic!("expected GLSL source");
+    };
+    assert!(glsl.starts_with("#version 300 es"));
+
+    let TargetOutput::Text(hlsl) = api.compile_many_to_target(
+        &paths,
+        &Target::Hlsl(HlslOptions {
+            shader_model: mew_api::naga::back::hlsl::ShaderModel::V6_0,
+            entry_point: Some("vertex".to_string()),
+        }),
+    )?
+    else {
+        panic!("expected HLSL source");
+    };
+    assert!(hlsl.contains(" vertex("));
+    assert!(!hlsl.contains(" fragment("));
+
+    let TargetOutput::Text(msl) =
+        api.compile_many_to_target(&paths, &Target::Msl(MslOptions::default()))?
+    else {
+        panic!("expected MSL source");
+    };
+    assert!(msl.contains("metal_stdlib"));
+    Ok(())
+}
+
+#[test]
+fn reflection_mew_samples() -> Result<(), Box<MewError>> {
+    let source =
+        std::fs::read_to_string("reflection-inputs/scene.mew").expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "scene",
+        source: mew_api::Source::Text(&source),
+    })?;
+    let reflection = api.reflect_many(&[
+        Path::Text("scene::vertex".to_string()),
+        Path::Text("scene::fragment".to_string()),
+        Path::Text("scene::simulate".to_string()),
+    ])?;
+
+    let simulate = reflection
+        .entry_point("scene::simulate")
+        .expect("entry point");
+    assert_eq!(simulate.name.name, "simulate");
+    assert_eq!(simulate.stage, mew_api::naga::ShaderStage::Compute);
+    assert_eq!(simulate.workgroup_size, [64, 1, 1]);
+
+    let vertex = reflection.entry_point("vertex").expect("entry point");
+    assert_eq!(vertex.name.mew_path.as_deref(), Some("scene::vertex"));
+    assert_eq!(
+        vertex
+            .inputs
+            .iter()
+            .map(|input| (input.name.as_str(), input.location, input.ty.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("position", 0, "vec3<f32>"),
+            ("uv", 1, "vec2<f32>"),
+            ("tint", 2, "vec4<f32>"),
+        ]
+    );
+
+    let uniforms = reflection
+        .binding("scene::Camera::uniforms")
+        .expect("binding");
+    assert_eq!((uniforms.group, uniforms.binding), (0, 0));
+    assert_eq!(uniforms.kind, mew_api::ResourceKind::UniformBuffer);
+    assert_eq!(uniforms.ty, "scene_Camera_Uniforms");
+    let particles = reflection.binding("scene::particles").expect("binding");
+    assert_eq!((particles.group, particles.binding), (1, 0));
+    assert_eq!(
+        particles.kind,
+        mew_api::ResourceKind::StorageBuffer {
+            access: mew_api::Access::ReadWrite
+        }
+    );
+    assert_eq!(
+        reflection.binding("scene::albedo").map(|b| b.kind),
+        Some(mew_api::ResourceKind::Texture)
+    );
+    assert_eq!(
+        reflection.binding("scene::albedo_sampler").map(|b| b.kind),
+        Some(mew_api::ResourceKind::Sampler { comparison: false })
+    );
+
+    let layout = reflection
+        .struct_layout("scene::Camera::Uniforms")
+        .expect("struct layout");
+    assert_eq!((layout.size, layout.alignment), (80, 16));
+    assert_eq!(
+        layout
+            .members
+            .iter()
+            .map(|member| (member.name.as_str(), member.offset, member.size))
+            .collect::<Vec<_>>(),
+        vec![
+            ("view_projection", 0, 64),
+            ("position", 64, 12),
+            ("exposure", 76, 4)
+        ]
+    );
+    let particle = reflection
+        .struct_layout("scene_Particle")
+        .expect("struct layout");
+    assert_eq!((particle.size, particle.alignment), (32, 16));
+    Ok(())
+}
+
+/// The host code generated for `reflection-inputs/scene.mew`, compiled to check that it is
+/// valid Rust and that bytemuck accepts its layouts.
+#[cfg(test)]
+#[allow(dead_code)]
+mod generated_host_code {
+    include!("../expected-host-outputs/scene.rs");
+}
+
+#[test]
+fn rust_host_code_mew_samples() -> Result<(), Box<MewError>> {
+    let source =
+        std::fs::read_to_string("reflection-inputs/scene.mew").expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "scene",
+        source: mew_api::Source::Text(&source),
+    })?;
+    let result = api.rust_host_code_many(&[
+        Path::Text("scene::vertex".to_string()),
+        Path::Text("scene::fragment".to_string()),
+        Path::Text("scene::simulate".to_string()),
+    ])?;
+
+    let expected_output_location: PathBuf = std::env::current_dir()
+        .unwrap()
+        .join("expected-host-outputs")
+        .join("scene.rs");
+
+    #[cfg(feature = "update_expected_output")]
+    {
+        let _ = std::fs::write(expected_output_location.clone(), &result).expect("Written");
+    }
+
+    assert_eq!(
+        result,
+        std::fs::read_to_string(expected_output_location).expect("READ")
+    );
+
+    use generated_host_code::scene;
+    assert_eq!(
+        (
+            scene::camera::UNIFORMS_GROUP,
+            scene::camera::UNIFORMS_BINDING
+        ),
+        (0, 0)
+    );
+    assert_eq!((scene::PARTICLES_GROUP, scene::PARTICLES_BINDING), (1, 0));
+    let uniforms = scene::camera::Uniforms {
+        exposure: 2.0,
+        ..bytemuck::Zeroable::zeroed()
+    };
+    let bytes = bytemuck::bytes_of(&uniforms);
+    assert_eq!(bytes.len(), 80);
+    assert_eq!(&bytes[76..80], &2.0f32.to_ne_bytes());
+    Ok(())
+}
+
+#[test]
+fn rust_host_code_rejects_f16() -> Result<(), Box<MewError>> {
+    use mew_api::naga::{Scalar, Span, StructMember, Type, TypeInner};
+
+    let source =
+        std::fs::read_to_string("reflection-inputs/scene.mew").expect("failed to read file");
+    let mut api = mew_api::MewApi::default();
+    api.add_module(ModuleDescriptor {
+        module_name: "scene",
+        source: mew_api::Source::Text(&source),
+    })?;
+    let mut naga = api.compile_to_naga(&Path::Text("scene::simulate".to_string()))?;
+
+    // naga's WGSL front end does not parse `f16` yet, so add the struct to the IR directly
+    let half = naga.module.types.insert(
+        Type {
+            name: None,
+            inner: TypeInner::Scalar(Scalar {
+                kind: mew_api::naga::ScalarKind::Float,
+                width: 2,
+            }),
+        },
+        Span::UNDEFINED,
+    );
+    naga.module.types.insert(
+        Type {
+            name: Some("Half".to_string()),
+            inner: TypeInner::Struct {
+                members: vec![StructMember {
+                    name: Some("value".to_string()),
+                    ty: half,
+                    binding: None,
+                    offset: 0,
+                }],
+                span: 4,
+            },
+        },
+        Span::UNDEFINED,
+    );
+
+    let err = mew_api::host::rust_host_code(&naga).expect_err("expected f16 to be rejected");
+    assert_eq!(err.diagnostic.code.as_deref(), Some("E0303"));
+    assert_eq!(
+        err.error.to_string(),
+        "failed to generate Rust host code: `Half::value` is or contains an `f16`, which has no Rust type"
+    );
+    Ok(())
+}
+
+/// Decodes the `mappings` field of a v3 source map into the absolute fields of each segment.
+fn decode_mappings(mappings: &str) -> Vec<Vec<Vec<i64>>> {
+    const BASE64: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut state = [0i64; 5];
+    let mut lines = vec![];
+    for line in mappings.split(';') {
+        state[0] = 0;
+        let mut segments = vec![];
+        for segment in line.split(',').filter(|segment| !segment.is_empty()) {
+            let (mut fields, mut value, mut shift) = (vec![], 0i64, 0);
+            for c in segment.chars() {
+                let digit = BASE64.find(c).expect("invalid base64") as i64;
+                value |= (digit & 0b11111) << shift;
+                shift += 5;
+                if digit & 0b100000 == 0 {
+                    let delta = if value & 1 == 1 {
+                        -(value >> 1)
+                    } else {
+                        value >> 1
+                    };
+                    state[fields.len()] += delta;
+                    fields.push(state[fields.len()]);
+                    (value, shift) = (0, 0);
+                }
+            }
+            segments.push(fields);
+        }
+        lines.push(segments);
+    }
+    lines
+}
+
+#[test]
+fn output_source_map_mew_samples() -> Result<(), Box<MewError>> {
+    let mut api = mew_api::MewApi::default();
+    for module_name in ["lighting", "main"] {
+        let source = std::fs::read_to_string(format!("output-source-map-inputs/{module_name}.mew"))
+            .expect("failed to read file");
+        api.add_module(ModuleDescriptor {
+            module_name,
+            source: mew_api::Source::Text(&source),
+        })?;
+    }
+    let (output, source_map) =
+        api.compile_with_source_map(&Path::Text("main::fragment".to_string()))?;
+    eprintln!("{output}");
+
+    // finds the mapping of the first occurrence of `text` in the output
+    let lookup = |text: &str| {
+        let offset = output.find(text).expect("missing text");
+        let line = output[..offset].matches('\n').count();
+        let column = offset - output[..offset].rfind('\n').map(|x| x + 1).unwrap_or(0);
+        let mapping = source_map
+            .lookup(line as u32, column as u32)
+            .expect("missing mapping");
+        assert_eq!(mapping.generated_column, column as u32);
+        let name = mapping
+            .name
+            .map(|name| source_map.names[name as usize].as_str());
+        (
+            source_map.sources[mapping.source as usize].as_str(),
+            mapping.line,
+            mapping.column,
+            name,
+        )
+    };
+    assert_eq!(
+        lookup("lighting_lambert(normal:"),
+        ("lighting", 2, 3, Some("lambert"))
+    );
+    assert_eq!(lookup("max("), ("lighting", 3, 11, None));
+    assert_eq!(
+        lookup("lighting_ambient)"),
+        ("lighting", 3, 35, Some("ambient"))
+    );
+    assert_eq!(lookup("fragment("), ("main", 6, 3, Some("fragment")));
+    assert_eq!(lookup("normalize"), ("main", 7, 26, None));
+    assert_eq!(
+        lookup("lighting_lambert(normalize"),
+        ("main", 7, 18, Some("lambert"))
+    );
+    assert_eq!(
+        lookup("main_light_0dir)"),
+        ("main", 7, 45, Some("light_dir"))
+    );
+
+    // the sources are embedded, so the map can be used without the MEW files
+    let content = &source_map.sources_content[source_map
+        .sources
+        .iter()
+        .position(|source| source == "main")
+        .unwrap()];
+    assert!(content.contains("the light shines from above"));
+
+    let decoded = decode_mappings(&source_map.mappings());
+    let expected = source_map
+        .lines
+        .iter()
+        .map(|mappings| {
+            mappings
+                .iter()
+                .map(|mapping| {
+                    let mut fields = vec![
+                        mapping.generated_column as i64,
+                        mapping.source as i64,
+                        mapping.line as i64,
+                        mapping.column as i64,
+                    ];
+                    fields.extend(mapping.name.map(|name| name as i64));
+                    fields
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(decoded, expected);
+
+    let json = source_map.to_json(Some("main.wgsl"));
+    assert!(json.starts_with("{\"version\":3,\"file\":\"main.wgsl\",\"sources\":["));
+    assert!(json.contains("\"names\":[") && json.contains("\"mappings\":\""));
+    assert!(json.contains("// the light shines from above\\n"));
+    Ok(())
+}
@@ -0,0 +1,366 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use mew_types::builtins::{get_builtin_functions, get_builtin_tokens};
+use wgsl_spec::{FunctionOverload, ParameterizationKind};
+
+use crate::types::Type;
+
+/// A parameter or result type of an overload of a builtin function, as written in the spec.
+#[derive(Debug, Clone)]
+enum Pattern {
+    /// A type variable of the overload, like `T`.
+    Var(String),
+    /// A type without variables, like `f32`.
+    Type(Type),
+    Vector(Size, Box<Pattern>),
+    Matrix(Size, Size, Box<Pattern>),
+    /// A runtime-sized array.
+    Array(Box<Pattern>),
+    Atomic(Box<Pattern>),
+    /// A pointer in any address space.
+    Pointer(Box<Pattern>),
+    /// A texture, a sampler or an enumerant.
+    Named(String, Vec<Pattern>),
+    /// A type the checker does not model, like the result structs of `frexp` and `modf`.
+    Unknown,
+}
+
+/// The number of components of a vector, or of columns or rows of a matrix.
+#[derive(Debug, Clone)]
+enum Size {
+    Fixed(u8),
+    /// A size variable of the overload, like the `N` of `vecN`.
+    Var(String),
+}
+
+/// The types and sizes bound to the variables of an overload.
+#[derive(Debug, Default, Clone)]
+struct Bindings {
+    types: HashMap<String, Type>,
+    sizes: HashMap<String, u8>,
+}
+
+#[derive(Debug)]
+struct Overload {
+    parameters: Vec<Pattern>,
+    result: Option<Pattern>,
+    /// The patterns the types bound to the type variables must match. Variables the spec only
+    /// describes in prose are not constrained.
+    constraints: Vec<(String, Vec<Pattern>)>,
+}
+
+/// The result of type checking a call to a builtin function.
+pub(crate) enum BuiltinCall {
+    /// The checker does not know the overloads of the function.
+    Unknown,
+    /// No overload accepts the types of the arguments.
+    NoMatchingOverload,
+    /// The result type of the call, unknown if the function returns nothing.
+    Result(Type),
+}
+
+static OVERLOADS: OnceLock<HashMap<String, Vec<Overload>>> = OnceLock::new();
+
+/// The overloads of the builtin functions, except those with an overload the checker cannot
+/// read, like the constructors and `bitcast`.
+fn overloads() -> &'static HashMap<String, Vec<Overload>> {
+    OVERLOADS.get_or_init(|| {
+        get_builtin_functions()
+            .functions
+            .iter()
+            .filter_map(|(name, function)| {
+                let overloads = function
+                    .overloads
+                    .iter()
+                    .map(parse_overload)
+                    .collect::<Option<Vec<_>>>()?;
+                Some((name.clone(), overloads))
+            })
+            .collect()
+    })
+}
+
+/// Resolves the overload of the builtin function called with arguments of the given types.
+pub(crate) fn call(name: &str, arguments: &[Type]) -> BuiltinCall {
+    let Some(overloads) = overloads().get(name) else {
+        return BuiltinCall::Unknown;
+    };
+    let mut results = overloads
+        .iter()
+        .filter_map(|overload| overload.call(arguments));
+    let Some(result) = results.next() else {
+        return BuiltinCall::NoMatchingOverload;
+    };
+    // unknown arguments can match overloads with different results
+    if results.all(|other| other == result) {
+        BuiltinCall::Result(result)
+    } else {
+        BuiltinCall::Result(Type::Unknown)
+    }
+}
+
+/// The type named by a predeclared alias, like `vec3f`.
+pub(crate) fn predeclared_alias(name: &str) -> Option<Type> {
+    let typ = get_builtin_tokens().type_aliases.get(name)?;
+    Some(parse_pattern(typ)?.instantiate(&Bindings::default()))
+}
+
+impl Overload {
+    /// The result type of the overload, if it accepts the arguments.
+    fn call(&self, arguments: &[Type]) -> Option<Type> {
+        if arguments.len() != self.parameters.len() {
+            return None;
+        }
+        let mut bindings = Bindings::default();
+        for (parameter, argument) in self.parameters.iter().zip(arguments) {
+            if !parameter.bind(argument, &mut bindings) {
+                return None;
+            }
+        }
+        let bindings = self.constrain(bindings, &[])?;
+        Some(
+            self.result
+                .as_ref()
+                .map(|result| result.instantiate(&bindings))
+                .unwrap_or(Type::Unknown),
+        )
+    }
+
+    /// Checks the bound variables against their constraints. Matching a constraint can bind
+    /// more variables, like the `S` of `T: S | vecN<S>`, so each choice is followed through.
+    fn constrain(&self, bindings: Bindings, checked: &[&str]) -> Option<Bindings> {
+        let Some((var, alternatives)) = self
+            .constraints
+            .iter()
+            .find(|(var, _)| !checked.contains(&var.as_str()) && bindings.types.contains_key(var))
+        else {
+            return Some(bindings);
+        };
+        let typ = &bindings.types[var];
+        let checked = [checked, &[var.as_str()]].concat();
+        alternatives.iter().find_map(|alternative| {
+            let mut trial = bindings.clone();
+            if alternative.bind(typ, &mut trial) {
+                self.constrain(trial, &checked)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl Pattern {
+    /// Whether the pattern matches the type, binding its variables.
+    fn bind(&self, typ: &Type, bindings: &mut Bindings) -> bool {
+        match (self, typ) {
+            (_, Type::Unknown) | (Pattern::Unknown, _) => true,
+            (Pattern::Var(var), typ) => match bindings.types.get(var) {
+                Some(bound) if typ.converts_to(bound) => true,
+                Some(bound) if !bound.converts_to(typ) => false,
+                _ => {
+                    bindings.types.insert(var.clone(), typ.clone());
+                    true
+                }
+            },
+            (Pattern::Type(expected), typ) => typ.converts_to(expected),
+            (Pattern::Vector(size, component), Type::Vector(actual_size, actual)) => {
+                size.bind(*actual_size, bindings) && component.bind(actual, bindings)
+            }
+            (
+                Pattern::Matrix(columns, rows, component),
+                Type::Matrix(actual_columns, actual_rows, actual),
+            ) => {
+                columns.bind(*actual_columns, bindings)
+                    && rows.bind(*actual_rows, bindings)
+                    && component.bind(actual, bindings)
+            }
+            (Pattern::Array(element), Type::Array(actual, _))
+            | (Pattern::Atomic(element), Type::Atomic(actual))
+            | (Pattern::Pointer(element), Type::Pointer(_, actual)) => {
+                element.bind(actual, bindings)
+            }
+            (Pattern::Named(name, args), Type::Named(actual_name, actual_args)) => {
+                name == actual_name
+                    && args.len() == actual_args.len()
+                    && args
+                        .iter()
+                        .zip(actual_args)
+                        .all(|(arg, actual)| arg.bind(actual, bindings))
+            }
+            _ => false,
+        }
+    }
+
+    /// The type of the pattern with its variables replaced by their bindings.
+    fn instantiate(&self, bindings: &Bindings) -> Type {
+        match self {
+            Pattern::Var(var) => bindings.types.get(var).cloned().unwrap_or(Type::Unknown),
+            Pattern::Type(typ) => typ.clone(),
+            Pattern::Vector(size, component) => size
+                .get(bindings)
+                .map(|size| Type::Vector(size, Box::new(component.instantiate(bindings))))
+                .unwrap_or(Type::Unknown),
+            Pattern::Matrix(columns, rows, component) => columns
+                .get(bindings)
+                .zip(rows.get(bindings))
+                .map(|(columns, rows)| {
+                    Type::Matrix(columns, rows, Box::new(component.instantiate(bindings)))
+                })
+                .unwrap_or(Type::Unknown),
+            Pattern::Array(element) => Type::Array(Box::new(element.instantiate(bindings)), None),
+            Pattern::Atomic(typ) => Type::Atomic(Box::new(typ.instantiate(bindings))),
+            Pattern::Named(name, args) => Type::Named(
+                name.clone(),
+                args.iter().map(|arg| arg.instantiate(bindings)).collect(),
+            ),
+            Pattern::Pointer(_) | Pattern::Unknown => Type::Unknown,
+        }
+    }
+}
+
+impl Size {
+    fn bind(&self, size: u8, bindings: &mut Bindings) -> bool {
+        match self {
+            Size::Fixed(expected) => *expected == size,
+            Size::Var(var) => *bindings.sizes.entry(var.clone()).or_insert(size) == size,
+        }
+    }
+
+    fn get(&self, bindings: &Bindings) -> Option<u8> {
+        match self {
+            Size::Fixed(size) => Some(*size),
+            Size::Var(var) => bindings.sizes.get(var).copied(),
+        }
+    }
+}
+
+/// Reads an overload from its signature, like `fn max ( e1: T, e2: T ) -> T`. Some signatures
+/// in the spec data are several overloads run together, or have a template list: these are
+/// not read.
+fn parse_overload(overload: &FunctionOverload) -> Option<Overload> {
+    let signature = overload.signature.as_str();
+    if signature.matches("fn ").count() != 1 {
+        return None;
+    }
+    let (_, signature) = signature.split_once("fn ")?;
+    let (name, signature) = signature.split_once('(')?;
+    if name.contains('<') {
+        return None;
+    }
+    let (parameters, result) = signature.rsplit_once(')')?;
+    let parameters = split_arguments(parameters)
+        .into_iter()
+        .filter(|parameter| !parameter.trim().is_empty())
+        .map(|parameter| parse_pattern(parameter.split_once(':')?.1))
+        .collect::<Option<Vec<_>>>()?;
+    let result = match result.trim().strip_prefix("->") {
+        Some(result) => Some(parse_pattern(result)?),
+        None => None,
+    };
+    let constraints = overload
+        .parameterization
+        .typevars
+        .iter()
+        .filter_map(|(var, kind)| match kind {
+            ParameterizationKind::Types(types) => {
+                let patterns = types
+                    .iter()
+                    .map(|typ| parse_pattern(typ))
+                    .collect::<Option<Vec<_>>>()?;
+                Some((var.clone(), patterns))
+            }
+            ParameterizationKind::Description(_) => None,
+        })
+        .collect();
+    Some(Overload {
+        parameters,
+        result,
+        constraints,
+    })
+}
+
+/// Splits template or function arguments at the commas that are not nested in a template.
+fn split_arguments(text: &str) -> Vec<&str> {
+    let mut depth = 0;
+    let mut start = 0;
+    let mut arguments = Vec::new();
+    for (index, char) in text.char_indices() {
+        match char {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push(&text[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    arguments.push(&text[start..]);
+    arguments
+}
+
+fn parse_pattern(text: &str) -> Option<Pattern> {
+    let text = text.trim();
+    let (name, args) = match text.split_once('<') {
+        Some((name, args)) => {
+            let args = split_arguments(args.strip_suffix('>')?)
+                .into_iter()
+                .map(parse_pattern)
+                .collect::<Option<Vec<_>>>()?;
+            (name.trim(), args)
+        }
+        None => (text, Vec::new()),
+    };
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+    let pattern = match (name, args.as_slice()) {
+        ("bool", []) => Pattern::Type(Type::Bool),
+        ("AbstractInt", []) => Pattern::Type(Type::AbstractInt),
+        ("AbstractFloat", []) => Pattern::Type(Type::AbstractFloat),
+        ("i32", []) => Pattern::Type(Type::I32),
+        ("u32", []) => Pattern::Type(Type::U32),
+        ("f32", []) => Pattern::Type(Type::F32),
+        ("f16", []) => Pattern::Type(Type::F16),
+        ("array", [element]) => Pattern::Array(Box::new(element.clone())),
+        ("atomic", [typ]) => Pattern::Atomic(Box::new(typ.clone())),
+        ("ptr", [_, typ] | [_, typ, _]) => Pattern::Pointer(Box::new(typ.clone())),
+        (
+            "read" | "write" | "read_write" | "function" | "private" | "workgroup" | "uniform"
+            | "storage",
+            [],
+        ) => Pattern::Named(name.to_string(), Vec::new()),
+        (name, [component]) if name.len() == 4 && name.starts_with("vec") => {
+            Pattern::Vector(parse_size(&name[3..])?, Box::new(component.clone()))
+        }
+        (name, [component]) if name.len() == 6 && name.starts_with("mat") && &name[4..5] == "x" => {
+            Pattern::Matrix(
+                parse_size(&name[3..4])?,
+                parse_size(&name[5..])?,
+                Box::new(component.clone()),
+            )
+        }
+        (name, args) if name.starts_with("texture_") || name.starts_with("sampler") => {
+            Pattern::Named(name.to_string(), args.to_vec())
+        }
+        (name, _) if name.starts_with("__") => Pattern::Unknown,
+        (name, [])
+            if name
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) =>
+        {
+            Pattern::Var(name.to_string())
+        }
+        _ => return None,
+    };
+    Some(pattern)
+}
+
+fn parse_size(text: &str) -> Option<Size> {
+    match text.parse() {
+        Ok(size @ 2..=4) => Some(Size::Fixed(size)),
+        Ok(_) => None,
+        Err(_) if text.chars().all(|c| c.is_ascii_uppercase()) => Some(Size::Var(text.into())),
+        Err(_) => None,
+    }
+}
@@ -0,0 +1,5 @@
+mod builtins;
+pub mod typecheck;
+mod types;
+
+pub use typecheck::*;
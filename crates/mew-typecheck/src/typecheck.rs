@@ -0,0 +1,993 @@
+use std::collections::HashMap;
+
+use mew_parse::{
+    span::{Span, Spanned},
+    syntax::{
+        Alias, AssignmentOperator, AssignmentStatement, BinaryOperator, CaseSelector,
+        CompoundStatement, Declaration, DeclarationKind, Expression, Function,
+        FunctionCallExpression, GlobalDeclaration, IndexingExpression, LiteralExpression,
+        NamedComponentExpression, PathPart, Statement, Struct, TranslationUnit, TypeExpression,
+        UnaryExpression, UnaryOperator,
+    },
+};
+use mew_types::{CompilerPass, CompilerPassError, CompilerPassResult, Diagnostics, TypeError};
+
+use crate::{
+    builtins::{self, BuiltinCall},
+    types::Type,
+};
+
+/// Infers the types of expressions and checks them against the rules of WGSL, including the
+/// conversion of abstract numeric values.
+///
+/// Runs on the mangled and flattened translation unit rather than the resolved one, because
+/// only there are templates specialized and aliases followed, so that every type is concrete.
+/// Declarations have their names in the output there, so errors name them by their MEW paths
+/// from [`TypeChecker::names`] and are reported at the MEW spans of the expressions. Expressions whose type
+/// cannot be inferred, like calls to functions the checker knows nothing of, are not checked.
+#[derive(Debug, Default, Clone)]
+pub struct TypeChecker {
+    /// The MEW paths of the declarations by their names in the output, to name them in errors.
+    pub names: HashMap<String, String>,
+}
+
+/// Aliases can name aliases, but not endlessly.
+const MAX_ALIAS_DEPTH: usize = 32;
+
+impl TypeChecker {
+    /// Reports the type errors of the translation unit to `diagnostics`.
+    pub fn check(&self, translation_unit: &TranslationUnit, diagnostics: &mut Diagnostics) {
+        let mut checker = Checker::new(translation_unit, &self.names);
+        for declaration in translation_unit.global_declarations.iter() {
+            checker.global_declaration(declaration);
+        }
+        for error in checker.errors {
+            diagnostics.error(error);
+        }
+    }
+}
+
+impl CompilerPass for TypeChecker {
+    fn apply_mut(&mut self, translation_unit: &mut TranslationUnit) -> CompilerPassResult {
+        let mut diagnostics = Diagnostics::default();
+        self.check(translation_unit, &mut diagnostics);
+        diagnostics.first_error()
+    }
+
+    fn apply_mut_with_diagnostics(
+        &mut self,
+        translation_unit: &mut TranslationUnit,
+        diagnostics: &mut Diagnostics,
+    ) -> CompilerPassResult {
+        self.check(translation_unit, diagnostics);
+        Ok(())
+    }
+}
+
+struct Checker<'a> {
+    names: &'a HashMap<String, String>,
+    structs: HashMap<&'a str, &'a Struct>,
+    aliases: HashMap<&'a str, &'a Alias>,
+    functions: HashMap<&'a str, &'a Function>,
+    globals: HashMap<&'a str, &'a Declaration>,
+    /// The types of the global declarations inferred so far.
+    global_types: HashMap<&'a str, Type>,
+    /// The types of the parameters and local declarations in scope, innermost last.
+    scopes: Vec<HashMap<String, Type>>,
+    /// The return type of the function being checked, with its span.
+    return_type: Option<(Type, Span)>,
+    errors: Vec<CompilerPassError>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(translation_unit: &'a TranslationUnit, names: &'a HashMap<String, String>) -> Self {
+        let mut checker = Checker {
+            names,
+            structs: HashMap::new(),
+            aliases: HashMap::new(),
+            functions: HashMap::new(),
+            globals: HashMap::new(),
+            global_types: HashMap::new(),
+            scopes: Vec::new(),
+            return_type: None,
+            errors: Vec::new(),
+        };
+        for declaration in translation_unit.global_declarations.iter() {
+            match &declaration.value {
+                GlobalDeclaration::Declaration(decl) => {
+                    checker.globals.insert(decl.name.as_str(), decl);
+                }
+                GlobalDeclaration::Alias(alias) => {
+                    checker.aliases.insert(alias.name.as_str(), alias);
+                }
+                GlobalDeclaration::Struct(strct) => {
+                    checker.structs.insert(strct.name.as_str(), strct);
+                }
+                GlobalDeclaration::Function(function) => {
+                    checker.functions.insert(function.name.as_str(), function);
+                }
+                _ => {}
+            }
+        }
+        checker
+    }
+
+    fn error(&mut self, error: TypeError, span: Span) {
+        self.errors.push(CompilerPassError::TypeError(error, span));
+    }
+
+    fn display(&self, typ: &Type) -> String {
+        typ.display(self.names).to_string()
+    }
+
+    fn name(&self, name: &str) -> String {
+        self.names
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// Reports a mismatch if `found` does not convert to `expected`, because of `cause`.
+    fn expect(&mut self, expected: &Type, found: &Type, span: Span, cause: Option<Span>) {
+        if !found.converts_to(expected) {
+            let error = TypeError::Mismatched(self.display(expected), self.display(found), cause);
+            self.error(error, span);
+        }
+    }
+
+    fn global_declaration(&mut self, declaration: &'a Spanned<GlobalDeclaration>) {
+        match &declaration.value {
+            GlobalDeclaration::Declaration(decl) => {
+                let typ = self.declaration(decl, false);
+                self.global_types.insert(decl.name.as_str(), typ);
+            }
+            GlobalDeclaration::Function(function) => self.function(function),
+            GlobalDeclaration::ConstAssert(assertion) => self.condition(&assertion.expression),
+            _ => {}
+        }
+    }
+
+    /// The type of the global declaration with the name, inferred without reporting errors
+    /// since they are reported where the declaration is checked.
+    fn global_type(&mut self, name: &str) -> Option<Type> {
+        if let Some(typ) = self.global_types.get(name) {
+            return Some(typ.clone());
+        }
+        let declaration = *self.globals.get(name)?;
+        // a declaration that refers to itself is not checked
+        self.global_types
+            .insert(declaration.name.as_str(), Type::Unknown);
+        let errors = std::mem::take(&mut self.errors);
+        let scopes = std::mem::take(&mut self.scopes);
+        let typ = self.declaration(declaration, false);
+        self.errors = errors;
+        self.scopes = scopes;
+        self.global_types
+            .insert(declaration.name.as_str(), typ.clone());
+        Some(typ)
+    }
+
+    /// Checks the initializer of the declaration against its type, and infers the type of the
+    /// identifier it declares: variables are references.
+    fn declaration(&mut self, declaration: &Declaration, local: bool) -> Type {
+        let initializer = declaration
+            .initializer
+            .as_ref()
+            .map(|initializer| (self.value(initializer), initializer.span()));
+        let typ = match (&declaration.typ, initializer) {
+            (Some(typ), initializer) => {
+                let expected = self.resolve_type(typ);
+                if let Some((found, span)) = initializer {
+                    self.expect(&expected, &found, span, Some(typ.span()));
+                }
+                expected
+            }
+            (None, Some((found, _))) if *declaration.kind == DeclarationKind::Const => found,
+            (None, Some((found, _))) => found.concretize(),
+            (None, None) => Type::Unknown,
+        };
+        if *declaration.kind != DeclarationKind::Var {
+            return typ;
+        }
+        let address_space = declaration
+            .template_args
+            .iter()
+            .flatten()
+            .next()
+            .and_then(|arg| identifier_name(&arg.expression));
+        match (local, address_space) {
+            (true, _) => Type::Reference("function".to_string(), Box::new(typ)),
+            (false, Some(space)) => Type::Reference(space.to_string(), Box::new(typ)),
+            // textures and samplers are not references
+            (false, None) => typ,
+        }
+    }
+
+    fn function(&mut self, function: &Function) {
+        let parameters = function
+            .parameters
+            .iter()
+            .map(|parameter| {
+                (
+                    parameter.name.to_string(),
+                    self.resolve_type(&parameter.typ),
+                )
+            })
+            .collect();
+        self.scopes = vec![parameters];
+        self.return_type = function
+            .return_type
+            .as_ref()
+            .map(|typ| (self.resolve_type(typ), typ.span()));
+        self.compound(&function.body);
+        self.scopes.clear();
+        self.return_type = None;
+    }
+
+    fn resolve_type(&self, typ: &TypeExpression) -> Type {
+        match typ.path.as_slice() {
+            [part] => self.path_type(part, 0).unwrap_or(Type::Unknown),
+            _ => Type::Unknown,
+        }
+    }
+
+    /// The type named by the path, or `None` if it does not name a type.
+    fn path_type(&self, part: &PathPart, depth: usize) -> Option<Type> {
+        let args = part
+            .template_args
+            .iter()
+            .flatten()
+            .map(|arg| &arg.expression.value)
+            .collect::<Vec<_>>();
+        let arg_type = |index: usize| {
+            args.get(index)
+                .and_then(|arg| self.expression_type(arg, depth))
+                .unwrap_or(Type::Unknown)
+        };
+        let name = part.name.as_str();
+        if let Some(strct) = self.structs.get(name) {
+            return Some(Type::Struct(strct.name.to_string()));
+        }
+        if let Some(alias) = self.aliases.get(name) {
+            return match alias.typ.path.as_slice() {
+                [part] if depth < MAX_ALIAS_DEPTH => self.path_type(part, depth + 1),
+                _ => Some(Type::Unknown),
+            };
+        }
+        let typ = match (name, args.len()) {
+            ("bool", 0) => Type::Bool,
+            ("i32", 0) => Type::I32,
+            ("u32", 0) => Type::U32,
+            ("f32", 0) => Type::F32,
+            ("f16", 0) => Type::F16,
+            ("vec2" | "vec3" | "vec4", _) => {
+                Type::Vector(name[3..].parse().ok()?, Box::new(arg_type(0)))
+            }
+            ("array", _) => Type::Array(
+                Box::new(arg_type(0)),
+                args.get(1).and_then(|size| array_size(size)),
+            ),
+            ("atomic", 1) => Type::Atomic(Box::new(arg_type(0))),
+            ("ptr", 2 | 3) => Type::Pointer(
+                identifier_name(args[0]).unwrap_or_default().to_string(),
+                Box::new(arg_type(1)),
+            ),
+            (name, _) if name.starts_with("texture_") || name.starts_with("sampler") => {
+                Type::Named(
+                    name.to_string(),
+                    args.iter()
+                        .map(|arg| {
+                            self.expression_type(arg, depth)
+                                .or_else(|| {
+                                    // texel formats and access modes
+                                    let name = identifier_name(arg)?;
+                                    Some(Type::Named(name.to_string(), Vec::new()))
+                                })
+                                .unwrap_or(Type::Unknown)
+                        })
+                        .collect(),
+                )
+            }
+            (name, _) => match matrix_size(name) {
+                Some((columns, rows)) => Type::Matrix(columns, rows, Box::new(arg_type(0))),
+                None if args.is_empty() => builtins::predeclared_alias(name)?,
+                None => return None,
+            },
+        };
+        Some(typ)
+    }
+
+    /// The type named by a template argument, or `None` if it does not name a type.
+    fn expression_type(&self, expression: &Expression, depth: usize) -> Option<Type> {
+        match expression {
+            Expression::Identifier(identifier) => match identifier.path.as_slice() {
+                [part] => self.path_type(part, depth),
+                _ => Some(Type::Unknown),
+            },
+            Expression::Type(typ) => match typ.path.as_slice() {
+                [part] => self.path_type(part, depth),
+                _ => Some(Type::Unknown),
+            },
+            _ => None,
+        }
+    }
+
+    fn compound(&mut self, compound: &CompoundStatement) {
+        self.scopes.push(HashMap::new());
+        self.statements(&compound.statements);
+        self.scopes.pop();
+    }
+
+    /// Checks statements in the current scope.
+    fn statements(&mut self, statements: &[Spanned<Statement>]) {
+        for statement in statements {
+            self.statement(statement);
+        }
+    }
+
+    fn statement(&mut self, statement: &Spanned<Statement>) {
+        let span = statement.span();
+        match &statement.value {
+            Statement::Void
+            | Statement::Error(_)
+            | Statement::Break
+            | Statement::Continue
+            | Statement::Discard => {}
+            Statement::Compound(compound) => self.compound(compound),
+            Statement::Assignment(assignment) => self.assignment(assignment, span),
+            Statement::Increment(expression) | Statement::Decrement(expression) => {
+                match self.expression_at(expression, span.clone()) {
+                    Type::Reference(_, typ) if !typ.is_integer() => {
+                        let operator = match &statement.value {
+                            Statement::Increment(_) => "++",
+                            _ => "--",
+                        };
+                        let error = TypeError::InvalidOperand(operator.into(), self.display(&typ));
+                        self.error(error, span);
+                    }
+                    Type::Reference(..) | Type::Unknown => {}
+                    _ => self.error(TypeError::NotAssignable, span),
+                }
+            }
+            Statement::If(statement) => {
+                self.condition(&statement.if_clause.0);
+                self.compound(&statement.if_clause.1);
+                for (condition, body) in statement.else_if_clauses.iter() {
+                    self.condition(condition);
+                    self.compound(body);
+                }
+                if let Some(body) = &statement.else_clause {
+                    self.compound(body);
+                }
+            }
+            Statement::Switch(statement) => {
+                let selector = self.value(&statement.expression);
+                for clause in statement.clauses.iter() {
+                    for case in clause.case_selectors.iter() {
+                        if let CaseSelector::Expression(expression) = &case.value {
+                            let typ = self.value_at(expression, case.span());
+                            if selector.common(&typ).is_none() {
+                                self.expect(&selector, &typ, case.span(), None);
+                            }
+                        }
+                    }
+                    self.compound(&clause.body);
+                }
+            }
+            Statement::Loop(statement) => {
+                // the continuing statement is in the scope of the body
+                self.scopes.push(HashMap::new());
+                self.statements(&statement.body.statements);
+                if let Some(continuing) = &statement.continuing {
+                    self.scopes.push(HashMap::new());
+                    self.statements(&continuing.body.statements);
+                    if let Some(break_if) = &continuing.break_if {
+                        let typ = self.value_at(break_if, break_if.span());
+                        self.expect(&Type::Bool, &typ, break_if.span(), None);
+                    }
+                    self.scopes.pop();
+                }
+                self.scopes.pop();
+            }
+            Statement::For(statement) => {
+                self.scopes.push(HashMap::new());
+                if let Some(initializer) = &statement.initializer {
+                    self.statement(initializer);
+                }
+                if let Some(condition) = &statement.condition {
+                    self.condition(condition);
+                }
+                if let Some(update) = &statement.update {
+                    self.statement(update);
+                }
+                self.compound(&statement.body);
+                self.scopes.pop();
+            }
+            Statement::While(statement) => {
+                self.condition(&statement.condition);
+                self.compound(&statement.body);
+            }
+            Statement::Return(value) => self.return_statement(value.as_ref(), span),
+            Statement::FunctionCall(call) => {
+                self.call(call, span);
+            }
+            Statement::ConstAssert(assertion) => self.condition(&assertion.expression),
+            Statement::Declaration(statement) => {
+                let typ = self.declaration(&statement.declaration, true);
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.insert(statement.declaration.name.to_string(), typ);
+                }
+                self.statements(&statement.statements);
+            }
+        }
+    }
+
+    fn condition(&mut self, condition: &Spanned<Expression>) {
+        let typ = self.value(condition);
+        self.expect(&Type::Bool, &typ, condition.span(), None);
+    }
+
+    fn assignment(&mut self, assignment: &AssignmentStatement, span: Span) {
+        let rhs = self.value(&assignment.rhs);
+        if is_phony(&assignment.lhs) {
+            return;
+        }
+        let lhs = match self.expression(&assignment.lhs) {
+            Type::Reference(_, typ) => *typ,
+            Type::Unknown => Type::Unknown,
+            _ => return self.error(TypeError::NotAssignable, assignment.lhs.span()),
+        };
+        match compound_operator(&assignment.operator) {
+            None => self.expect(&lhs, &rhs, assignment.rhs.span(), None),
+            Some(operator) => {
+                let result = self.binary(&operator, lhs.clone(), rhs, span.clone());
+                self.expect(&lhs, &result, span, None);
+            }
+        }
+    }
+
+    fn return_statement(&mut self, value: Option<&Spanned<Expression>>, span: Span) {
+        let found = value.map(|value| (self.value(value), value.span()));
+        match (self.return_type.clone(), found) {
+            (Some((expected, cause)), Some((found, span))) => {
+                self.expect(&expected, &found, span, Some(cause))
+            }
+            (Some((expected, _)), None) => {
+                let error = TypeError::MissingReturnValue(self.display(&expected));
+                self.error(error, span);
+            }
+            (None, Some((_, span))) => self.error(TypeError::UnexpectedReturnValue, span),
+            (None, None) => {}
+        }
+    }
+
+    fn expression(&mut self, expression: &Spanned<Expression>) -> Type {
+        self.expression_at(expression, expression.span())
+    }
+
+    /// The type of the value of the expression, after the load rule.
+    fn value(&mut self, expression: &Spanned<Expression>) -> Type {
+        self.expression(expression).load()
+    }
+
+    fn value_at(&mut self, expression: &Expression, span: Span) -> Type {
+        self.expression_at(expression, span).load()
+    }
+
+    fn expression_at(&mut self, expression: &Expression, span: Span) -> Type {
+        match expression {
+            Expression::Literal(literal) => literal_type(literal),
+            Expression::Parenthesized(expression) => self.expression(expression),
+            Expression::NamedComponent(expression) => self.component(expression),
+            Expression::Indexing(expression) => self.indexing(expression),
+            Expression::Unary(expression) => self.unary(expression, span),
+            Expression::Binary(expression) => {
+                let left = self.value(&expression.left);
+                let right = self.value(&expression.right);
+                self.binary(&expression.operator, left, right, span)
+            }
+            Expression::FunctionCall(call) => self.call(call, span),
+            Expression::Identifier(identifier) => self.identifier(&identifier.path),
+            Expression::Type(_) => Type::Unknown,
+        }
+    }
+
+    fn identifier(&mut self, path: &[PathPart]) -> Type {
+        let [part] = path else {
+            return Type::Unknown;
+        };
+        let name = part.name.as_str();
+        if let Some(typ) = self.scopes.iter().rev().find_map(|scope| scope.get(name)) {
+            return typ.clone();
+        }
+        self.global_type(name).unwrap_or(Type::Unknown)
+    }
+
+    fn component(&mut self, expression: &NamedComponentExpression) -> Type {
+        let (space, base) = match self.expression(&expression.base) {
+            Type::Reference(space, typ) | Type::Pointer(space, typ) => (Some(space), *typ),
+            typ => (None, typ),
+        };
+        let name = expression.component.as_str();
+        let component = match &base {
+            Type::Struct(strct) => match self.structs.get(strct.as_str()) {
+                Some(strct) => strct
+                    .members
+                    .iter()
+                    .find(|member| *member.name == name)
+                    .map(|member| self.resolve_type(&member.typ)),
+                None => Some(Type::Unknown),
+            },
+            Type::Vector(size, typ) => match swizzle_size(name, *size) {
+                Some(1) => Some(*typ.clone()),
+                // a swizzle of several components is a value
+                Some(size) => return Type::Vector(size, typ.clone()),
+                None => None,
+            },
+            Type::Unknown => Some(Type::Unknown),
+            _ => None,
+        };
+        match (component, space) {
+            (Some(component), Some(space)) => Type::Reference(space, Box::new(component)),
+            (Some(component), None) => component,
+            (None, _) => {
+                let error = TypeError::NoMember(self.display(&base), name.to_string());
+                self.error(error, expression.component.span());
+                Type::Unknown
+            }
+        }
+    }
+
+    fn indexing(&mut self, expression: &IndexingExpression) -> Type {
+        let (space, base) = match self.expression(&expression.base) {
+            Type::Reference(space, typ) | Type::Pointer(space, typ) => (Some(space), *typ),
+            typ => (None, typ),
+        };
+        let index = self.value(&expression.index);
+        if !index.is_integer() {
+            let error = TypeError::InvalidIndex(self.display(&index));
+            self.error(error, expression.index.span());
+        }
+        let element = match base {
+            Type::Vector(_, component) => *component,
+            Type::Matrix(_, rows, component) => Type::Vector(rows, component),
+            Type::Array(element, _) => *element,
+            Type::Unknown => Type::Unknown,
+            base => {
+                let error = TypeError::NotIndexable(self.display(&base));
+                self.error(error, expression.base.span());
+                return Type::Unknown;
+            }
+        };
+        match space {
+            Some(space) => Type::Reference(space, Box::new(element)),
+            None => element,
+        }
+    }
+
+    fn unary(&mut self, expression: &UnaryExpression, span: Span) -> Type {
+        let operand = match *expression.operator {
+            UnaryOperator::AddressOf => self.expression(&expression.operand),
+            _ => self.value(&expression.operand),
+        };
+        let typ = match (&*expression.operator, operand.clone()) {
+            (_, Type::Unknown) => Some(Type::Unknown),
+            (UnaryOperator::AddressOf, Type::Reference(space, typ)) => {
+                Some(Type::Pointer(space, typ))
+            }
+            (UnaryOperator::Indirection, Type::Pointer(space, typ)) => {
+                Some(Type::Reference(space, typ))
+            }
+            (UnaryOperator::AddressOf | UnaryOperator::Indirection, _) => None,
+            (UnaryOperator::LogicalNegation, typ) => typ
+                .shape()
+                .is_some_and(|(_, component)| component.matches(&Type::Bool))
+                .then_some(typ),
+            (UnaryOperator::Negation, typ @ Type::Matrix(..)) => Some(typ),
+            (UnaryOperator::Negation, typ) => typ
+                .shape()
+                .is_some_and(|(_, component)| component.is_numeric() && *component != Type::U32)
+                .then_some(typ),
+            (UnaryOperator::BitwiseComplement, typ) => typ
+                .shape()
+                .is_some_and(|(_, component)| component.is_integer())
+                .then_some(typ),
+        };
+        typ.unwrap_or_else(|| {
+            let error =
+                TypeError::InvalidOperand(expression.operator.to_string(), self.display(&operand));
+            self.error(error, span);
+            Type::Unknown
+        })
+    }
+
+    fn binary(&mut self, operator: &BinaryOperator, left: Type, right: Type, span: Span) -> Type {
+        if left.is_unknown() || right.is_unknown() {
+            return Type::Unknown;
+        }
+        binary_type(operator, &left, &right).unwrap_or_else(|| {
+            let error = TypeError::InvalidOperands(
+                operator.to_string(),
+                self.display(&left),
+                self.display(&right),
+            );
+            self.error(error, span);
+            Type::Unknown
+        })
+    }
+
+    fn call(&mut self, call: &FunctionCallExpression, span: Span) -> Type {
+        let arguments = call
+            .arguments
+            .iter()
+            .map(|argument| self.value(argument))
+            .collect::<Vec<_>>();
+        let [part] = call.path.as_slice() else {
+            return Type::Unknown;
+        };
+        let name = part.name.as_str();
+        if let Some(function) = self.functions.get(name).copied() {
+            return self.function_call(function, call, &arguments, span);
+        }
+        if name == "bitcast" {
+            return part
+                .template_args
+                .iter()
+                .flatten()
+                .next()
+                .and_then(|arg| self.expression_type(&arg.expression, 0))
+                .unwrap_or(Type::Unknown);
+        }
+        if let Some(typ) = self.path_type(part, 0) {
+            let constructed = match &typ {
+                Type::Struct(strct) => self.construct_struct(strct, &arguments),
+                typ => construct(typ, &arguments),
+            };
+            return match constructed {
+                Some(typ) => typ,
+                None => {
+                    let constructor = if self.structs.contains_key(name) {
+                        self.name(name)
+                    } else {
+                        part.to_string()
+                    };
+                    self.no_matching_overload(constructor, &arguments, span)
+                }
+            };
+        }
+        if part.template_args.is_some() {
+            return Type::Unknown;
+        }
+        match builtins::call(name, &arguments) {
+            BuiltinCall::Unknown => Type::Unknown,
+            BuiltinCall::NoMatchingOverload => {
+                self.no_matching_overload(name.to_string(), &arguments, span)
+            }
+            BuiltinCall::Result(typ) => typ,
+        }
+    }
+
+    /// The struct built by a value constructor, or `None` if the constructor does not accept
+    /// the arguments.
+    fn construct_struct(&self, name: &str, arguments: &[Type]) -> Option<Type> {
+        let typ = Type::Struct(name.to_string());
+        let Some(strct) = self.structs.get(name) else {
+            return Some(typ);
+        };
+        if arguments.is_empty() {
+            return Some(typ);
+        }
+        let accepted = strct.members.len() == arguments.len()
+            && strct
+                .members
+                .iter()
+                .zip(arguments)
+                .all(|(member, argument)| argument.converts_to(&self.resolve_type(&member.typ)));
+        accepted.then_some(typ)
+    }
+
+    fn no_matching_overload(&mut self, function: String, arguments: &[Type], span: Span) -> Type {
+        let arguments = arguments.iter().map(|typ| self.display(typ)).collect();
+        self.error(TypeError::NoMatchingOverload(function, arguments), span);
+        Type::Unknown
+    }
+
+    fn function_call(
+        &mut self,
+        function: &Function,
+        call: &FunctionCallExpression,
+        arguments: &[Type],
+        span: Span,
+    ) -> Type {
+        if arguments.len() != function.parameters.len() {
+            let error = TypeError::ArgumentCount(
+                self.name(&function.name),
+                function.parameters.len(),
+                arguments.len(),
+            );
+            self.error(error, span);
+        } else {
+            for ((parameter, argument), expression) in function
+                .parameters
+                .iter()
+                .zip(arguments)
+                .zip(call.arguments.iter())
+            {
+                let expected = self.resolve_type(&parameter.typ);
+                self.expect(
+                    &expected,
+                    argument,
+                    expression.span(),
+                    Some(parameter.typ.span()),
+                );
+            }
+        }
+        function
+            .return_type
+            .as_ref()
+            .map(|typ| self.resolve_type(typ))
+            .unwrap_or(Type::Unknown)
+    }
+}
+
+/// The type of the value built by a value constructor, or `None` if the constructor does not
+/// accept the arguments. The component or element type of a vector, matrix or array without
+/// template arguments is unknown, and inferred from the arguments.
+fn construct(typ: &Type, arguments: &[Type]) -> Option<Type> {
+    if arguments.is_empty() {
+        return Some(typ.clone());
+    }
+    match typ {
+        Type::Bool | Type::I32 | Type::U32 | Type::F32 | Type::F16 => match arguments {
+            [argument] if argument.is_scalar() => Some(typ.clone()),
+            _ => None,
+        },
+        Type::Vector(size, component) => match arguments {
+            // conversion
+            [Type::Vector(argument_size, argument)] if argument_size == size => {
+                let component = if component.is_unknown() {
+                    argument
+                } else {
+                    component
+                };
+                Some(Type::Vector(*size, component.clone()))
+            }
+            _ => {
+                let component = components(component, arguments, *size as usize, true)?;
+                Some(Type::Vector(*size, Box::new(component)))
+            }
+        },
+        Type::Matrix(columns, rows, component) => {
+            let component = match arguments {
+                // conversion
+                [Type::Matrix(argument_columns, argument_rows, argument)]
+                    if argument_columns == columns && argument_rows == rows =>
+                {
+                    if component.is_unknown() {
+                        *argument.clone()
+                    } else {
+                        *component.clone()
+                    }
+                }
+                // column vectors, or scalars
+                _ => components(component, arguments, (columns * rows) as usize, false)?,
+            };
+            component
+                .is_float()
+                .then(|| Type::Matrix(*columns, *rows, Box::new(component)))
+        }
+        Type::Array(element, size) => {
+            if size.is_some_and(|size| size != arguments.len() as u64) {
+                return None;
+            }
+            let inferred = element.is_unknown();
+            let mut element = *element.clone();
+            for argument in arguments {
+                if inferred {
+                    element = element.common(argument)?;
+                } else if !argument.converts_to(&element) {
+                    return None;
+                }
+            }
+            Some(Type::Array(Box::new(element), Some(arguments.len() as u64)))
+        }
+        _ => Some(typ.clone()),
+    }
+}
+
+/// The component type of a vector or matrix built from the arguments, which are scalars or
+/// vectors with `count` components in total. A single scalar is splat if `splat` is set.
+fn components(component: &Type, arguments: &[Type], count: usize, splat: bool) -> Option<Type> {
+    let mut total = 0;
+    // the number of components of unknown arguments is unknown
+    let mut unknown = false;
+    let mut inferred = component.clone();
+    for argument in arguments {
+        let (size, argument_component) = argument.shape()?;
+        unknown |= argument.is_unknown();
+        total += size.unwrap_or(1) as usize;
+        inferred = if component.is_unknown() {
+            inferred.common(argument_component)?
+        } else {
+            argument_component
+                .converts_to(component)
+                .then(|| component.clone())?
+        };
+    }
+    let splat = splat && arguments.len() == 1 && total == 1;
+    (total == count || splat || (unknown && total <= count)).then_some(inferred)
+}
+
+fn binary_type(operator: &BinaryOperator, left: &Type, right: &Type) -> Option<Type> {
+    match operator {
+        BinaryOperator::ShortCircuitOr | BinaryOperator::ShortCircuitAnd => {
+            (*left == Type::Bool && *right == Type::Bool).then_some(Type::Bool)
+        }
+        BinaryOperator::Addition
+        | BinaryOperator::Subtraction
+        | BinaryOperator::Multiplication
+        | BinaryOperator::Division
+        | BinaryOperator::Remainder => {
+            if matches!(left, Type::Matrix(..)) || matches!(right, Type::Matrix(..)) {
+                return matrix_arithmetic(operator, left, right);
+            }
+            let (size, component) = common_shape(left, right, true)?;
+            component
+                .is_numeric()
+                .then(|| Type::with_shape(size, component))
+        }
+        BinaryOperator::Equality | BinaryOperator::Inequality => {
+            let (size, _) = common_shape(left, right, false)?;
+            Some(Type::with_shape(size, Type::Bool))
+        }
+        BinaryOperator::LessThan
+        | BinaryOperator::LessThanEqual
+        | BinaryOperator::GreaterThan
+        | BinaryOperator::GreaterThanEqual => {
+            let (size, component) = common_shape(left, right, false)?;
+            component
+                .is_numeric()
+                .then(|| Type::with_shape(size, Type::Bool))
+        }
+        BinaryOperator::BitwiseOr | BinaryOperator::BitwiseAnd => {
+            let (size, component) = common_shape(left, right, false)?;
+            (component.is_integer() || component == Type::Bool)
+                .then(|| Type::with_shape(size, component))
+        }
+        BinaryOperator::BitwiseXor => {
+            let (size, component) = common_shape(left, right, false)?;
+            component
+                .is_integer()
+                .then(|| Type::with_shape(size, component))
+        }
+        BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight => {
+            let (left_size, left_component) = left.shape()?;
+            let (right_size, right_component) = right.shape()?;
+            (left_size == right_size && left_component.is_integer() && right_component.is_integer())
+                .then(|| left.clone())
+        }
+    }
+}
+
+/// The number of components and the common component type of scalar or vector operands of
+/// the same size. Vectors can be mixed with scalars if `mixed` is set.
+fn common_shape(left: &Type, right: &Type, mixed: bool) -> Option<(Option<u8>, Type)> {
+    let (left_size, left_component) = left.shape()?;
+    let (right_size, right_component) = right.shape()?;
+    let size = match (left_size, right_size) {
+        (Some(left), Some(right)) if left != right => return None,
+        (Some(_), None) | (None, Some(_)) if !mixed => return None,
+        (left, right) => left.or(right),
+    };
+    Some((size, left_component.common(right_component)?))
+}
+
+fn matrix_arithmetic(operator: &BinaryOperator, left: &Type, right: &Type) -> Option<Type> {
+    let float = |a: &Type, b: &Type| a.common(b).filter(Type::is_float).map(Box::new);
+    match (operator, left, right) {
+        (
+            BinaryOperator::Addition | BinaryOperator::Subtraction,
+            Type::Matrix(columns, rows, a),
+            Type::Matrix(other_columns, other_rows, b),
+        ) if columns == other_columns && rows == other_rows => {
+            Some(Type::Matrix(*columns, *rows, float(a, b)?))
+        }
+        (
+            BinaryOperator::Multiplication,
+            Type::Matrix(inner, rows, a),
+            Type::Matrix(columns, other_inner, b),
+        ) if inner == other_inner => Some(Type::Matrix(*columns, *rows, float(a, b)?)),
+        (BinaryOperator::Multiplication, Type::Matrix(columns, rows, a), Type::Vector(size, b))
+            if columns == size =>
+        {
+            Some(Type::Vector(*rows, float(a, b)?))
+        }
+        (BinaryOperator::Multiplication, Type::Vector(size, b), Type::Matrix(columns, rows, a))
+            if rows == size =>
+        {
+            Some(Type::Vector(*columns, float(a, b)?))
+        }
+        (BinaryOperator::Multiplication, Type::Matrix(columns, rows, a), scalar)
+        | (BinaryOperator::Multiplication, scalar, Type::Matrix(columns, rows, a))
+            if scalar.is_float() =>
+        {
+            Some(Type::Matrix(*columns, *rows, float(a, scalar)?))
+        }
+        _ => None,
+    }
+}
+
+/// The binary operator of a compound assignment.
+fn compound_operator(operator: &AssignmentOperator) -> Option<BinaryOperator> {
+    match operator {
+        AssignmentOperator::Equal => None,
+        AssignmentOperator::PlusEqual => Some(BinaryOperator::Addition),
+        AssignmentOperator::MinusEqual => Some(BinaryOperator::Subtraction),
+        AssignmentOperator::TimesEqual => Some(BinaryOperator::Multiplication),
+        AssignmentOperator::DivisionEqual => Some(BinaryOperator::Division),
+        AssignmentOperator::ModuloEqual => Some(BinaryOperator::Remainder),
+        AssignmentOperator::AndEqual => Some(BinaryOperator::BitwiseAnd),
+        AssignmentOperator::OrEqual => Some(BinaryOperator::BitwiseOr),
+        AssignmentOperator::XorEqual => Some(BinaryOperator::BitwiseXor),
+        AssignmentOperator::ShiftRightAssign => Some(BinaryOperator::ShiftRight),
+        AssignmentOperator::ShiftLeftAssign => Some(BinaryOperator::ShiftLeft),
+    }
+}
+
+fn literal_type(literal: &LiteralExpression) -> Type {
+    match literal {
+        LiteralExpression::True | LiteralExpression::False => Type::Bool,
+        LiteralExpression::AbstractInt(_) => Type::AbstractInt,
+        LiteralExpression::AbstractFloat(_) => Type::AbstractFloat,
+        LiteralExpression::I32(_) => Type::I32,
+        LiteralExpression::U32(_) => Type::U32,
+        LiteralExpression::F32(_) => Type::F32,
+        LiteralExpression::F16(_) => Type::F16,
+        LiteralExpression::String(_) => Type::Unknown,
+    }
+}
+
+/// The number of components selected by a swizzle of a vector of the size.
+fn swizzle_size(name: &str, size: u8) -> Option<u8> {
+    let size = size as usize;
+    let valid = ["xyzw", "rgba"]
+        .iter()
+        .any(|letters| name.chars().all(|c| letters[..size].contains(c)));
+    (valid && (1..=4).contains(&name.len())).then_some(name.len() as u8)
+}
+
+/// The number of columns and rows of a matrix type generator, like `mat4x3`.
+fn matrix_size(name: &str) -> Option<(u8, u8)> {
+    let (columns, rows) = name.strip_prefix("mat")?.split_once('x')?;
+    let columns = columns.parse().ok().filter(|size| (2..=4).contains(size))?;
+    let rows = rows.parse().ok().filter(|size| (2..=4).contains(size))?;
+    Some((columns, rows))
+}
+
+fn array_size(expression: &Expression) -> Option<u64> {
+    match expression {
+        Expression::Literal(literal) => match &literal.value {
+            LiteralExpression::AbstractInt(size) => size.parse().ok(),
+            LiteralExpression::I32(size) => u64::try_from(*size).ok(),
+            LiteralExpression::U32(size) => Some(*size as u64),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The name of an identifier without template arguments, like an address space.
+fn identifier_name(expression: &Expression) -> Option<&str> {
+    match expression {
+        Expression::Identifier(identifier) => match identifier.path.as_slice() {
+            [part] if part.template_args.is_none() => Some(part.name.as_str()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether the expression is the `_` of a phony assignment.
+fn is_phony(expression: &Expression) -> bool {
+    identifier_name(expression) == Some("_")
+}
@@ -0,0 +1,218 @@
+use std::{collections::HashMap, fmt};
+
+/// The type of a WGSL expression, as far as the type checker can tell.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Type {
+    Bool,
+    AbstractInt,
+    AbstractFloat,
+    I32,
+    U32,
+    F32,
+    F16,
+    /// A vector with its number of components and its component type.
+    Vector(u8, Box<Type>),
+    /// A matrix with its number of columns and rows, and its component type.
+    Matrix(u8, u8, Box<Type>),
+    /// An array with its element type, and its size when it is known.
+    Array(Box<Type>, Option<u64>),
+    Atomic(Box<Type>),
+    /// A user-defined struct, by its name in the output.
+    Struct(String),
+    /// A pointer with its address space and store type.
+    Pointer(String, Box<Type>),
+    /// A reference to memory with its address space and store type. Variables and their
+    /// components are references, which are loaded when their value is used.
+    Reference(String, Box<Type>),
+    /// Textures, samplers and the enumerants of their template arguments.
+    Named(String, Vec<Type>),
+    /// The type could not be inferred. It is compatible with every type, so that only the
+    /// errors the checker is sure of are reported.
+    Unknown,
+}
+
+impl Type {
+    pub(crate) fn is_unknown(&self) -> bool {
+        matches!(self, Type::Unknown)
+    }
+
+    /// Applies the load rule: the value of a reference is its store type.
+    pub(crate) fn load(self) -> Type {
+        match self {
+            Type::Reference(_, typ) => *typ,
+            typ => typ,
+        }
+    }
+
+    /// Whether the type is an integer scalar, or unknown.
+    pub(crate) fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            Type::AbstractInt | Type::I32 | Type::U32 | Type::Unknown
+        )
+    }
+
+    /// Whether the type is a floating point scalar, or unknown.
+    pub(crate) fn is_float(&self) -> bool {
+        matches!(
+            self,
+            Type::AbstractFloat | Type::F32 | Type::F16 | Type::Unknown
+        )
+    }
+
+    /// Whether the type is a numeric scalar, or unknown.
+    pub(crate) fn is_numeric(&self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+
+    /// Whether the type is a scalar, or unknown.
+    pub(crate) fn is_scalar(&self) -> bool {
+        self.is_numeric() || matches!(self, Type::Bool)
+    }
+
+    /// The number of components and the component type of a vector, or `None` and the type
+    /// itself for a scalar.
+    pub(crate) fn shape(&self) -> Option<(Option<u8>, &Type)> {
+        match self {
+            Type::Vector(size, component) => Some((Some(*size), component)),
+            typ if typ.is_scalar() => Some((None, typ)),
+            _ => None,
+        }
+    }
+
+    /// A vector of the component type with `size` components, or the component type itself.
+    pub(crate) fn with_shape(size: Option<u8>, component: Type) -> Type {
+        match size {
+            Some(size) => Type::Vector(size, Box::new(component)),
+            None => component,
+        }
+    }
+
+    /// The type that abstract numeric values of this type take when they are not converted.
+    pub(crate) fn concretize(&self) -> Type {
+        match self {
+            Type::AbstractInt => Type::I32,
+            Type::AbstractFloat => Type::F32,
+            Type::Vector(size, component) => Type::Vector(*size, Box::new(component.concretize())),
+            Type::Matrix(columns, rows, component) => {
+                Type::Matrix(*columns, *rows, Box::new(component.concretize()))
+            }
+            Type::Array(element, size) => Type::Array(Box::new(element.concretize()), *size),
+            typ => typ.clone(),
+        }
+    }
+
+    /// Whether a value of this type can be used where a value of type `to` is expected,
+    /// converting abstract numeric values if needed.
+    pub(crate) fn converts_to(&self, to: &Type) -> bool {
+        match (self, to) {
+            (Type::Unknown, _) | (_, Type::Unknown) => true,
+            (
+                Type::AbstractInt,
+                Type::AbstractFloat | Type::I32 | Type::U32 | Type::F32 | Type::F16,
+            ) => true,
+            (Type::AbstractFloat, Type::F32 | Type::F16) => true,
+            (Type::Vector(size, from), Type::Vector(to_size, to)) => {
+                size == to_size && from.converts_to(to)
+            }
+            (Type::Matrix(columns, rows, from), Type::Matrix(to_columns, to_rows, to)) => {
+                columns == to_columns && rows == to_rows && from.converts_to(to)
+            }
+            (Type::Array(from, size), Type::Array(to, to_size)) => {
+                sizes_match(*size, *to_size) && from.converts_to(to)
+            }
+            _ => self.matches(to),
+        }
+    }
+
+    /// Whether the types are the same, where unknown types match any type.
+    pub(crate) fn matches(&self, other: &Type) -> bool {
+        match (self, other) {
+            (Type::Unknown, _) | (_, Type::Unknown) => true,
+            (Type::Vector(size, a), Type::Vector(other_size, b)) => {
+                size == other_size && a.matches(b)
+            }
+            (Type::Matrix(columns, rows, a), Type::Matrix(other_columns, other_rows, b)) => {
+                columns == other_columns && rows == other_rows && a.matches(b)
+            }
+            (Type::Array(a, size), Type::Array(b, other_size)) => {
+                sizes_match(*size, *other_size) && a.matches(b)
+            }
+            (Type::Atomic(a), Type::Atomic(b)) => a.matches(b),
+            (Type::Pointer(space, a), Type::Pointer(other_space, b))
+            | (Type::Reference(space, a), Type::Reference(other_space, b)) => {
+                space == other_space && a.matches(b)
+            }
+            (Type::Named(name, args), Type::Named(other_name, other_args)) => {
+                name == other_name
+                    && args.len() == other_args.len()
+                    && args.iter().zip(other_args).all(|(a, b)| a.matches(b))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// The type both types convert to, preferring the known one.
+    pub(crate) fn common(&self, other: &Type) -> Option<Type> {
+        if self.is_unknown() {
+            Some(other.clone())
+        } else if other.is_unknown() || other.converts_to(self) {
+            Some(self.clone())
+        } else if self.converts_to(other) {
+            Some(other.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Displays the type as in WGSL, with structs named by their MEW paths.
+    pub(crate) fn display<'a>(&'a self, names: &'a HashMap<String, String>) -> DisplayType<'a> {
+        DisplayType { typ: self, names }
+    }
+}
+
+fn sizes_match(size: Option<u64>, other: Option<u64>) -> bool {
+    size.zip(other).is_none_or(|(size, other)| size == other)
+}
+
+pub(crate) struct DisplayType<'a> {
+    typ: &'a Type,
+    names: &'a HashMap<String, String>,
+}
+
+impl fmt::Display for DisplayType<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = self.names;
+        match self.typ {
+            Type::Bool => write!(f, "bool"),
+            Type::AbstractInt => write!(f, "AbstractInt"),
+            Type::AbstractFloat => write!(f, "AbstractFloat"),
+            Type::I32 => write!(f, "i32"),
+            Type::U32 => write!(f, "u32"),
+            Type::F32 => write!(f, "f32"),
+            Type::F16 => write!(f, "f16"),
+            Type::Vector(size, component) => write!(f, "vec{size}<{}>", component.display(names)),
+            Type::Matrix(columns, rows, component) => {
+                write!(f, "mat{columns}x{rows}<{}>", component.display(names))
+            }
+            Type::Array(element, Some(size)) => {
+                write!(f, "array<{}, {size}>", element.display(names))
+            }
+            Type::Array(element, None) => write!(f, "array<{}>", element.display(names)),
+            Type::Atomic(typ) => write!(f, "atomic<{}>", typ.display(names)),
+            Type::Struct(name) => write!(f, "{}", names.get(name).unwrap_or(name)),
+            Type::Pointer(space, typ) => write!(f, "ptr<{space}, {}>", typ.display(names)),
+            Type::Reference(space, typ) => write!(f, "ref<{space}, {}>", typ.display(names)),
+            Type::Named(name, args) if args.is_empty() => write!(f, "{name}"),
+            Type::Named(name, args) => write!(
+                f,
+                "{name}<{}>",
+                args.iter()
+                    .map(|arg| arg.display(names).to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Type::Unknown => write!(f, "_"),
+        }
+    }
+}
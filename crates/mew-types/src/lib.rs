@@ -1,4 +1,5 @@
 use mew_parse::{
+    diagnostic::Diagnostic,
     span::{Span, Spanned},
     syntax::{
         CompoundDirective, FormalTemplateParameter, GlobalDirective, ModuleDirective, PathPart,
@@ -11,12 +12,162 @@ pub mod mangling;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum CompilerPassError {
-    SymbolNotFound(Vec<PathPart>, Span),
+    SymbolNotFound(Vec<PathPart>, Span, Vec<Suggestion>),
     UnableToResolvePath(Vec<PathPart>),
     MissingRequiredTemplateArgument(Spanned<FormalTemplateParameter>, Span),
     InternalError(InternalCompilerError),
     MalformedTemplateArgument(Span),
-    ParseError(String, Span),
+    /// A declaration has the same name in the output as another declaration, or as a WGSL
+    /// keyword or builtin when the other span is `None`.
+    NameCollision(String, Span, Option<Span>),
+    /// The argument of the `@export` attribute at the span is not a single string.
+    MalformedExport(Span),
+    /// The condition of an `@if` or `@elif` is not made of features, `true`, `false`, `!`,
+    /// `&&`, `||` and parentheses.
+    MalformedCondition(Span),
+    /// An `@elif` or `@else` does not follow an `@if` or `@elif`, or an element has more than
+    /// one of them.
+    MisplacedCondition(String, Span),
+    /// The template argument at the span does not implement the interface that bounds its
+    /// parameter, because of the members listed. No members are listed when the argument is
+    /// not a module.
+    UnsatisfiedInterface(String, Span, String, Span, Vec<UnsatisfiedMember>),
+    /// The bound of a template parameter is not an interface.
+    NotAnInterface(Vec<PathPart>, Span),
+    /// No `override` declaration has the path, which a permutation gives a value.
+    OverrideNotFound(String),
+    /// A permutation gives the `override` declaration at the path a value its type cannot
+    /// hold: a value that is not finite, or for an integer type, not a whole number in its
+    /// range. The value and the type follow the path.
+    InvalidOverrideValue(String, String, String),
+    /// An expression at the span does not type check.
+    TypeError(TypeError, Span),
+    ParseError(Diagnostic),
+    /// The generated WGSL was rejected by the validator, reported at the MEW source.
+    ValidationError(Diagnostic),
+}
+
+/// A hint on how to fix a [`CompilerPassError::SymbolNotFound`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Suggestion {
+    /// A name in scope that is spelled similarly.
+    SimilarName(String),
+    /// A member with the same name in another module, which can be imported.
+    Import(Vec<String>),
+}
+
+impl Suggestion {
+    pub fn help(&self) -> String {
+        match self {
+            Suggestion::SimilarName(name) => format!("a similar name exists: `{name}`"),
+            Suggestion::Import(path) => format!(
+                "`{}` exists in module `{}`, import it with `import {};`",
+                path.last().map(String::as_str).unwrap_or_default(),
+                path[..path.len().saturating_sub(1)].join("::"),
+                path.join("::")
+            ),
+        }
+    }
+}
+
+/// A member required by an interface, which a template argument does not provide.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnsatisfiedMember {
+    /// The argument has no member with the name, required at the span.
+    Missing(String, Span),
+    /// The member of the argument at the second span does not match the requirement at the
+    /// first span, for the reason given.
+    Mismatched(String, Span, Span, String),
+}
+
+/// A type error, with types written as in WGSL and declarations named by their MEW paths.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeError {
+    /// A value of the second type is used where the first type is expected, because of the
+    /// type at the span if any.
+    Mismatched(String, String, Option<Span>),
+    /// The binary operator cannot be applied to operands of the types.
+    InvalidOperands(String, String, String),
+    /// The unary operator cannot be applied to an operand of the type.
+    InvalidOperand(String, String),
+    /// A value of the type has no member or swizzle with the name.
+    NoMember(String, String),
+    /// A value of the type cannot be indexed.
+    NotIndexable(String),
+    /// A value of the type is used as an index.
+    InvalidIndex(String),
+    /// No overload of the function or constructor accepts arguments of the types.
+    NoMatchingOverload(String, Vec<String>),
+    /// The function takes the first number of arguments, but is called with the second.
+    ArgumentCount(String, usize, usize),
+    /// The expression is assigned to, but it is not a reference to memory.
+    NotAssignable,
+    /// A return statement has no value, in a function returning the type.
+    MissingReturnValue(String),
+    /// A return statement has a value, in a function without a return type.
+    UnexpectedReturnValue,
+}
+
+impl TypeError {
+    fn diagnostic(&self, span: &Span) -> Diagnostic {
+        let diagnostic = match self {
+            TypeError::Mismatched(expected, found, cause) => {
+                let diagnostic = Diagnostic::error("mismatched types").with_primary_label(
+                    span.clone(),
+                    format!("expected `{expected}`, found `{found}`"),
+                );
+                match cause {
+                    Some(cause) => {
+                        diagnostic.with_secondary_label(cause.clone(), "expected due to this")
+                    }
+                    None => diagnostic,
+                }
+            }
+            TypeError::InvalidOperands(operator, left, right) => Diagnostic::error(format!(
+                "cannot apply `{operator}` to `{left}` and `{right}`"
+            ))
+            .with_primary_label(span.clone(), "invalid operands"),
+            TypeError::InvalidOperand(operator, operand) => {
+                Diagnostic::error(format!("cannot apply `{operator}` to `{operand}`"))
+                    .with_primary_label(span.clone(), "invalid operand")
+            }
+            TypeError::NoMember(typ, member) => {
+                Diagnostic::error(format!("no member `{member}` on type `{typ}`"))
+                    .with_primary_label(span.clone(), "unknown member")
+            }
+            TypeError::NotIndexable(typ) => {
+                Diagnostic::error(format!("cannot index into a value of type `{typ}`"))
+                    .with_primary_label(span.clone(), "cannot be indexed")
+            }
+            TypeError::InvalidIndex(typ) => {
+                Diagnostic::error(format!("cannot index with a value of type `{typ}`"))
+                    .with_primary_label(span.clone(), "expected `i32` or `u32`")
+            }
+            TypeError::NoMatchingOverload(function, arguments) => Diagnostic::error(format!(
+                "no overload of `{function}` takes arguments of types ({})",
+                arguments
+                    .iter()
+                    .map(|argument| format!("`{argument}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+            .with_primary_label(span.clone(), "no matching overload"),
+            TypeError::ArgumentCount(function, expected, found) => Diagnostic::error(format!(
+                "`{function}` takes {expected} argument{} but {found} {} supplied",
+                if *expected == 1 { "" } else { "s" },
+                if *found == 1 { "was" } else { "were" }
+            ))
+            .with_primary_label(span.clone(), "wrong number of arguments"),
+            TypeError::NotAssignable => Diagnostic::error("cannot assign to this expression")
+                .with_primary_label(span.clone(), "not a reference to memory")
+                .with_help("only variables and their components can be assigned to"),
+            TypeError::MissingReturnValue(typ) => Diagnostic::error("missing return value")
+                .with_primary_label(span.clone(), format!("expected a value of type `{typ}`")),
+            TypeError::UnexpectedReturnValue => Diagnostic::error("unexpected return value")
+                .with_primary_label(span.clone(), "the function has no return type"),
+        };
+        diagnostic.with_code("E0109")
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -27,11 +178,209 @@ pub enum InternalCompilerError {
     UnexpectedMember,
 }
 
+fn format_path(path: &[PathPart]) -> String {
+    path.iter()
+        .map(|part| part.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+impl CompilerPassError {
+    pub fn diagnostic(&self) -> Diagnostic {
+        match self {
+            CompilerPassError::SymbolNotFound(path, span, suggestions) => suggestions.iter().fold(
+                Diagnostic::error(format!("cannot find `{}` in this scope", format_path(path)))
+                    .with_code("E0100")
+                    .with_primary_label(span.clone(), "not found in this scope"),
+                |diagnostic, suggestion| diagnostic.with_help(suggestion.help()),
+            ),
+            CompilerPassError::UnableToResolvePath(path) => {
+                Diagnostic::error(format!("unable to resolve path `{}`", format_path(path)))
+                    .with_code("E0101")
+            }
+            // the parameter name may already be mangled, so the labels point at it instead
+            CompilerPassError::MissingRequiredTemplateArgument(param, span) => Diagnostic::error(
+                "missing required template argument",
+            )
+            .with_code("E0102")
+            .with_primary_label(span.clone(), "a template argument is missing here")
+            .with_secondary_label(param.span(), "this template parameter has no default value")
+            .with_help(
+                "pass a value for the parameter, or give it a default value where it is declared",
+            ),
+            CompilerPassError::InternalError(err) => err.diagnostic(),
+            CompilerPassError::MalformedTemplateArgument(span) => {
+                Diagnostic::error("malformed template argument")
+                    .with_code("E0103")
+                    .with_primary_label(span.clone(), "this template argument is malformed")
+            }
+            CompilerPassError::NameCollision(name, span, Some(other)) => Diagnostic::error(
+                format!("`{name}` is the name of more than one declaration in the output"),
+            )
+            .with_code("E0104")
+            .with_primary_label(span.clone(), format!("this declaration is named `{name}`"))
+            .with_secondary_label(other.clone(), "and so is this declaration")
+            .with_help("rename one of the declarations, or name it with `@export(\"name\")`"),
+            CompilerPassError::NameCollision(name, span, None) => {
+                Diagnostic::error(format!("`{name}` is reserved by WGSL"))
+                    .with_code("E0104")
+                    .with_primary_label(span.clone(), format!("this declaration is named `{name}`"))
+                    .with_help("rename the declaration or the modules that contain it")
+            }
+            CompilerPassError::MalformedExport(span) => Diagnostic::error("malformed `@export`")
+                .with_code("E0110")
+                .with_primary_label(span.clone(), "this attribute is malformed")
+                .with_help(
+                    "`@export` keeps the name of the declaration, and `@export(\"name\")` names it `name`",
+                ),
+            CompilerPassError::MalformedCondition(span) => Diagnostic::error("malformed condition")
+                .with_code("E0105")
+                .with_primary_label(span.clone(), "this is not a condition")
+                .with_help(
+                    "conditions are made of features, `true`, `false`, `!`, `&&`, `||` and parentheses",
+                ),
+            CompilerPassError::MisplacedCondition(name, span) => {
+                Diagnostic::error(format!("misplaced `@{name}`"))
+                    .with_code("E0106")
+                    .with_primary_label(span.clone(), format!("this `@{name}` is misplaced"))
+                    .with_help(
+                        "an element has at most one of `@if`, `@elif` and `@else`, and `@elif` and `@else` follow an element with `@if` or `@elif`",
+                    )
+            }
+            CompilerPassError::UnsatisfiedInterface(argument, span, interface, bound, members) => {
+                let diagnostic =
+                    Diagnostic::error(format!("`{argument}` does not implement `{interface}`"))
+                        .with_code("E0107")
+                        .with_primary_label(
+                            span.clone(),
+                            format!("this argument does not implement `{interface}`"),
+                        )
+                        .with_secondary_label(bound.clone(), "required by this bound");
+                if members.is_empty() {
+                    return diagnostic.with_note("only modules can implement interfaces");
+                }
+                members
+                    .iter()
+                    .fold(diagnostic, |diagnostic, member| match member {
+                        UnsatisfiedMember::Missing(name, requirement) => diagnostic
+                            .with_secondary_label(
+                                requirement.clone(),
+                                format!("`{name}` is missing from `{argument}`"),
+                            ),
+                        UnsatisfiedMember::Mismatched(name, requirement, found, reason) => {
+                            diagnostic
+                                .with_secondary_label(
+                                    requirement.clone(),
+                                    format!("`{name}` is required to match this"),
+                                )
+                                .with_secondary_label(found.clone(), reason.clone())
+                        }
+                    })
+            }
+            CompilerPassError::NotAnInterface(path, span) => {
+                Diagnostic::error(format!("`{}` is not an interface", format_path(path)))
+                    .with_code("E0108")
+                    .with_primary_label(span.clone(), "expected an interface")
+                    .with_help("template parameters can only be bounded by interfaces")
+            }
+            CompilerPassError::OverrideNotFound(path) => {
+                Diagnostic::error(format!("cannot find the `override` declaration `{path}`"))
+                    .with_code("E0111")
+                    .with_help("overrides are named by their paths, like `module::name`")
+            }
+            CompilerPassError::InvalidOverrideValue(path, value, typ) => Diagnostic::error(
+                format!("cannot set the `override` declaration `{path}` of type `{typ}` to `{value}`"),
+            )
+            .with_code("E0112")
+            .with_help("integer overrides take whole numbers in the range of their type"),
+            CompilerPassError::TypeError(err, span) => err.diagnostic(span),
+            CompilerPassError::ParseError(diagnostic)
+            | CompilerPassError::ValidationError(diagnostic) => diagnostic.clone(),
+        }
+    }
+}
+
+impl InternalCompilerError {
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error("internal compiler error")
+            .with_code("E0900")
+            .with_note("this is a bug in the compiler, please report it");
+        match self {
+            InternalCompilerError::UnexpectedGlobalDirective(_, span) => {
+                diagnostic.with_primary_label(span.clone(), "unexpected global directive")
+            }
+            InternalCompilerError::UnexpectedModuleDirective(_, span) => {
+                diagnostic.with_primary_label(span.clone(), "unexpected module directive")
+            }
+            InternalCompilerError::UnexpectedCompoundDirective(_, span) => {
+                diagnostic.with_primary_label(span.clone(), "unexpected compound directive")
+            }
+            InternalCompilerError::UnexpectedMember => {
+                diagnostic.with_note("encountered an unexpected module member")
+            }
+        }
+    }
+}
+
 pub type CompilerPassResult<T = ()> = std::result::Result<T, Box<CompilerPassError>>;
 
+/// Collects the errors a pass recovered from, and its warnings.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Diagnostics {
+    pub errors: Vec<CompilerPassError>,
+    pub warnings: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn error(&mut self, error: CompilerPassError) {
+        self.errors.push(error);
+    }
+
+    pub fn warning(&mut self, warning: Diagnostic) {
+        self.warnings.push(warning);
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// Records the error of `result`, if any.
+    pub fn report<T>(&mut self, result: CompilerPassResult<T>) -> Option<T> {
+        result.map_err(|err| self.error(*err)).ok()
+    }
+
+    /// Fails with the first error, if any.
+    pub fn first_error(&self) -> CompilerPassResult {
+        match self.errors.first() {
+            Some(err) => Err(err.clone().into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Every error and warning, errors first.
+    pub fn diagnostics(&self) -> impl Iterator<Item = Diagnostic> {
+        self.errors
+            .iter()
+            .map(|err| err.diagnostic())
+            .chain(self.warnings.iter().cloned())
+    }
+}
+
 pub trait CompilerPass {
     fn apply_mut(&mut self, translation_unit: &mut TranslationUnit) -> CompilerPassResult;
 
+    /// Applies the pass, reporting the errors it can recover from to `diagnostics`
+    /// instead of stopping at the first one. Errors it cannot recover from are returned.
+    ///
+    /// Passes that do not recover from errors return them all.
+    fn apply_mut_with_diagnostics(
+        &mut self,
+        translation_unit: &mut TranslationUnit,
+        _diagnostics: &mut Diagnostics,
+    ) -> CompilerPassResult {
+        self.apply_mut(translation_unit)
+    }
+
     fn apply(&mut self, translation_unit: &TranslationUnit) -> CompilerPassResult<TranslationUnit> {
         let mut clone = translation_unit.clone();
         self.apply_mut(&mut clone)?;
@@ -1,33 +1,77 @@
-use mew_parse::{
-    span::Spanned,
-    syntax::{Expression, PathPart},
-};
-
-fn mangle_expression(expr: &Expression) -> String {
-    let data: String = format!("{expr}").replace(' ', "").replace('\n', "");
-    let mut result = String::new();
-    let mut was_unicode = false;
-    for c in data.chars() {
-        if c.is_alphanumeric() {
-            if was_unicode {
-                result.push('_');
-                was_unicode = false;
+//! Mangling of MEW paths into WGSL identifiers.
+//!
+//! A path is mangled by writing it as text, like `My_Lib::Shader::main<f32, 4>`, and encoding
+//! the characters of the text that cannot appear in an identifier. Alphanumeric characters are
+//! kept, `::` becomes `_`, and every other character or sequence becomes `_` followed by a
+//! digit:
+//!
+//! | text       | code         |
+//! |------------|--------------|
+//! | `::`       | `_`          |
+//! | `_`        | `_0`         |
+//! | `<`        | `_1`         |
+//! | `>`        | `_2`         |
+//! | `, `       | `_3`         |
+//! | any other  | `_4<hex>_`   |
+//!
+//! so `My_Lib::Shader::main<f32, 4>` becomes `My_0Lib_Shader_main_1f32_34_2`, and paths
+//! without underscores or template arguments read as they are written, like
+//! `Lighting_Phong_shade`. The names in a path never start with a digit, so a `_` followed by a
+//! digit is always a code, and no code is a prefix of another: distinct paths get distinct
+//! identifiers, and [`demangle`] recovers the text exactly.
+//!
+//! The codes avoid `__`, which naga's writers collapse and GLSL reserves, unless a name starts
+//! with an underscore.
+//!
+//! This is the [`ReadableMangling`] strategy. The mangler can use any [`ManglingStrategy`],
+//! like [`HashedMangling`], which gives short names to deeply specialized generics, or
+//! [`PreserveEntryPoints`], which keeps the names of entry points for host code.
+
+use std::fmt::Write;
+
+use mew_parse::{span::Spanned, syntax::PathPart};
+
+/// Encodes text as the characters of a WGSL identifier.
+pub fn mangle_str(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let (code, len) = match c {
+            ':' if rest.starts_with("::") => ("_", 2),
+            ',' if rest.starts_with(", ") => ("_3", 2),
+            '_' => ("_0", 1),
+            '<' => ("_1", 1),
+            '>' => ("_2", 1),
+            c if c.is_alphanumeric() => {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+                continue;
             }
-            result.push(c);
-        } else {
-            was_unicode = true;
-            let mut buf = vec![0; c.len_utf8()];
-            let _ = c.encode_utf8(&mut buf);
-            result.push('_');
-            for item in buf {
-                let str = item.to_string();
-                result.push_str(&str);
+            c => {
+                let _ = write!(result, "_4{:x}_", c as u32);
+                rest = &rest[c.len_utf8()..];
+                continue;
             }
-        }
+        };
+        result.push_str(code);
+        rest = &rest[len..];
     }
     result
 }
 
+/// The text of a path part with its template arguments, e.g. `main<f32, 4>`. This is the
+/// name a declaration gets when it is specialized, which [`mangle_path`] later encodes.
+pub fn mangle_template_args(path_part: &PathPart) -> String {
+    let args = path_part
+        .template_args
+        .iter()
+        .flatten()
+        .map(|arg| arg.expression.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{}<{args}>", path_part.name.value)
+}
+
 pub fn maybe_mangle_template_args_if_needed(path_part: &PathPart) -> String {
     if path_part.template_args.is_none() || path_part.template_args.as_ref().unwrap().is_empty() {
         return path_part.name.value.clone();
@@ -35,68 +79,177 @@ pub fn maybe_mangle_template_args_if_needed(path_part: &PathPart) -> String {
     mangle_template_args(path_part)
 }
 
-pub fn mangle_template_args(path_part: &PathPart) -> String {
-    let name: &String = &path_part.name.replace('_', "__");
-    let mut template_args = String::new();
-    for template_arg in path_part.template_args.iter().flatten() {
-        template_args.push('_');
-        template_args.push_str(&mangle_expression(&template_arg.expression).replace('_', "__"));
-    }
-    format!("{name}{template_args}")
+/// The text of a path, with the template arguments of its parts.
+pub fn path_text<'a>(path: impl IntoIterator<Item = &'a PathPart>) -> String {
+    path.into_iter()
+        .map(maybe_mangle_template_args_if_needed)
+        .collect::<Vec<_>>()
+        .join("::")
 }
 
+/// The name of the member a module gets for an inline template argument, which is
+/// named after the path of the argument.
 pub fn mangle_inline_arg_name(
     enclosing_path: &[PathPart],
     parent_path: &[PathPart],
     template_arg_name: &str,
 ) -> String {
-    let template_arg_name = template_arg_name.replace('_', "__");
-    let mut enclosing_path_result = String::new();
-    for i in enclosing_path.iter() {
-        enclosing_path_result.push_str(&maybe_mangle_template_args_if_needed(i).replace('_', "__"));
-        enclosing_path_result.push('_');
-    }
-    let mut parent_path_result = String::new();
-    for i in parent_path.iter() {
-        parent_path_result.push_str(&maybe_mangle_template_args_if_needed(i).replace('_', "__"));
-        parent_path_result.push('_');
+    let mut text = path_text(enclosing_path.iter().chain(parent_path.iter()));
+    if !text.is_empty() {
+        text.push_str("::");
     }
-
-    format!("{enclosing_path_result}{parent_path_result}{template_arg_name}")
+    text.push_str(template_arg_name);
+    mangle_str(&text)
 }
 
+/// Replaces the path by a single part, named by mangling the path.
 pub fn mangle_path(path: &mut Vec<PathPart>) {
-    let mut result = Vec::new();
-    let first = path.first();
-    let last = path.last();
+    mangle_path_with(path, &ReadableMangling);
+}
+
+/// Replaces the path by a single part, named by mangling the path with a strategy.
+pub fn mangle_path_with(path: &mut Vec<PathPart>, strategy: &dyn ManglingStrategy) {
     let mut mangled_span = 0..0;
-    if let (Some(first), Some(last)) = (first, last) {
-        let first_name_span = first.name.span();
-        let last_name_span = last.name.span();
-        let mut end = last_name_span.end;
-        let start = first_name_span.start;
+    if let (Some(first), Some(last)) = (path.first(), path.last()) {
+        let start = first.name.span().start;
+        let mut end = last.name.span().end;
         if let Some(last) = last.template_args.as_ref().and_then(|x| x.last()) {
             end = last.span().end;
         }
         mangled_span = start..end;
     };
-    for p in path.iter_mut() {
-        let mut current = String::new();
-        current.push_str(p.name.replace('_', "__").as_str());
-        if let Some(args) = p.template_args.as_mut() {
-            for arg in args.iter_mut() {
-                current.push_str("__");
-                mangle_expression(&arg.expression);
-                current.push_str(format!("{}", arg.expression).as_str());
-            }
-        }
-        result.push(current);
-    }
-    let joined = result.join("_");
+    let mangled = strategy.mangle(&path_text(path.iter()));
     path.clear();
     path.push(PathPart {
-        name: Spanned::new(joined, mangled_span),
+        name: Spanned::new(mangled, mangled_span),
         template_args: None,
         inline_template_args: None,
     });
 }
+
+/// Turns a mangled WGSL identifier back into the text of the MEW path it was mangled from,
+/// e.g. `My_0Lib_Shader_main_1f32_34_2` into `My_Lib::Shader::main<f32, 4>`.
+///
+/// Returns `None` if the identifier is not a well-formed mangled name.
+pub fn demangle(mangled: &str) -> Option<String> {
+    let mut result = String::with_capacity(mangled.len());
+    let mut chars = mangled.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '_' {
+            result.push(c);
+            continue;
+        }
+        match chars.next_if(char::is_ascii_digit) {
+            None if chars.peek().is_some() => result.push_str("::"),
+            Some('0') => result.push('_'),
+            Some('1') => result.push('<'),
+            Some('2') => result.push('>'),
+            Some('3') => result.push_str(", "),
+            Some('4') => {
+                let hex = chars.by_ref().take_while(|c| *c != '_').collect::<String>();
+                result.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            }
+            _ => return None,
+        }
+    }
+    Some(result)
+}
+
+/// How the mangler names declarations in the generated WGSL.
+///
+/// Paths are given as text with their template arguments, like `Lighting::phong<f32>`, and
+/// distinct paths must get distinct names. Only the members of modules are named by the
+/// strategy: root declarations, local variables and parameters are encoded with [`mangle_str`].
+pub trait ManglingStrategy: std::fmt::Debug + Send + Sync {
+    /// The identifier of the declaration at a path.
+    fn mangle(&self, path: &str) -> String;
+
+    /// The identifier of the entry point at a path, which is named `name` in its module, if it
+    /// is not mangled like the other declarations.
+    fn entry_point_name(&self, path: &str, name: &str) -> Option<String> {
+        let _ = (path, name);
+        None
+    }
+
+    /// The path of an identifier made by [`ManglingStrategy::mangle`], if it can be recovered
+    /// from the identifier alone. Names that cannot are found in the table of the mangled names
+    /// of a compilation.
+    fn demangle(&self, mangled: &str) -> Option<String>;
+}
+
+/// The readable scheme described in the [module documentation](self), e.g. `Lighting_phong`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReadableMangling;
+
+impl ManglingStrategy for ReadableMangling {
+    fn mangle(&self, path: &str) -> String {
+        mangle_str(path)
+    }
+
+    fn demangle(&self, mangled: &str) -> Option<String> {
+        demangle(mangled)
+    }
+}
+
+/// Short names made of `_m` and the hex digits of a hash of the path, e.g. `_m3f9a2`.
+///
+/// The hash does not depend on the compiler version or on the other declarations, so a path
+/// keeps its name across compilations. Names of local variables never start with `_m`, as
+/// [`mangle_str`] encodes a leading underscore as `_0`.
+#[derive(Debug, Clone, Copy)]
+pub struct HashedMangling {
+    /// How many hex digits of the hash are kept, at most 16. Paths whose names collide,
+    /// including the specializations of one template, are reported by the mangler as name
+    /// collisions, and need more digits.
+    pub digits: usize,
+}
+
+impl Default for HashedMangling {
+    fn default() -> Self {
+        Self { digits: 6 }
+    }
+}
+
+/// The 64-bit FNV-1a hash, which unlike the hashers of `std` is stable across releases.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+impl ManglingStrategy for HashedMangling {
+    fn mangle(&self, path: &str) -> String {
+        let digits = self.digits.clamp(1, 16);
+        let hash = format!("{:016x}", fnv1a(path));
+        format!("_m{}", &hash[..digits])
+    }
+
+    fn demangle(&self, _mangled: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Keeps the names entry points have in their modules, so `@vertex fn main` stays `main` for
+/// host code, and names the other declarations with the wrapped strategy. Specialized entry
+/// points, like `main<f32>`, are named by encoding their names with [`mangle_str`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PreserveEntryPoints<S>(pub S);
+
+impl<S: ManglingStrategy> ManglingStrategy for PreserveEntryPoints<S> {
+    fn mangle(&self, path: &str) -> String {
+        self.0.mangle(path)
+    }
+
+    fn entry_point_name(&self, _path: &str, name: &str) -> Option<String> {
+        // specialized entry points are named with their template arguments, like `main<f32>`
+        if name.contains('<') {
+            Some(mangle_str(name))
+        } else {
+            Some(name.to_string())
+        }
+    }
+
+    fn demangle(&self, mangled: &str) -> Option<String> {
+        self.0.demangle(mangled)
+    }
+}